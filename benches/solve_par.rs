@@ -0,0 +1,43 @@
+//! Compares sequential and parallel solving of a few hundred locales.
+//!
+//! Run with `cargo bench --features parallel`.
+use poly_l10n::{ARulebook, LanguageIdentifier, LocaleFallbackSolver};
+use std::time::Instant;
+
+const LANGUAGES: [&str; 30] = [
+    "ar", "bn", "de", "en", "es", "fa", "fr", "gsw", "ha", "hi", "id", "it", "ja", "kk", "ko",
+    "ms", "nl", "pa", "pl", "pt", "ru", "sr", "sw", "ta", "th", "tr", "uk", "ur", "vi", "zh",
+];
+const REGIONS: [&str; 10] = ["AE", "AT", "BR", "CA", "CN", "DE", "GB", "IN", "MX", "US"];
+const ROUNDS: u32 = 5;
+
+fn main() {
+    let solver = LocaleFallbackSolver {
+        rulebook: ARulebook::default(),
+    };
+    let locales: Vec<LanguageIdentifier> = (LANGUAGES.iter())
+        .flat_map(|lang| REGIONS.iter().map(move |region| format!("{lang}-{region}")))
+        .map(|l| l.parse().expect("cannot parse locale"))
+        .collect();
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        let chains: Vec<_> = locales.iter().map(|l| solver.solve_locale(l)).collect();
+        std::hint::black_box(chains);
+    }
+    let sequential = start.elapsed().checked_div(ROUNDS).unwrap_or_default();
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        std::hint::black_box(solver.solve_locales_par(&locales));
+    }
+    let parallel = start.elapsed().checked_div(ROUNDS).unwrap_or_default();
+
+    println!("{} locales", locales.len());
+    println!("sequential: {sequential:?}");
+    println!("parallel:   {parallel:?}");
+    println!(
+        "speedup:    {:.2}x",
+        sequential.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
@@ -0,0 +1,221 @@
+//! Resolution of localized asset paths, see [`resolve_asset`] and [`pick_localized_dir`].
+use crate::LanguageIdentifier;
+use unic_langid::subtags;
+
+/// A way to spell a locale in a path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LocaleSpelling {
+    /// The BCP-47 tag, `zh-Hant-TW`.
+    Tag,
+    /// The lowercase BCP-47 tag, `zh-hant-tw`.
+    LowercaseTag,
+    /// The POSIX `language_REGION@modifier` form, `zh_TW` or `sr_RS@latin`.
+    ///
+    /// The script is left out if it is the likely script of the region (`zh-Hant-TW` is `zh_TW`),
+    /// and is otherwise spelt as the modifier `@latin`, `@cyrillic`, `@devanagari` or `@arabic`
+    /// like in freedesktop files. Locales with other scripts have no such spelling, since
+    /// `zh-Hant` is not `zh`, nor is `zh-Hans-TW` `zh_TW`.
+    Posix,
+    /// The BCP-47 tag with underscores, `zh_Hant_TW`.
+    Underscored,
+    /// The POSIX form with the UTF-8 codeset, as in `LANG`, `zh_TW.UTF-8` or
+    /// `sr_RS.UTF-8@latin`. Like [`Self::Posix`], locales with some scripts have no such
+    /// spelling.
+    PosixUtf8,
+}
+
+impl LocaleSpelling {
+    /// Spell `locale`, or [`None`] if it cannot be spelt this way.
+    ///
+    /// # Examples
+    /// ```
+    /// use poly_l10n::{langid, LocaleSpelling};
+    /// let locale = langid!("zh-Hant-TW");
+    /// assert_eq!(LocaleSpelling::LowercaseTag.spell(&locale).as_deref(), Some("zh-hant-tw"));
+    /// assert_eq!(LocaleSpelling::Posix.spell(&locale).as_deref(), Some("zh_TW"));
+    /// assert_eq!(LocaleSpelling::Posix.spell(&langid!("zh-Hant")), None);
+    /// assert_eq!(LocaleSpelling::Posix.spell(&langid!("zh-Hans-TW")), None);
+    /// let serbian = |tag| LocaleSpelling::Posix.spell(&tag);
+    /// assert_eq!(serbian(langid!("sr-Latn-RS")).as_deref(), Some("sr_RS@latin"));
+    /// assert_eq!(serbian(langid!("sr-Cyrl-RS")).as_deref(), Some("sr_RS"));
+    /// assert_eq!(
+    ///     LocaleSpelling::PosixUtf8.spell(&langid!("sr-Latn-RS")).as_deref(),
+    ///     Some("sr_RS.UTF-8@latin")
+    /// );
+    /// ```
+    #[must_use]
+    pub fn spell(self, locale: &LanguageIdentifier) -> Option<String> {
+        match self {
+            Self::Tag => Some(locale.to_string()),
+            Self::LowercaseTag => Some(locale.to_string().to_ascii_lowercase()),
+            Self::Posix => posix(locale, ""),
+            Self::Underscored => Some(locale.to_string().replace('-', "_")),
+            Self::PosixUtf8 => posix(locale, ".UTF-8"),
+        }
+    }
+}
+
+/// The POSIX spelling of `locale` with the given codeset suffix, see [`LocaleSpelling::Posix`].
+fn posix(locale: &LanguageIdentifier, codeset: &str) -> Option<String> {
+    let modifier = match locale.script {
+        Some(script)
+            if locale
+                .region
+                .is_none_or(|region| likely_script(locale.language, region) != Some(script)) =>
+        {
+            Some(crate::freedesktop::script_modifier(script.as_str())?)
+        }
+        _ => None,
+    };
+    let region = locale.region.map(|r| format!("_{r}")).unwrap_or_default();
+    let modifier = modifier.map(|m| format!("@{m}")).unwrap_or_default();
+    Some(format!("{}{region}{codeset}{modifier}", locale.language))
+}
+
+/// The script the language is usually written in in `region`, for the languages whose script
+/// depends on the region. With the feature `likely_subtags`, all languages are known.
+fn likely_script(language: subtags::Language, region: subtags::Region) -> Option<subtags::Script> {
+    #[cfg(feature = "likely_subtags")]
+    {
+        let locale = LanguageIdentifier::from_parts(language, None, Some(region), &[]);
+        if let Some(script) = crate::canonicalize::maximize(&locale).script {
+            return Some(script);
+        }
+    }
+    let script = match (language.as_str(), region.as_str()) {
+        ("zh", "CN" | "SG" | "MY") => "Hans",
+        ("zh", "TW" | "HK" | "MO") => "Hant",
+        ("sr", "ME") => "Latn",
+        ("sr", _) => "Cyrl",
+        _ => return None,
+    };
+    script.parse().ok()
+}
+
+/// Write `locale` back out in a specific spelling, e.g. after solving, or [`None`] if it cannot
+/// be spelt this way.
+///
+/// | style                  | spelling                         | example       |
+/// |------------------------|----------------------------------|---------------|
+/// | POSIX (`LANG`)         | [`LocaleSpelling::PosixUtf8`]    | `zh_TW.UTF-8` |
+/// | BCP-47                 | [`LocaleSpelling::Tag`]          | `zh-Hant-TW`  |
+/// | glibc locale directory | [`LocaleSpelling::Posix`]        | `zh_TW`       |
+/// | URL                    | [`LocaleSpelling::LowercaseTag`] | `zh-hant-tw`  |
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{format_as, langid, LocaleSpelling};
+/// let locale = langid!("zh-Hant-TW");
+/// assert_eq!(format_as(&locale, LocaleSpelling::PosixUtf8).as_deref(), Some("zh_TW.UTF-8"));
+/// assert_eq!(format_as(&locale, LocaleSpelling::Tag).as_deref(), Some("zh-Hant-TW"));
+/// assert_eq!(format_as(&langid!("de"), LocaleSpelling::PosixUtf8).as_deref(), Some("de.UTF-8"));
+/// assert_eq!(format_as(&langid!("zh-Hant"), LocaleSpelling::PosixUtf8), None);
+/// ```
+#[must_use]
+pub fn format_as(locale: &LanguageIdentifier, style: LocaleSpelling) -> Option<String> {
+    style.spell(locale)
+}
+
+/// The spellings of `locale` in the order of `spellings`, without duplicates.
+pub fn spell_all(locale: &LanguageIdentifier, spellings: &[LocaleSpelling]) -> Vec<String> {
+    let mut out: Vec<String> = vec![];
+    for spelling in spellings.iter().filter_map(|s| s.spell(locale)) {
+        if !out.contains(&spelling) {
+            out.push(spelling);
+        }
+    }
+    out
+}
+
+/// The spellings substituted into asset paths by default.
+const DEFAULT_SPELLINGS: [LocaleSpelling; 2] = [LocaleSpelling::Tag, LocaleSpelling::Posix];
+
+/// Find the first existing localized variant of an asset.
+///
+/// `{locale}` in `template` is replaced by each locale in `chain` in turn, spelt both as a BCP-47
+/// tag (`zh-Hant-TW`) and in the POSIX form (`zh_TW`), and the first path for which `exists`
+/// returns `true` is returned. This works with any kind of asset (documentation, sounds, fonts,
+/// …) and storage: pass e.g. `|p| std::path::Path::new(p).exists()` for files.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, resolve_asset};
+/// let chain = [langid!("zh-Hant-TW"), langid!("zh-Hant"), langid!("en")];
+/// let files = ["help/zh_TW/intro.md", "help/en/intro.md"];
+/// assert_eq!(
+///     resolve_asset("help/{locale}/intro.md", &chain, |p| files.contains(&p)),
+///     Some("help/zh_TW/intro.md".to_owned())
+/// );
+/// // `zh-Hant` is never spelt `zh`
+/// assert_eq!(resolve_asset("{locale}.txt", &chain[1..2], |p| p == "zh.txt"), None);
+/// // `sr_RS` is Cyrillic
+/// let files = ["help/sr_RS/intro.md", "help/sr_RS@latin/intro.md"];
+/// assert_eq!(
+///     resolve_asset("help/{locale}/intro.md", &[langid!("sr-Latn-RS")], |p| files.contains(&p)),
+///     Some("help/sr_RS@latin/intro.md".to_owned())
+/// );
+/// assert_eq!(resolve_asset("sounds/{locale}.ogg", &chain, |_| false), None);
+/// ```
+pub fn resolve_asset<F: FnMut(&str) -> bool>(
+    template: &str,
+    chain: &[LanguageIdentifier],
+    mut exists: F,
+) -> Option<String> {
+    (chain.iter())
+        .flat_map(|locale| spell_all(locale, &DEFAULT_SPELLINGS))
+        .map(|spelling| template.replace("{locale}", &spelling))
+        .find(|path| exists(path))
+}
+
+/// Find the best localized subdirectory of `base`, e.g. of a documentation tree with
+/// `zh_TW/`, `zh-Hant/` and `zh/` directories.
+///
+/// Each locale in `chain` is tried in turn, spelt as a BCP-47 tag (`zh-Hant-TW`) and in the POSIX
+/// form (`zh_TW`); see [`pick_localized_dir_with`] to configure the spellings.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, pick_localized_dir};
+/// let base = std::env::temp_dir().join("poly_l10n_pick_dir_doctest");
+/// std::fs::create_dir_all(base.join("zh-Hant")).unwrap();
+/// std::fs::create_dir_all(base.join("zh")).unwrap();
+/// let chain = [langid!("zh-TW"), langid!("zh-Hant"), langid!("zh")];
+/// assert_eq!(pick_localized_dir(&base, &chain), Some(base.join("zh-Hant")));
+/// # std::fs::remove_dir_all(&base).unwrap();
+/// ```
+#[must_use]
+pub fn pick_localized_dir<P: AsRef<std::path::Path>>(
+    base: P,
+    chain: &[LanguageIdentifier],
+) -> Option<std::path::PathBuf> {
+    pick_localized_dir_with(base, chain, &DEFAULT_SPELLINGS)
+}
+
+/// Like [`pick_localized_dir`], trying the `spellings` of each locale in order.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, pick_localized_dir_with, LocaleSpelling};
+/// let base = std::env::temp_dir().join("poly_l10n_pick_dir_with_doctest");
+/// std::fs::create_dir_all(base.join("zh-hant")).unwrap();
+/// let chain = [langid!("zh-TW"), langid!("zh-Hant")];
+/// assert_eq!(pick_localized_dir_with(&base, &chain, &[LocaleSpelling::Tag]), None);
+/// assert_eq!(
+///     pick_localized_dir_with(&base, &chain, &[LocaleSpelling::LowercaseTag]),
+///     Some(base.join("zh-hant"))
+/// );
+/// # std::fs::remove_dir_all(&base).unwrap();
+/// ```
+#[must_use]
+pub fn pick_localized_dir_with<P: AsRef<std::path::Path>>(
+    base: P,
+    chain: &[LanguageIdentifier],
+    spellings: &[LocaleSpelling],
+) -> Option<std::path::PathBuf> {
+    let base = base.as_ref();
+    (chain.iter())
+        .flat_map(|locale| spell_all(locale, spellings))
+        .map(|spelling| base.join(spelling))
+        .find(|dir| dir.is_dir())
+}
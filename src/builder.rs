@@ -0,0 +1,168 @@
+//! Fluent construction of rulebooks, see [`RulebookBuilder`].
+use crate::{
+    AFnRule, ARulebook, BorrowingRulebook, FnRule, LanguageIdentifier, Rulebook, default_rulebook,
+};
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// A rule of a builder, with the default rules resolved when building.
+#[derive(Clone)]
+enum Part<R> {
+    Default,
+    Rule(R),
+}
+
+/// The default rules, see [`RulebookBuilder::without_per_lang_rules`].
+fn default_rules(per_lang_rules: bool) -> fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> {
+    if per_lang_rules {
+        default_rulebook::default_rulebook
+    } else {
+        default_rulebook::default_rulebook_without_per_lang
+    }
+}
+
+macro_rules! impl_builder {
+    ($builder:ident, $rulebook:ident, $rule:ident, $ptr:ident $(+ $bound:ident)*) => {
+        impl $builder {
+            /// Append the default rules, the same as those of the default rulebook.
+            pub fn with_default_rules(mut self) -> Self {
+                self.parts.push(Part::Default);
+                self
+            }
+
+            /// Leave out [`crate::per_lang_default_rules`] from the default rules.
+            pub const fn without_per_lang_rules(mut self) -> Self {
+                self.per_lang_rules = false;
+                self
+            }
+
+            /// Append the rule `f`.
+            pub fn with_fn<F>(self, f: F) -> Self
+            where
+                F: Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> $(+ $bound)* + 'static,
+            {
+                self.with_rule($ptr::new(f))
+            }
+
+            /// Append `rule`, e.g. one taken from the rules of another rulebook.
+            pub fn with_rule(mut self, rule: $rule) -> Self {
+                self.parts.push(Part::Rule(rule));
+                self
+            }
+
+            /// Append a rule looking up `map`, like [`Rulebook::from_lookup`].
+            pub fn with_map<M: BorrowingRulebook $(+ $bound)* + 'static>(self, map: M) -> Self {
+                self.with_fn(move |l| {
+                    BorrowingRulebook::find_fallback_locale_ref(&map, l)
+                        .cloned()
+                        .collect()
+                })
+            }
+
+            /// Append the rules of `rulebook`.
+            pub fn with_rulebook<B>(mut self, rulebook: $rulebook<B>) -> Self {
+                self.parts.extend(rulebook.rules.into_iter().map(Part::Rule));
+                self
+            }
+
+            /// Build the rulebook, with its rules in the order they were added.
+            pub fn build(self) -> $rulebook {
+                let per_lang_rules = self.per_lang_rules;
+                let default = move || -> $rule {
+                    let rules = default_rules(per_lang_rules);
+                    #[cfg(feature = "user_rules")]
+                    if let Some(overlay) = crate::rules_file::load_overlay() {
+                        return $ptr::new(crate::rules_file::with_overlay(overlay, rules));
+                    }
+                    $ptr::new(rules)
+                };
+                let default = (self.parts.iter())
+                    .any(|part| matches!(part, Part::Default))
+                    .then(default);
+                let rules = (self.parts.into_iter())
+                    .filter_map(|part| match part {
+                        Part::Rule(rule) => Some(rule),
+                        Part::Default => default.as_ref().map($ptr::clone),
+                    })
+                    .collect();
+                $rulebook::from_fns(rules)
+            }
+        }
+
+        impl Default for $builder {
+            fn default() -> Self {
+                Self {
+                    parts: vec![],
+                    per_lang_rules: true,
+                }
+            }
+        }
+
+        impl std::fmt::Debug for $builder {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!($builder))
+                    .field("len", &self.parts.len())
+                    .field("per_lang_rules", &self.per_lang_rules)
+                    .finish_non_exhaustive()
+            }
+        }
+    };
+}
+
+/// Builder for composed [`Rulebook`]s, created with [`Rulebook::builder`].
+///
+/// Rules give fallbacks in the order they were added, like [`Rulebook::push_fn`].
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, LocaleFallbackSolver, Rulebook};
+/// let map = std::collections::HashMap::from([(langid!("arb"), vec![langid!("ar-AE")])]);
+/// let rulebook = Rulebook::builder()
+///     .with_map(map)
+///     .with_default_rules()
+///     .with_fn(|_| vec![langid!("en")])
+///     .without_per_lang_rules()
+///     .build();
+/// assert_eq!(rulebook.len(), 3);
+/// let solver = LocaleFallbackSolver { rulebook };
+/// assert_eq!(solver.solve_locale(langid!("arb")), langid!["ar-AE", "arb", "ar", "en"]);
+/// ```
+#[must_use]
+#[derive(Clone)]
+pub struct RulebookBuilder {
+    parts: Vec<Part<FnRule>>,
+    per_lang_rules: bool,
+}
+
+/// Builder for composed [`ARulebook`]s, created with [`ARulebook::builder`].
+///
+/// See [`RulebookBuilder`].
+#[must_use]
+#[derive(Clone)]
+pub struct ARulebookBuilder {
+    parts: Vec<Part<AFnRule>>,
+    per_lang_rules: bool,
+}
+
+impl_builder!(RulebookBuilder, Rulebook, FnRule, Rc);
+impl_builder!(ARulebookBuilder, ARulebook, AFnRule, Arc + Send + Sync);
+
+#[cfg(test)]
+mod test {
+    #[test]
+    #[cfg(feature = "per_lang_default_rules")]
+    fn without_per_lang_rules() {
+        use crate::{LocaleFallbackSolver, Rulebook, langid};
+        let with = LocaleFallbackSolver {
+            rulebook: Rulebook::builder().with_default_rules().build(),
+        };
+        let without = LocaleFallbackSolver {
+            rulebook: (Rulebook::builder().with_default_rules())
+                .without_per_lang_rules()
+                .build(),
+        };
+        let kk = langid!("kk");
+        assert!(with.solve_locale(&kk).contains(&langid!("kk-Cyrl")));
+        assert!(!without.solve_locale(&kk).contains(&langid!("kk-Cyrl")));
+    }
+}
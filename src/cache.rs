@@ -0,0 +1,170 @@
+//! Memoization of solved fallbacks, see [`CachedSolver`].
+use crate::{
+    FallbackChain, LanguageIdentifier, LocaleFallbackSolver, LocaleSolver, PolyL10nRulebook,
+};
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+/// A [`LocaleFallbackSolver`] remembering the fallbacks of the most recently solved locales.
+///
+/// Created with [`LocaleFallbackSolver::with_cache`]. Up to `capacity` chains are kept; when full,
+/// the least recently used one is evicted. Call [`Self::invalidate`] after changing the rulebook,
+/// e.g. through [`Self::rulebook_mut`] or interior mutability.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, LocaleFallbackSolver, Rulebook};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// static CALLS: AtomicUsize = AtomicUsize::new(0);
+/// let solver = LocaleFallbackSolver {
+///     rulebook: Rulebook::from_fn(|l| {
+///         CALLS.fetch_add(1, Ordering::Relaxed);
+///         match l.to_string().as_str() {
+///             "de-AT" => vec![langid!("de")],
+///             _ => vec![],
+///         }
+///     }),
+/// }
+/// .with_cache(16);
+/// assert_eq!(solver.solve_locale(langid!("de-AT")), [langid!("de")]);
+/// assert_eq!(solver.solve_locale(langid!("de-AT")), [langid!("de")]);
+/// assert_eq!(CALLS.load(Ordering::Relaxed), 2);
+/// solver.invalidate();
+/// assert_eq!(solver.solve_locale(langid!("de-AT")), [langid!("de")]);
+/// assert_eq!(CALLS.load(Ordering::Relaxed), 4);
+/// ```
+pub struct CachedSolver<R: for<'a> PolyL10nRulebook<'a>> {
+    solver: LocaleFallbackSolver<R>,
+    capacity: usize,
+    cache: Mutex<Lru>,
+}
+
+/// The cached chains with the tick of their last use.
+#[derive(Default)]
+struct Lru {
+    chains: HashMap<LanguageIdentifier, (FallbackChain, u64)>,
+    tick: u64,
+}
+
+impl<R: for<'a> PolyL10nRulebook<'a> + std::fmt::Debug> std::fmt::Debug for CachedSolver<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachedSolver")
+            .field("solver", &self.solver)
+            .field("capacity", &self.capacity)
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R: for<'a> PolyL10nRulebook<'a>> LocaleFallbackSolver<R> {
+    /// Remember the fallbacks of up to `capacity` recently solved locales, so that solving them
+    /// again is cheap. See [`CachedSolver`].
+    pub fn with_cache(self, capacity: usize) -> CachedSolver<R> {
+        CachedSolver {
+            solver: self,
+            capacity,
+            cache: Mutex::default(),
+        }
+    }
+}
+
+impl<R: for<'a> PolyL10nRulebook<'a>> CachedSolver<R> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Lru> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Same as [`LocaleFallbackSolver::solve_locale`], but cached.
+    pub fn solve_locale<L: AsRef<LanguageIdentifier>>(&self, locale: L) -> FallbackChain {
+        let locale = locale.as_ref();
+        {
+            let mut lru = self.lock();
+            lru.tick = lru.tick.saturating_add(1);
+            let tick = lru.tick;
+            if let Some((chain, used)) = lru.chains.get_mut(locale) {
+                *used = tick;
+                return chain.clone();
+            }
+        }
+        // solve without holding the lock, as the rulebook may be slow
+        let chain = self.solver.solve_locale(locale);
+        if self.capacity == 0 {
+            return chain;
+        }
+        let mut lru = self.lock();
+        if lru.chains.len() >= self.capacity && !lru.chains.contains_key(locale) {
+            let oldest = (lru.chains.iter())
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(l, _)| l.clone());
+            if let Some(oldest) = oldest {
+                lru.chains.remove(&oldest);
+            }
+        }
+        let tick = lru.tick;
+        lru.chains.insert(locale.clone(), (chain.clone(), tick));
+        chain
+    }
+
+    /// Forget all cached chains, e.g. after the rulebook changed.
+    pub fn invalidate(&self) {
+        self.lock().chains.clear();
+    }
+
+    /// Number of cached chains.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().chains.len()
+    }
+
+    /// Whether no chains are cached.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The underlying solver.
+    pub const fn solver(&self) -> &LocaleFallbackSolver<R> {
+        &self.solver
+    }
+
+    /// The rulebook of the solver, for changing it. This invalidates the cache.
+    pub fn rulebook_mut(&mut self) -> &mut R {
+        self.invalidate();
+        &mut self.solver.rulebook
+    }
+
+    /// The underlying solver, discarding the cache.
+    pub fn into_inner(self) -> LocaleFallbackSolver<R> {
+        self.solver
+    }
+}
+
+impl<R: for<'a> PolyL10nRulebook<'a>> LocaleSolver for CachedSolver<R> {
+    fn solve_locale(&self, locale: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+        Self::solve_locale(self, locale).0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{FnRulebook, langid};
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let rulebook = FnRulebook(|l: &LanguageIdentifier| {
+            l.region
+                .map(|_| LanguageIdentifier::from_parts(l.language, None, None, &[]))
+        });
+        let solver = LocaleFallbackSolver { rulebook }.with_cache(2);
+        for l in langid!["de-AT", "fr-CA", "de-AT", "en-GB"] {
+            solver.solve_locale(l);
+        }
+        let cached = solver.lock().chains.keys().cloned().collect::<Vec<_>>();
+        assert_eq!(cached.len(), 2);
+        assert!(cached.contains(&langid!("de-AT")));
+        assert!(!cached.contains(&langid!("fr-CA")));
+        let solver = LocaleFallbackSolver { rulebook }.with_cache(0);
+        assert_eq!(solver.solve_locale(langid!("de-AT")), [langid!("de")]);
+        assert!(solver.is_empty());
+    }
+}
@@ -0,0 +1,383 @@
+//! Canonicalization of legacy language tags.
+//!
+//! [`unic_langid`] only understands the modern `language-script-region-variants` form of BCP-47
+//! tags. Older content and some APIs still emit legacy forms, which are mapped to their modern
+//! equivalents here before parsing. This is done by [`crate::langid!`] automatically.
+use std::borrow::Cow;
+use unic_langid::LanguageIdentifier;
+
+/// Extended language subtags and their prefixes (i.e. macrolanguages), as registered in the IANA
+/// Language Subtag Registry. Sorted by the extlang subtag.
+const EXTLANGS: &[(&str, &str)] = &[
+    ("aao", "ar"),
+    ("abh", "ar"),
+    ("abv", "ar"),
+    ("acm", "ar"),
+    ("acq", "ar"),
+    ("acw", "ar"),
+    ("acx", "ar"),
+    ("acy", "ar"),
+    ("adf", "ar"),
+    ("aeb", "ar"),
+    ("aec", "ar"),
+    ("afb", "ar"),
+    ("ajp", "ar"),
+    ("apc", "ar"),
+    ("apd", "ar"),
+    ("arb", "ar"),
+    ("arq", "ar"),
+    ("ars", "ar"),
+    ("ary", "ar"),
+    ("arz", "ar"),
+    ("auz", "ar"),
+    ("avl", "ar"),
+    ("ayh", "ar"),
+    ("ayl", "ar"),
+    ("ayn", "ar"),
+    ("ayp", "ar"),
+    ("bbz", "ar"),
+    ("bjn", "ms"),
+    ("btj", "ms"),
+    ("bve", "ms"),
+    ("bvu", "ms"),
+    ("cdo", "zh"),
+    ("cjy", "zh"),
+    ("cmn", "zh"),
+    ("cnp", "zh"),
+    ("coa", "ms"),
+    ("cpx", "zh"),
+    ("csp", "zh"),
+    ("czh", "zh"),
+    ("czo", "zh"),
+    ("dup", "ms"),
+    ("ekk", "et"),
+    ("gan", "zh"),
+    ("gom", "kok"),
+    ("hak", "zh"),
+    ("hji", "ms"),
+    ("hsn", "zh"),
+    ("jak", "ms"),
+    ("jax", "ms"),
+    ("knn", "kok"),
+    ("kvb", "ms"),
+    ("kvr", "ms"),
+    ("kxd", "ms"),
+    ("lce", "ms"),
+    ("lcf", "ms"),
+    ("liw", "ms"),
+    ("ltg", "lv"),
+    ("lvs", "lv"),
+    ("lzh", "zh"),
+    ("max", "ms"),
+    ("meo", "ms"),
+    ("mfa", "ms"),
+    ("mfb", "ms"),
+    ("min", "ms"),
+    ("mnp", "zh"),
+    ("mqg", "ms"),
+    ("msi", "ms"),
+    ("mui", "ms"),
+    ("nan", "zh"),
+    ("orn", "ms"),
+    ("ors", "ms"),
+    ("pel", "ms"),
+    ("pga", "ar"),
+    ("pse", "ms"),
+    ("shu", "ar"),
+    ("ssh", "ar"),
+    ("swc", "sw"),
+    ("swh", "sw"),
+    ("tmw", "ms"),
+    ("urk", "ms"),
+    ("uzn", "uz"),
+    ("uzs", "uz"),
+    ("vkk", "ms"),
+    ("vkt", "ms"),
+    ("vro", "et"),
+    ("wuu", "zh"),
+    ("xmm", "ms"),
+    ("yue", "zh"),
+    ("zlm", "ms"),
+    ("zmi", "ms"),
+    ("zsm", "ms"),
+];
+
+/// Grandfathered tags (both irregular and regular ones) from RFC 5646 and their modern
+/// equivalents.
+///
+/// Tags without a preferred value in the IANA registry are mapped to the closest modern tag, or
+/// `und` if there are none.
+const GRANDFATHERED: &[(&str, &str)] = &[
+    ("art-lojban", "jbo"),
+    ("cel-gaulish", "xtg"),
+    ("en-gb-oed", "en-GB-oxendict"),
+    ("i-ami", "ami"),
+    ("i-bnn", "bnn"),
+    ("i-default", "und"),
+    ("i-enochian", "und"),
+    ("i-hak", "hak"),
+    ("i-klingon", "tlh"),
+    ("i-lux", "lb"),
+    ("i-mingo", "und"),
+    ("i-navajo", "nv"),
+    ("i-pwn", "pwn"),
+    ("i-tao", "tao"),
+    ("i-tay", "tay"),
+    ("i-tsu", "tsu"),
+    ("no-bok", "nb"),
+    ("no-nyn", "nn"),
+    ("sgn-be-fr", "sfb"),
+    ("sgn-be-nl", "vgt"),
+    ("sgn-ch-de", "sgg"),
+    ("zh-guoyu", "cmn"),
+    ("zh-hakka", "hak"),
+    ("zh-min", "zh"),
+    ("zh-min-nan", "nan"),
+    ("zh-xiang", "hsn"),
+];
+
+/// Sign languages with a legacy region-based tag (`sgn-US`) registered as redundant in the IANA
+/// Language Subtag Registry, and the region of the legacy tag. Sorted by the region.
+const LEGACY_SIGN_LANGUAGES: &[(&str, &str)] = &[
+    ("BR", "bzs"),
+    ("CO", "csn"),
+    ("DE", "gsg"),
+    ("DK", "dsl"),
+    ("ES", "ssp"),
+    ("FR", "fsl"),
+    ("GB", "bfi"),
+    ("GR", "gss"),
+    ("IE", "isg"),
+    ("IT", "ise"),
+    ("JP", "jsl"),
+    ("MX", "mfs"),
+    ("NI", "ncs"),
+    ("NL", "dse"),
+    ("NO", "nsl"),
+    ("PT", "psr"),
+    ("SE", "swl"),
+    ("US", "ase"),
+    ("ZA", "sfs"),
+];
+
+/// Sign languages with a grandfathered tag (`sgn-BE-FR`) and their regions.
+const GRANDFATHERED_SIGN_LANGUAGES: &[(&str, &str)] =
+    &[("BE", "sfb"), ("BE", "vgt"), ("CH", "sgg")];
+
+/// The region of a national sign language, e.g. `US` for `ase` (American Sign Language).
+///
+/// Only sign languages with legacy `sgn-…` tags are known.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::canonicalize::sign_language_region;
+/// assert_eq!(sign_language_region("ase"), Some("US"));
+/// assert_eq!(sign_language_region("sgg"), Some("CH"));
+/// assert_eq!(sign_language_region("eng"), None);
+/// ```
+#[must_use]
+pub fn sign_language_region(language: &str) -> Option<&'static str> {
+    (LEGACY_SIGN_LANGUAGES.iter())
+        .chain(GRANDFATHERED_SIGN_LANGUAGES)
+        .find(|(_, lang)| lang.eq_ignore_ascii_case(language))
+        .map(|(region, _)| *region)
+}
+
+/// The sign language of a legacy region-based tag such as `sgn-US`.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::canonicalize::legacy_sign_language;
+/// assert_eq!(legacy_sign_language("sgn-US"), Some("ase"));
+/// assert_eq!(legacy_sign_language("SGN_gb"), Some("bfi"));
+/// assert_eq!(legacy_sign_language("sgn-AQ"), None);
+/// assert_eq!(legacy_sign_language("en-US"), None);
+/// ```
+#[must_use]
+pub fn legacy_sign_language(tag: &str) -> Option<&'static str> {
+    let mut subtags = tag.split(['-', '_']);
+    let (Some(sgn), Some(region), None) = (subtags.next(), subtags.next(), subtags.next()) else {
+        return None;
+    };
+    if !sgn.eq_ignore_ascii_case("sgn") {
+        return None;
+    }
+    let region = region.to_ascii_uppercase();
+    let i = LEGACY_SIGN_LANGUAGES
+        .binary_search_by(|(r, _)| r.cmp(&region.as_str()))
+        .ok()?;
+    LEGACY_SIGN_LANGUAGES.get(i).map(|(_, lang)| *lang)
+}
+
+/// Deprecated primary language subtags and their replacements, as registered in the IANA
+/// Language Subtag Registry.
+const DEPRECATED_LANGUAGES: &[(&str, &str)] = &[
+    ("in", "id"),
+    ("iw", "he"),
+    ("ji", "yi"),
+    ("jw", "jv"),
+    ("mo", "ro"),
+];
+
+/// Replace a deprecated language subtag (e.g. `iw` for Hebrew) with its modern equivalent.
+///
+/// Returns whether the language subtag has been replaced. This is not done by
+/// [`crate::langid!`] automatically, since content is sometimes still stored under the
+/// deprecated codes.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{canonicalize::replace_deprecated_language, langid};
+/// let mut l = langid!("iw-IL");
+/// assert!(replace_deprecated_language(&mut l));
+/// assert_eq!(l, langid!("he-IL"));
+/// ```
+pub fn replace_deprecated_language(langid: &mut LanguageIdentifier) -> bool {
+    let Some(new) = (DEPRECATED_LANGUAGES.iter())
+        .find(|(old, _)| langid.language == *old)
+        .and_then(|(_, new)| new.parse().ok())
+    else {
+        return false;
+    };
+    langid.language = new;
+    true
+}
+
+/// Add the likely script and region to `langid` (CLDR "add likely subtags").
+///
+/// For example, `zh-TW` becomes `zh-Hant-TW`. Locales unknown to the likely subtags data are
+/// returned unchanged.
+///
+/// Only available with the feature `likely_subtags`.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{canonicalize::maximize, langid};
+/// assert_eq!(maximize(&langid!("zh-TW")), langid!("zh-Hant-TW"));
+/// assert_eq!(maximize(&langid!("en")), langid!("en-Latn-US"));
+/// ```
+#[cfg(feature = "likely_subtags")]
+#[must_use]
+pub fn maximize(langid: &LanguageIdentifier) -> LanguageIdentifier {
+    let mut langid = langid.clone();
+    langid.maximize();
+    langid
+}
+
+/// Remove the likely script and region from `langid` (CLDR "remove likely subtags").
+///
+/// For example, `zh-Hans-CN` becomes `zh` and `zh-Hant-TW` becomes `zh-TW`. This is the inverse
+/// of [`maximize`], so that minimal tags can be stored while solving with the full ones. Locales
+/// unknown to the likely subtags data are returned unchanged.
+///
+/// Only available with the feature `likely_subtags`.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{canonicalize::minimize, langid};
+/// assert_eq!(minimize(&langid!("zh-Hans-CN")), langid!("zh"));
+/// assert_eq!(minimize(&langid!("zh-Hant-TW")), langid!("zh-TW"));
+/// assert_eq!(minimize(&langid!("en-Latn-US")), langid!("en"));
+/// assert_eq!(minimize(&langid!("sr-Latn-RS")), langid!("sr-Latn"));
+/// ```
+#[cfg(feature = "likely_subtags")]
+#[must_use]
+pub fn minimize(langid: &LanguageIdentifier) -> LanguageIdentifier {
+    let mut langid = langid.clone();
+    langid.minimize();
+    langid
+}
+
+/// Find the modern equivalent of the given grandfathered tag.
+///
+/// # Examples
+/// ```
+/// assert_eq!(poly_l10n::canonicalize::grandfathered("i-klingon"), Some("tlh"));
+/// assert_eq!(poly_l10n::canonicalize::grandfathered("zh_min_nan"), Some("nan"));
+/// assert_eq!(poly_l10n::canonicalize::grandfathered("zh-TW"), None);
+/// ```
+#[must_use]
+pub fn grandfathered(tag: &str) -> Option<&'static str> {
+    let eq = |(old, new): &(u8, u8)| old == new || (*old == b'-' && *new == b'_');
+    (GRANDFATHERED.iter())
+        .find(|(old, _)| {
+            old.len() == tag.len()
+                && (old.bytes())
+                    .zip(tag.bytes().map(|b| b.to_ascii_lowercase()))
+                    .all(|bs| eq(&bs))
+        })
+        .map(|(_, new)| *new)
+}
+
+/// Find the prefix (macrolanguage) of the given extended language subtag.
+///
+/// # Examples
+/// ```
+/// assert_eq!(poly_l10n::canonicalize::extlang_prefix("yue"), Some("zh"));
+/// assert_eq!(poly_l10n::canonicalize::extlang_prefix("afb"), Some("ar"));
+/// assert_eq!(poly_l10n::canonicalize::extlang_prefix("eng"), None);
+/// ```
+#[must_use]
+pub fn extlang_prefix(extlang: &str) -> Option<&'static str> {
+    let i = EXTLANGS
+        .binary_search_by(|(ext, _)| ext.cmp(&extlang))
+        .ok()?;
+    EXTLANGS.get(i).map(|(_, prefix)| *prefix)
+}
+
+/// Canonicalize legacy forms in a BCP-47 language tag.
+///
+/// The following legacy forms are handled:
+/// - grandfathered tags are mapped to their modern equivalents (see [`grandfathered`]), e.g.
+///   `i-klingon` becomes `tlh` and `no-nyn` becomes `nn`;
+/// - legacy sign language tags are mapped to the sign language (see [`legacy_sign_language`]),
+///   e.g. `sgn-US` becomes `ase`;
+/// - extended language subtags are replaced by their primary language equivalents, e.g. `zh-yue`
+///   becomes `yue` and `zh-cmn-Hans` becomes `cmn-Hans`.
+///
+/// Tags without legacy forms are returned as is.
+///
+/// Both `-` and `_` are accepted as separators, and matching is case-insensitive.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::canonicalize::canonicalize_tag;
+/// assert_eq!(canonicalize_tag("zh-yue"), "yue");
+/// assert_eq!(canonicalize_tag("zh-cmn-Hans"), "cmn-Hans");
+/// assert_eq!(canonicalize_tag("AR_afb"), "afb");
+/// assert_eq!(canonicalize_tag("i-klingon"), "tlh");
+/// assert_eq!(canonicalize_tag("zh-min-nan"), "nan");
+/// assert_eq!(canonicalize_tag("zh-Hant-TW"), "zh-Hant-TW");
+/// ```
+#[must_use]
+pub fn canonicalize_tag(tag: &str) -> Cow<'_, str> {
+    if let Some(new) = grandfathered(tag).or_else(|| legacy_sign_language(tag)) {
+        return Cow::Borrowed(new);
+    }
+    let mut subtags = tag.split(['-', '_']);
+    let (Some(lang), Some(ext)) = (subtags.next(), subtags.next()) else {
+        return Cow::Borrowed(tag);
+    };
+    if ext.len() != 3 || !ext.bytes().all(|b| b.is_ascii_alphabetic()) {
+        return Cow::Borrowed(tag);
+    }
+    let ext = ext.to_ascii_lowercase();
+    match extlang_prefix(&ext) {
+        Some(prefix) if prefix.eq_ignore_ascii_case(lang) => {
+            // strip `lang` and its separator
+            Cow::Borrowed(tag.get(lang.len().saturating_add(1)..).unwrap_or(tag))
+        }
+        _ => Cow::Borrowed(tag),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn extlangs_sorted() {
+        assert!(EXTLANGS.is_sorted_by_key(|(ext, _)| ext));
+        assert!(LEGACY_SIGN_LANGUAGES.is_sorted_by_key(|(region, _)| region));
+    }
+}
@@ -0,0 +1,300 @@
+//! Translation catalogs in common file formats, looked up along fallback chains.
+//!
+//! This module is gated behind the feature `catalog`.
+//!
+//! A [`Catalog`] maps message keys to translated text for one locale. A [`LayeredCatalog`] holds
+//! the catalogs of the locales in a fallback chain, so keys missing from a locale are resolved in
+//! the locales after it.
+use crate::LocaleSpelling;
+use std::collections::HashMap;
+use unic_langid::LanguageIdentifier;
+
+pub mod apple;
+pub mod arb;
+pub mod i18next;
+pub mod xliff;
+
+/// Error when parsing a translation file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<crate::json::Error> for ParseError {
+    fn from(err: crate::json::Error) -> Self {
+        Self {
+            message: err.to_string(),
+        }
+    }
+}
+
+/// A [CLDR plural category](https://cldr.unicode.org/index/cldr-spec/plural-rules).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    /// All categories, in CLDR order.
+    pub const ALL: [Self; 6] = [
+        Self::Zero,
+        Self::One,
+        Self::Two,
+        Self::Few,
+        Self::Many,
+        Self::Other,
+    ];
+
+    /// The CLDR keyword of the category, e.g. `few`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Zero => "zero",
+            Self::One => "one",
+            Self::Two => "two",
+            Self::Few => "few",
+            Self::Many => "many",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl std::str::FromStr for PluralCategory {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        (Self::ALL.into_iter())
+            .find(|c| c.as_str() == s)
+            .ok_or_else(|| ParseError {
+                message: format!("unknown plural category `{s}`"),
+            })
+    }
+}
+
+impl std::fmt::Display for PluralCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Translated messages of one locale, by key.
+///
+/// Plural messages have a form per [`PluralCategory`] and are stored separately from the other
+/// messages, see [`Self::get_plural`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Catalog {
+    /// The locale declared by the translation file, if the format has such a declaration.
+    pub locale: Option<LanguageIdentifier>,
+    messages: HashMap<String, String>,
+    plurals: HashMap<String, HashMap<PluralCategory, String>>,
+}
+
+impl Catalog {
+    /// Translation of `key`.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(String::as_str)
+    }
+
+    /// Add or replace the translation of `key`.
+    pub fn insert<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) {
+        self.messages.insert(key.into(), value.into());
+    }
+
+    /// Form of the plural message `key` for `category`, or its [`PluralCategory::Other`] form if
+    /// the message has no form for `category`.
+    #[must_use]
+    pub fn get_plural(&self, key: &str, category: PluralCategory) -> Option<&str> {
+        let forms = self.plurals.get(key)?;
+        (forms.get(&category))
+            .or_else(|| forms.get(&PluralCategory::Other))
+            .map(String::as_str)
+    }
+
+    /// Add or replace the form of the plural message `key` for `category`.
+    pub fn insert_plural<K: Into<String>, V: Into<String>>(
+        &mut self,
+        key: K,
+        category: PluralCategory,
+        value: V,
+    ) {
+        (self.plurals.entry(key.into()).or_default()).insert(category, value.into());
+    }
+
+    /// Iterate over the keys and translations of the messages that are not plural, in arbitrary
+    /// order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        (self.messages.iter()).map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Number of translated messages, including plural messages.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.messages.len().saturating_add(self.plurals.len())
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty() && self.plurals.is_empty()
+    }
+
+    /// Add the messages of `other`, replacing existing ones with the same key.
+    pub fn extend(&mut self, other: Self) {
+        self.messages.extend(other.messages);
+        self.plurals.extend(other.plurals);
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for Catalog {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        Self {
+            messages: (iter.into_iter())
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Catalogs of the locales in a fallback chain, most preferred first. Each key is looked up in
+/// the catalogs in order, so later locales fill in keys missing from earlier ones.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{catalog::{Catalog, LayeredCatalog}, langid};
+/// let catalogs = LayeredCatalog::new([
+///     (langid!("pt-BR"), Catalog::from_iter([("save", "Salvar")])),
+///     (langid!("pt"), Catalog::from_iter([("save", "Guardar"), ("open", "Abrir")])),
+/// ]);
+/// assert_eq!(catalogs.get("save"), Some("Salvar"));
+/// assert_eq!(catalogs.get_with_locale("open"), Some((&langid!("pt"), "Abrir")));
+/// assert_eq!(catalogs.get("close"), None);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LayeredCatalog {
+    pub layers: Vec<(LanguageIdentifier, Catalog)>,
+}
+
+impl LayeredCatalog {
+    /// Layer `layers`, most preferred first.
+    pub fn new<I: IntoIterator<Item = (LanguageIdentifier, Catalog)>>(layers: I) -> Self {
+        Self {
+            layers: layers.into_iter().collect(),
+        }
+    }
+
+    /// Translation of `key` from the first catalog containing it.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.get_with_locale(key).map(|(_, value)| value)
+    }
+
+    /// Translation of `key` from the first catalog containing it, with the locale of that catalog.
+    #[must_use]
+    pub fn get_with_locale(&self, key: &str) -> Option<(&LanguageIdentifier, &str)> {
+        (self.layers.iter()).find_map(|(locale, catalog)| Some((locale, catalog.get(key)?)))
+    }
+
+    /// Form of the plural message `key` for `category` from the first catalog containing the
+    /// message. All forms of a plural message come from the same catalog, since locales may have
+    /// different plural categories.
+    #[must_use]
+    pub fn get_plural(&self, key: &str, category: PluralCategory) -> Option<&str> {
+        (self.layers.iter())
+            .find(|(_, catalog)| catalog.plurals.contains_key(key))
+            .and_then(|(_, catalog)| catalog.get_plural(key, category))
+    }
+}
+
+/// Parse the files in `dir` with one of the extensions `exts` and layer those of the locales in
+/// `chain`, merging files of the same locale.
+///
+/// The locale of each file is the one declared in it ([`Catalog::locale`]), or else the one
+/// `locale_of_stem` finds in its name.
+fn load_dir(
+    dir: &std::path::Path,
+    chain: &[LanguageIdentifier],
+    exts: &[&str],
+    parse: fn(&str) -> Result<Catalog, ParseError>,
+    locale_of_stem: fn(&str) -> Option<LanguageIdentifier>,
+) -> std::io::Result<LayeredCatalog> {
+    let mut layers: Vec<Option<Catalog>> = vec![None; chain.len()];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let ext = path.extension().and_then(|ext| ext.to_str());
+        if !ext.is_some_and(|ext| exts.contains(&ext)) {
+            continue;
+        }
+        let Some(source) = read_first([path.clone()]) else {
+            continue;
+        };
+        let mut catalog = match parse(&source) {
+            Ok(catalog) => catalog,
+            #[allow(unused_variables)]
+            Err(err) => {
+                #[cfg(feature = "tracing")]
+                tracing::error!(?path, %err, "cannot parse translation file");
+                continue;
+            }
+        };
+        let locale =
+            (catalog.locale.take()).or_else(|| locale_of_stem(path.file_stem()?.to_str()?));
+        let Some(i) = locale.and_then(|l| chain.iter().position(|locale| *locale == l)) else {
+            continue;
+        };
+        if let Some(layer) = layers.get_mut(i) {
+            layer.get_or_insert_default().extend(catalog);
+        }
+    }
+    Ok(LayeredCatalog::new(chain.iter().zip(layers).filter_map(
+        |(locale, layer)| {
+            let mut layer = layer?;
+            layer.locale = Some(locale.clone());
+            Some((locale.clone(), layer))
+        },
+    )))
+}
+
+/// The spellings of `locale` tried in file names: [`LocaleSpelling::Tag`] (`zh-Hant-TW`) and
+/// [`LocaleSpelling::Underscored`] (`zh_Hant_TW`).
+fn file_stems(locale: &LanguageIdentifier) -> Vec<String> {
+    crate::assets::spell_all(locale, &[LocaleSpelling::Tag, LocaleSpelling::Underscored])
+}
+
+/// Decode the content of a text file in UTF-8, or UTF-16 with a byte order mark.
+fn decode(bytes: &[u8]) -> Option<String> {
+    let utf16 = |be: bool, bytes: &[u8]| {
+        let units = bytes.chunks(2).map(|pair| match *pair {
+            [a, b] if be => u16::from_be_bytes([a, b]),
+            [a, b] => u16::from_le_bytes([a, b]),
+            _ => 0xfffd,
+        });
+        char::decode_utf16(units)
+            .collect::<Result<String, _>>()
+            .ok()
+    };
+    match bytes {
+        [0xff, 0xfe, rest @ ..] => utf16(false, rest),
+        [0xfe, 0xff, rest @ ..] => utf16(true, rest),
+        [0xef, 0xbb, 0xbf, rest @ ..] => String::from_utf8(rest.to_vec()).ok(),
+        _ => String::from_utf8(bytes.to_vec()).ok(),
+    }
+}
+
+/// Read the first existing file among `paths`, returning [`None`] if none can be read.
+fn read_first<I: IntoIterator<Item = std::path::PathBuf>>(paths: I) -> Option<String> {
+    (paths.into_iter()).find_map(|path| decode(&std::fs::read(path).ok()?))
+}
@@ -0,0 +1,332 @@
+//! Apple `.strings` and `.stringsdict` files, as found in `.lproj` bundle directories.
+use super::{Catalog, LayeredCatalog, ParseError, PluralCategory, read_first};
+use crate::xml::Element;
+use unic_langid::LanguageIdentifier;
+
+struct StringsParser<'a> {
+    source: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> StringsParser<'a> {
+    fn err<T>(&self, message: &str) -> Result<T, ParseError> {
+        let offset = self.source.len().saturating_sub(self.rest.len());
+        let line = (self.source.get(..offset).unwrap_or_default())
+            .matches('\n')
+            .count()
+            .saturating_add(1);
+        Err(ParseError {
+            message: format!("line {line}: {message}"),
+        })
+    }
+
+    /// Skip whitespace and comments.
+    fn skip(&mut self) -> Result<(), ParseError> {
+        loop {
+            self.rest = self.rest.trim_start();
+            if let Some(rest) = self.rest.strip_prefix("/*") {
+                let Some((_, rest)) = rest.split_once("*/") else {
+                    return self.err("unterminated comment");
+                };
+                self.rest = rest;
+            } else if let Some(rest) = self.rest.strip_prefix("//") {
+                self.rest = rest.split_once('\n').map_or("", |(_, rest)| rest);
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.rest.strip_prefix(c).is_some_and(|rest| {
+            self.rest = rest;
+            true
+        })
+    }
+
+    fn hex4(&self, chars: &mut std::str::Chars<'a>) -> Result<char, ParseError> {
+        let hex = chars.as_str().get(..4).unwrap_or_default();
+        let c = u32::from_str_radix(hex, 16).ok().and_then(char::from_u32);
+        let Some(c) = c else {
+            return self.err("invalid unicode escape");
+        };
+        *chars = chars.as_str().get(4..).unwrap_or_default().chars();
+        Ok(c)
+    }
+
+    /// A quoted or unquoted string.
+    fn string(&mut self) -> Result<String, ParseError> {
+        if !self.eat('"') {
+            let end = (self.rest)
+                .find(|c: char| !(c.is_alphanumeric() || "_.$:/-".contains(c)))
+                .unwrap_or(self.rest.len());
+            let (s, rest) = self.rest.split_at(end);
+            if s.is_empty() {
+                return self.err("expected a string");
+            }
+            self.rest = rest;
+            return Ok(s.to_owned());
+        }
+        let mut out = String::new();
+        let mut chars = self.rest.chars();
+        loop {
+            match chars.next() {
+                None => return self.err("unterminated string"),
+                Some('"') => break,
+                Some('\\') => match chars.next() {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('r') => out.push('\r'),
+                    Some('0') => out.push('\0'),
+                    Some('U' | 'u') => out.push(self.hex4(&mut chars)?),
+                    Some(c @ ('"' | '\\' | '\'')) => out.push(c),
+                    _ => return self.err("invalid escape sequence"),
+                },
+                Some(c) => out.push(c),
+            }
+        }
+        self.rest = chars.as_str();
+        Ok(out)
+    }
+}
+
+/// Parse the content of a `.strings` file.
+///
+/// Entries are `"key" = "value";`, or `"key";` for a value equal to the key. Keys and values may
+/// be unquoted if they consist of alphanumerics and `_.$:/-` only.
+///
+/// # Errors
+/// Returns [`ParseError`] if the file is malformed.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::catalog::apple;
+/// let catalog = apple::parse_strings(r#"
+/// /* Title of the save button */
+/// "save" = "Enregistrer";
+/// "quote" = "\"Bonjour\"\n\U00e0 tous"; // trailing comment
+/// OK;
+/// "#).unwrap();
+/// assert_eq!(catalog.get("save"), Some("Enregistrer"));
+/// assert_eq!(catalog.get("quote"), Some("\"Bonjour\"\nà tous"));
+/// assert_eq!(catalog.get("OK"), Some("OK"));
+/// ```
+pub fn parse_strings(source: &str) -> Result<Catalog, ParseError> {
+    let source = source.strip_prefix('\u{feff}').unwrap_or(source);
+    let mut parser = StringsParser {
+        source,
+        rest: source,
+    };
+    let mut catalog = Catalog::default();
+    loop {
+        parser.skip()?;
+        if parser.rest.is_empty() {
+            return Ok(catalog);
+        }
+        let key = parser.string()?;
+        parser.skip()?;
+        let value = if parser.eat('=') {
+            parser.skip()?;
+            let value = parser.string()?;
+            parser.skip()?;
+            value
+        } else {
+            key.clone()
+        };
+        if !parser.eat(';') {
+            return parser.err("expected `;`");
+        }
+        catalog.insert(key, value);
+    }
+}
+
+/// The key-value pairs of a plist `<dict>`.
+fn dict(element: &Element) -> Vec<(String, &Element)> {
+    let mut entries = vec![];
+    let mut elements = element.elements();
+    while let Some(key) = elements.next() {
+        if key.local_name() != "key" {
+            continue;
+        }
+        let Some(value) = elements.next() else { break };
+        entries.push((key.text(), value));
+    }
+    entries
+}
+
+fn dict_string(entries: &[(String, &Element)], key: &str) -> Option<String> {
+    (entries.iter())
+        .find(|(k, v)| k == key && v.local_name() == "string")
+        .map(|(_, v)| v.text())
+}
+
+/// Parse the content of a `.stringsdict` file into plural messages.
+///
+/// For each entry, the plural variable referenced as `%#@name@` in `NSStringLocalizedFormatKey`
+/// is replaced by its form for each plural category. Entries referencing several plural variables
+/// cannot be expressed with a single category and are skipped.
+///
+/// # Errors
+/// Returns [`ParseError`] if the file is not a valid property list.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::catalog::{apple, PluralCategory};
+/// let catalog = apple::parse_stringsdict(r#"<?xml version="1.0" encoding="UTF-8"?>
+/// <plist version="1.0"><dict>
+///   <key>%d files</key>
+///   <dict>
+///     <key>NSStringLocalizedFormatKey</key><string>Found %#@files@</string>
+///     <key>files</key>
+///     <dict>
+///       <key>NSStringFormatSpecTypeKey</key><string>NSStringPluralRuleType</string>
+///       <key>NSStringFormatValueTypeKey</key><string>d</string>
+///       <key>one</key><string>%d file</string>
+///       <key>other</key><string>%d files</string>
+///     </dict>
+///   </dict>
+/// </dict></plist>"#).unwrap();
+/// assert_eq!(catalog.get_plural("%d files", PluralCategory::One), Some("Found %d file"));
+/// assert_eq!(catalog.get_plural("%d files", PluralCategory::Few), Some("Found %d files"));
+/// ```
+pub fn parse_stringsdict(source: &str) -> Result<Catalog, ParseError> {
+    let plist = Element::parse(source).map_err(|e| ParseError {
+        message: e.to_string(),
+    })?;
+    let Some(root) = plist.child("dict") else {
+        return Err(ParseError {
+            message: "expected a root <dict>".into(),
+        });
+    };
+    let mut catalog = Catalog::default();
+    for (key, entry) in dict(root) {
+        let entry = dict(entry);
+        let Some(format) = dict_string(&entry, "NSStringLocalizedFormatKey") else {
+            continue;
+        };
+        let mut variables = (entry.iter()).filter(|(name, value)| {
+            format.contains(&format!("%#@{name}@"))
+                && dict_string(&dict(value), "NSStringFormatSpecTypeKey").as_deref()
+                    == Some("NSStringPluralRuleType")
+        });
+        let (Some((name, variable)), None) = (variables.next(), variables.next()) else {
+            continue;
+        };
+        let placeholder = format!("%#@{name}@");
+        for (category, form) in dict(variable) {
+            let Ok(category) = category.parse::<PluralCategory>() else {
+                continue;
+            };
+            let form = format.replace(&placeholder, &form.text());
+            catalog.insert_plural(key.clone(), category, form);
+        }
+    }
+    Ok(catalog)
+}
+
+/// Names of the `.lproj` directory of `locale`: BCP-47 (`pt-BR`), underscored (`pt_BR`), and the legacy
+/// English name used by old bundles (`English`).
+fn lproj_names(locale: &LanguageIdentifier) -> Vec<String> {
+    let mut names = super::file_stems(locale);
+    if locale.script.is_none() && locale.region.is_none() {
+        let lang = isolang::Language::from_639_1(locale.language.as_str())
+            .or_else(|| isolang::Language::from_639_3(locale.language.as_str()));
+        names.extend(lang.map(|l| l.to_name().to_owned()));
+    }
+    names
+}
+
+/// A parser of a file format.
+type Parse = fn(&str) -> Result<Catalog, ParseError>;
+
+/// Load the table `table` (e.g. `Localizable`) of the locales in `chain` from the bundle
+/// resources directory `dir`, i.e. `{dir}/{locale}.lproj/{table}.strings` and `.stringsdict`.
+///
+/// Files may be encoded in UTF-8 or UTF-16. Locales without any readable file are skipped, and
+/// files that cannot be parsed are skipped with an error logged if the feature `tracing` is
+/// enabled.
+pub fn load<P: AsRef<std::path::Path>>(
+    dir: P,
+    chain: &[LanguageIdentifier],
+    table: &str,
+) -> LayeredCatalog {
+    let dir = dir.as_ref();
+    LayeredCatalog::new(chain.iter().filter_map(|locale| {
+        let mut catalog = Catalog::default();
+        let mut found = false;
+        let parsers: [(&str, Parse); 2] = [
+            ("strings", parse_strings),
+            ("stringsdict", parse_stringsdict),
+        ];
+        for (ext, parse) in parsers {
+            let file = format!("{table}.{ext}");
+            let paths = lproj_names(locale)
+                .into_iter()
+                .map(|name| dir.join(format!("{name}.lproj")).join(&file));
+            let Some(source) = read_first(paths) else {
+                continue;
+            };
+            found = true;
+            match parse(&source) {
+                Ok(part) => catalog.extend(part),
+                #[allow(unused_variables)]
+                Err(err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::error!(?dir, %locale, file, %err, "cannot parse Apple strings file");
+                }
+            }
+        }
+        found.then(|| (locale.clone(), catalog))
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::langid;
+
+    #[test]
+    fn strings_errors() {
+        for bad in [
+            r#""a" = "b""#,
+            r#""a = "b";"#,
+            "/* a",
+            r#""a" = ;"#,
+            r#""\q";"#,
+        ] {
+            parse_strings(bad).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn load_lproj() {
+        let dir = std::env::temp_dir().join("poly_l10n_lproj_test");
+        let write = |lproj: &str, file: &str, content: &[u8]| {
+            std::fs::create_dir_all(dir.join(lproj)).unwrap();
+            std::fs::write(dir.join(lproj).join(file), content).unwrap();
+        };
+        // UTF-16LE with BOM, as written by Xcode
+        let utf16: Vec<u8> = std::iter::once(0xfeff)
+            .chain("\"ok\" = \"Certo\";".encode_utf16())
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        write("pt_BR.lproj", "Localizable.strings", &utf16);
+        write(
+            "pt.lproj",
+            "Localizable.strings",
+            b"\"ok\" = \"OK\"; \"no\" = \"N\xc3\xa3o\";",
+        );
+        write(
+            "English.lproj",
+            "Localizable.strings",
+            b"\"yes\" = \"Yes\";",
+        );
+        let chain = [langid!("pt-BR"), langid!("pt"), langid!("en")];
+        let catalogs = load(&dir, &chain, "Localizable");
+        assert_eq!(catalogs.layers.len(), 3);
+        assert_eq!(catalogs.get("ok"), Some("Certo"));
+        assert_eq!(catalogs.get("no"), Some("Não"));
+        assert_eq!(catalogs.get("yes"), Some("Yes"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
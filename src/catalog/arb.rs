@@ -0,0 +1,100 @@
+//! Flutter [Application Resource Bundle](https://github.com/google/app-resource-bundle) (`.arb`)
+//! files.
+use super::{Catalog, LayeredCatalog, ParseError};
+use crate::json::Value;
+use unic_langid::LanguageIdentifier;
+
+/// Parse an ARB file.
+///
+/// The locale is read from `@@locale`. Other global attributes (`@@…`) and the metadata of
+/// messages (`@key`) are skipped. Messages are kept as ICU message format strings.
+///
+/// # Errors
+/// Returns [`ParseError`] if the JSON is invalid, is not an object, or has an invalid
+/// `@@locale`.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{catalog::arb, langid};
+/// let catalog = arb::parse(r#"{
+///     "@@locale": "pt_BR",
+///     "helloWorld": "Olá, mundo!",
+///     "@helloWorld": {"description": "The conventional newborn programmer greeting"},
+///     "nItems": "{count, plural, =1{1 item} other{{count} itens}}"
+/// }"#).unwrap();
+/// assert_eq!(catalog.locale, Some(langid!("pt-BR")));
+/// assert_eq!(catalog.get("helloWorld"), Some("Olá, mundo!"));
+/// assert_eq!(catalog.get("@helloWorld"), None);
+/// assert_eq!(catalog.len(), 2);
+/// ```
+pub fn parse(json: &str) -> Result<Catalog, ParseError> {
+    let Value::Object(members) = Value::parse(json)? else {
+        return Err(ParseError {
+            message: "expected an object".into(),
+        });
+    };
+    let mut catalog = Catalog::default();
+    for (key, value) in members {
+        if key == "@@locale" {
+            let locale = value.as_text().unwrap_or_default();
+            catalog.locale = Some(locale.parse().map_err(|e| ParseError {
+                message: format!("invalid @@locale `{locale}`: {e}"),
+            })?);
+        } else if !key.starts_with('@')
+            && let Some(text) = value.as_text()
+        {
+            catalog.insert(key, text);
+        }
+    }
+    Ok(catalog)
+}
+
+/// Locale of an ARB file named like `app_pt_BR.arb`, i.e. the longest valid locale at the end of
+/// the file stem after an underscore.
+fn locale_of_stem(stem: &str) -> Option<LanguageIdentifier> {
+    (stem.match_indices('_'))
+        .filter_map(|(i, _)| stem.get(i.saturating_add(1)..))
+        .find_map(|s| s.parse().ok())
+}
+
+/// Load the `.arb` files in the directory `dir` and layer those of the locales in `chain`.
+///
+/// The locale of each file is its `@@locale`, or else taken from its name (`app_pt_BR.arb`), as
+/// in Flutter. Multiple files of the same locale are merged. Files that cannot be parsed are
+/// skipped with an error logged if the feature `tracing` is enabled.
+///
+/// # Errors
+/// Returns an error if `dir` cannot be read.
+pub fn load<P: AsRef<std::path::Path>>(
+    dir: P,
+    chain: &[LanguageIdentifier],
+) -> std::io::Result<LayeredCatalog> {
+    super::load_dir(dir.as_ref(), chain, &["arb"], parse, locale_of_stem)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::langid;
+
+    #[test]
+    fn load_arb() {
+        let dir = std::env::temp_dir().join("poly_l10n_arb_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let write = |name: &str, json: &str| std::fs::write(dir.join(name), json).unwrap();
+        write("app_en.arb", r#"{"ok": "OK", "cancel": "Cancel"}"#);
+        write("app_pt_BR.arb", r#"{"ok": "Certo"}"#);
+        write(
+            "strings.arb",
+            r#"{"@@locale": "pt", "cancel": "Cancelar", "ok": "Pronto"}"#,
+        );
+        write("broken_pt.arb", "{");
+        write("ignored.json", "{}");
+        let chain = [langid!("pt-BR"), langid!("pt"), langid!("en")];
+        let catalogs = load(&dir, &chain).unwrap();
+        assert_eq!(catalogs.layers.len(), 3);
+        assert_eq!(catalogs.get("ok"), Some("Certo"));
+        assert_eq!(catalogs.get("cancel"), Some("Cancelar"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,180 @@
+//! [i18next](https://www.i18next.com) JSON resources.
+use super::{Catalog, LayeredCatalog, ParseError, file_stems, read_first};
+use crate::json::Value;
+use unic_langid::LanguageIdentifier;
+
+/// Separator between the namespace and the key, as in `common:button.save`.
+pub const NS_SEPARATOR: char = ':';
+
+/// Separator between the segments of nested keys, as in `button.save`.
+pub const KEY_SEPARATOR: char = '.';
+
+fn flatten(catalog: &mut Catalog, prefix: &str, value: &Value) {
+    let key = |k: &str| {
+        if prefix.is_empty() {
+            k.to_owned()
+        } else {
+            format!("{prefix}{KEY_SEPARATOR}{k}")
+        }
+    };
+    match value {
+        Value::Object(members) => {
+            for (k, v) in members {
+                flatten(catalog, &key(k), v);
+            }
+        }
+        Value::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                flatten(catalog, &key(&i.to_string()), v);
+            }
+        }
+        Value::Null => {}
+        value => {
+            if let Some(text) = value.as_text() {
+                catalog.insert(prefix, text);
+            }
+        }
+    }
+}
+
+/// Flatten the resource `value` of the namespace `ns` into `catalog`.
+fn namespaced(catalog: &mut Catalog, ns: &str, value: &Value) {
+    if let Value::Object(members) = value {
+        for (k, v) in members {
+            flatten(catalog, &format!("{ns}{NS_SEPARATOR}{k}"), v);
+        }
+    }
+}
+
+/// Parse an i18next JSON resource of one namespace.
+///
+/// Nested keys are flattened with [`KEY_SEPARATOR`] (arrays by index), and numbers and
+/// booleans are converted to text. Plural forms keep their i18next suffixes (`key_one`,
+/// `key_other`).
+///
+/// # Errors
+/// Returns [`ParseError`] if the JSON is invalid or not an object.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::catalog::i18next;
+/// let catalog = i18next::parse(
+///     r#"{"button": {"save": "Save"}, "items_one": "{{count}} item", "list": ["a", "b"]}"#,
+/// ).unwrap();
+/// assert_eq!(catalog.get("button.save"), Some("Save"));
+/// assert_eq!(catalog.get("items_one"), Some("{{count}} item"));
+/// assert_eq!(catalog.get("list.1"), Some("b"));
+/// ```
+pub fn parse(json: &str) -> Result<Catalog, ParseError> {
+    let value = Value::parse(json)?;
+    if !matches!(value, Value::Object(_)) {
+        return Err(ParseError {
+            message: "expected an object".into(),
+        });
+    }
+    let mut catalog = Catalog::default();
+    flatten(&mut catalog, "", &value);
+    Ok(catalog)
+}
+
+/// Parse an i18next resources object (`{ "lng": { "ns": { "key": … } } }`) and layer the
+/// languages in `chain`, skipping those it does not contain.
+///
+/// Languages are matched by their BCP-47 tag, with hyphens or underscores. Keys are prefixed with their
+/// namespace, as in `translation:key`.
+///
+/// # Errors
+/// Returns [`ParseError`] if the JSON is invalid.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{catalog::i18next, langid};
+/// let resources = r#"{
+///     "en": {"translation": {"greeting": {"hello": "Hello", "bye": "Bye"}}},
+///     "en-GB": {"translation": {"greeting": {"hello": "Hiya"}}}
+/// }"#;
+/// let chain = [langid!("en-GB"), langid!("en")];
+/// let catalogs = i18next::parse_resources(resources, &chain).unwrap();
+/// assert_eq!(catalogs.get("translation:greeting.hello"), Some("Hiya"));
+/// assert_eq!(catalogs.get("translation:greeting.bye"), Some("Bye"));
+/// ```
+pub fn parse_resources(
+    json: &str,
+    chain: &[LanguageIdentifier],
+) -> Result<LayeredCatalog, ParseError> {
+    let resources = Value::parse(json)?;
+    Ok(LayeredCatalog::new(chain.iter().filter_map(|locale| {
+        let namespaces = file_stems(locale)
+            .iter()
+            .find_map(|lng| resources.get(lng))?;
+        let Value::Object(namespaces) = namespaces else {
+            return None;
+        };
+        let mut catalog = Catalog::default();
+        for (ns, value) in namespaces {
+            namespaced(&mut catalog, ns, value);
+        }
+        Some((locale.clone(), catalog))
+    })))
+}
+
+/// Load the i18next namespaces `namespaces` of the locales in `chain` from `{dir}/{lng}/{ns}.json`.
+///
+/// Keys are prefixed with their namespace, as in `common:key`. Locales are tried in their
+/// BCP-47 tag, with hyphens or underscores; those without any readable namespace file are skipped, and files
+/// that cannot be parsed are skipped with an error logged if the feature `tracing` is enabled.
+pub fn load<P: AsRef<std::path::Path>>(
+    dir: P,
+    chain: &[LanguageIdentifier],
+    namespaces: &[&str],
+) -> LayeredCatalog {
+    let dir = dir.as_ref();
+    LayeredCatalog::new(chain.iter().filter_map(|locale| {
+        let mut catalog = Catalog::default();
+        let mut found = false;
+        for ns in namespaces {
+            let file = format!("{ns}.json");
+            let Some(json) = read_first(
+                file_stems(locale)
+                    .into_iter()
+                    .map(|lng| dir.join(lng).join(&file)),
+            ) else {
+                continue;
+            };
+            found = true;
+            match Value::parse(&json) {
+                Ok(value) => namespaced(&mut catalog, ns, &value),
+                #[allow(unused_variables)]
+                Err(err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::error!(?dir, %locale, ns, %err, "cannot parse i18next resource");
+                }
+            }
+        }
+        found.then(|| (locale.clone(), catalog))
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::langid;
+
+    #[test]
+    fn load_i18next() {
+        let dir = std::env::temp_dir().join("poly_l10n_i18next_test");
+        let write = |lng: &str, ns: &str, json: &str| {
+            std::fs::create_dir_all(dir.join(lng)).unwrap();
+            std::fs::write(dir.join(lng).join(format!("{ns}.json")), json).unwrap();
+        };
+        write("zh_Hant_TW", "common", r#"{"ok": "確定"}"#);
+        write("zh-Hant", "common", r#"{"ok": "好", "cancel": "取消"}"#);
+        write("zh-Hant", "menu", "{ invalid");
+        let chain = [langid!("zh-Hant-TW"), langid!("zh-Hant"), langid!("en")];
+        let catalogs = load(&dir, &chain, &["common", "menu"]);
+        assert_eq!(catalogs.layers.len(), 2);
+        assert_eq!(catalogs.get("common:ok"), Some("確定"));
+        assert_eq!(catalogs.get("common:cancel"), Some("取消"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,184 @@
+//! [XLIFF](https://docs.oasis-open.org/xliff/) 1.2 and 2.0 files.
+use super::{Catalog, LayeredCatalog, ParseError};
+use crate::xml::Element;
+use unic_langid::LanguageIdentifier;
+
+/// Add the translation units in `element` (XLIFF 1.2) and its groups to `catalog`.
+fn trans_units(catalog: &mut Catalog, element: &Element) {
+    for e in element.elements() {
+        match e.local_name() {
+            "group" => trans_units(catalog, e),
+            "trans-unit" => {
+                let key = e.attr("resname").or_else(|| e.attr("id"));
+                let target = e.child("target").map(Element::text);
+                if let (Some(key), Some(target)) = (key, target.filter(|t| !t.is_empty())) {
+                    catalog.insert(key, target);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Add the units in `element` (XLIFF 2.0) and its groups to `catalog`.
+fn units(catalog: &mut Catalog, element: &Element) {
+    for e in element.elements() {
+        match e.local_name() {
+            "group" => units(catalog, e),
+            "unit" => {
+                let Some(key) = e.attr("name").or_else(|| e.attr("id")) else {
+                    continue;
+                };
+                let target: String = (e.elements())
+                    .filter(|s| matches!(s.local_name(), "segment" | "ignorable"))
+                    .filter_map(|s| Some(s.child("target").unwrap_or(s.child("source")?).text()))
+                    .collect();
+                let translated = (e.elements()).any(|s| s.child("target").is_some());
+                if translated && !target.is_empty() {
+                    catalog.insert(key, target);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Parse an XLIFF 1.2 or 2.0 document.
+///
+/// The key of each unit is its `resname` (1.2) or `name` (2.0) if present, otherwise its `id`.
+/// Units without a non-empty target are skipped. Inline codes are replaced by their text content.
+/// The locale is the target language of the document (`target-language` or `trgLang`); with
+/// XLIFF 1.2, files with different target languages should not be mixed in one document.
+///
+/// # Errors
+/// Returns [`ParseError`] if the document is not well-formed XML, is not XLIFF, or has an
+/// invalid target language.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{catalog::xliff, langid};
+/// let v12 = xliff::parse(r#"<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+///   <file source-language="en" target-language="de" datatype="plaintext" original="app">
+///     <body>
+///       <trans-unit id="1" resname="greeting"><source>Hello</source><target>Hallo</target></trans-unit>
+///       <group id="menu">
+///         <trans-unit id="menu.open"><source>Open <x id="1"/></source><target>Öffnen</target></trans-unit>
+///       </group>
+///       <trans-unit id="untranslated"><source>Bye</source></trans-unit>
+///     </body>
+///   </file>
+/// </xliff>"#).unwrap();
+/// assert_eq!(v12.locale, Some(langid!("de")));
+/// assert_eq!(v12.get("greeting"), Some("Hallo"));
+/// assert_eq!(v12.get("menu.open"), Some("Öffnen"));
+/// assert_eq!(v12.get("untranslated"), None);
+///
+/// let v20 = xliff::parse(r#"<xliff version="2.0" xmlns="urn:oasis:names:tc:xliff:document:2.0"
+///     srcLang="en" trgLang="fr">
+///   <file id="f1">
+///     <unit id="u1" name="greeting">
+///       <segment><source>Hello.</source><target>Bonjour.</target></segment>
+///       <ignorable><source> </source></ignorable>
+///       <segment><source>Welcome <pc id="1">home</pc>!</source><target>Bienvenue <pc id="1">chez vous</pc> !</target></segment>
+///     </unit>
+///   </file>
+/// </xliff>"#).unwrap();
+/// assert_eq!(v20.locale, Some(langid!("fr")));
+/// assert_eq!(v20.get("greeting"), Some("Bonjour. Bienvenue chez vous !"));
+/// ```
+pub fn parse(source: &str) -> Result<Catalog, ParseError> {
+    let root = Element::parse(source).map_err(|e| ParseError {
+        message: e.to_string(),
+    })?;
+    if root.local_name() != "xliff" {
+        return Err(ParseError {
+            message: "expected an <xliff> root element".into(),
+        });
+    }
+    let v2 = root.attr("version").is_some_and(|v| v.starts_with('2'));
+    let mut catalog = Catalog::default();
+    let mut target = root.attr("trgLang");
+    for file in root.elements().filter(|e| e.local_name() == "file") {
+        if v2 {
+            units(&mut catalog, file);
+        } else {
+            target = target.or_else(|| file.attr("target-language"));
+            if let Some(body) = file.child("body") {
+                trans_units(&mut catalog, body);
+            }
+        }
+    }
+    if let Some(target) = target {
+        catalog.locale = Some(target.parse().map_err(|e| ParseError {
+            message: format!("invalid target language `{target}`: {e}"),
+        })?);
+    }
+    Ok(catalog)
+}
+
+/// Locale of an XLIFF file named like `messages.fr.xlf` or `messages_fr.xlf`.
+fn locale_of_stem(stem: &str) -> Option<LanguageIdentifier> {
+    let (_, locale) = stem.rsplit_once(['.', '_'])?;
+    locale.parse().ok()
+}
+
+/// Load the `.xlf` and `.xliff` files in the directory `dir` and layer those of the locales in
+/// `chain`.
+///
+/// The locale of each file is its target language, or else taken from its name
+/// (`messages.fr.xlf`). Multiple files of the same locale are merged. Files may be encoded in
+/// UTF-8 or UTF-16; those that cannot be parsed are skipped with an error logged if the feature
+/// `tracing` is enabled.
+///
+/// # Errors
+/// Returns an error if `dir` cannot be read.
+pub fn load<P: AsRef<std::path::Path>>(
+    dir: P,
+    chain: &[LanguageIdentifier],
+) -> std::io::Result<LayeredCatalog> {
+    super::load_dir(
+        dir.as_ref(),
+        chain,
+        &["xlf", "xliff"],
+        parse,
+        locale_of_stem,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::langid;
+
+    #[test]
+    fn load_xliff() {
+        let dir = std::env::temp_dir().join("poly_l10n_xliff_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let unit = |id: &str, target: &str| {
+            format!(
+                r#"<trans-unit id="{id}"><source>x</source><target>{target}</target></trans-unit>"#
+            )
+        };
+        let write = |name: &str, lang: Option<&str>, units: &str| {
+            let lang = lang.map_or(String::new(), |l| format!(r#" target-language="{l}""#));
+            let doc =
+                format!(r#"<xliff version="1.2"><file{lang}><body>{units}</body></file></xliff>"#);
+            std::fs::write(dir.join(name), doc).unwrap();
+        };
+        write("messages.es-MX.xlf", None, &unit("ok", "Órale"));
+        write(
+            "anything.xliff",
+            Some("es"),
+            &(unit("ok", "Vale") + &unit("no", "No")),
+        );
+        write("broken.es.xlf", None, "<trans-unit>");
+        let chain = [langid!("es-MX"), langid!("es")];
+        let catalogs = load(&dir, &chain).unwrap();
+        assert_eq!(catalogs.layers.len(), 2);
+        assert_eq!(catalogs.get("ok"), Some("Órale"));
+        assert_eq!(catalogs.get("no"), Some("No"));
+        parse("<xliff/>").unwrap();
+        parse("<root/>").unwrap_err();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
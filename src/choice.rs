@@ -0,0 +1,100 @@
+//! Persistence of the locale the user explicitly chose in an application, see [`ChoiceStore`].
+//!
+//! This module is gated behind the feature `persist`.
+use crate::LanguageIdentifier;
+use std::path::{Path, PathBuf};
+
+/// Storage of the user's explicit language selection in an application, which should take
+/// precedence over the locales detected from the system.
+///
+/// The choice is stored as a language tag in a file, by default `poly_l10n/locale` in the
+/// application's directory in the user configuration directory (see [`Self::for_app`]).
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, ChoiceStore};
+/// let store = ChoiceStore::at(std::env::temp_dir().join("poly_l10n_choice_doctest"));
+/// store.save_choice(&langid!("pt-BR")).unwrap();
+/// assert_eq!(store.load_choice(), Some(langid!("pt-BR")));
+/// store.clear_choice().unwrap();
+/// assert_eq!(store.load_choice(), None);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChoiceStore {
+    path: PathBuf,
+}
+
+impl ChoiceStore {
+    /// Store the choice of the application `app` in the user configuration directory, i.e.
+    /// `$XDG_CONFIG_HOME/{app}/poly_l10n/locale` (or `~/.config/…`) on Unix and
+    /// `%APPDATA%\{app}\poly_l10n\locale` on Windows.
+    ///
+    /// Returns [`None`] if the configuration directory cannot be determined.
+    #[must_use]
+    pub fn for_app(app: &str) -> Option<Self> {
+        let dir = crate::dirs::user_config_dir()?;
+        Some(Self::at(dir.join(app).join("poly_l10n").join("locale")))
+    }
+
+    /// Store the choice in the file at `path`.
+    #[must_use]
+    pub fn at<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Path to the file storing the choice.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Persist `locale` as the user's choice, creating the parent directories if needed.
+    ///
+    /// The file is replaced atomically where the platform supports it, so a concurrent
+    /// [`Self::load_choice`] never sees a partial write.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    pub fn save_choice(&self, locale: &LanguageIdentifier) -> std::io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, format!("{locale}\n"))?;
+        std::fs::rename(&tmp, &self.path)
+    }
+
+    /// The persisted choice, or [`None`] if there is none or it cannot be read or parsed.
+    #[must_use]
+    pub fn load_choice(&self) -> Option<LanguageIdentifier> {
+        let content = std::fs::read_to_string(&self.path).ok()?;
+        content.trim().parse().ok()
+    }
+
+    /// Forget the choice, so the system locales are used again.
+    ///
+    /// # Errors
+    /// Returns an error if the file exists but cannot be removed.
+    pub fn clear_choice(&self) -> std::io::Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            res => res,
+        }
+    }
+
+    /// The locales the user wants: the persisted choice if any, followed by the system locales
+    /// (see [`crate::system_want_langids`]) without duplicates.
+    ///
+    /// Only available with the feature `getlang`.
+    #[cfg(feature = "getlang_lite")]
+    #[must_use]
+    pub fn want_langids(&self) -> Vec<LanguageIdentifier> {
+        let mut langids: Vec<_> = self.load_choice().into_iter().collect();
+        for langid in crate::system_want_langids() {
+            if !langids.contains(&langid) {
+                langids.push(langid);
+            }
+        }
+        langids
+    }
+}
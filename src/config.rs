@@ -0,0 +1,133 @@
+//! Rulebooks loaded from configuration files, see [`crate::Rulebook::from_toml_str`].
+use crate::json::Value;
+use crate::macros::IntoLangIdAble;
+use crate::{LanguageIdentifier, Rule, rules_file};
+
+/// Key of the generic rules in configuration files.
+const DIRECTIVES: &str = "directives";
+
+/// Error when parsing a configuration file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigError {
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<rules_file::ParseError> for ConfigError {
+    fn from(err: rules_file::ParseError) -> Self {
+        Self {
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<crate::json::Error> for ConfigError {
+    fn from(err: crate::json::Error) -> Self {
+        Self {
+            message: err.to_string(),
+        }
+    }
+}
+
+const fn err<T>(message: String) -> Result<T, ConfigError> {
+    Err(ConfigError { message })
+}
+
+fn directive(name: &str) -> Option<Rule> {
+    Some(match name {
+        "default" => Rule::Default,
+        "strip-script" => Rule::StripScript,
+        "strip-region" => Rule::StripRegion,
+        "strip-variants" => Rule::StripVariants,
+        "to-639-1" => Rule::To639_1,
+        _ => return None,
+    })
+}
+
+fn locale(s: &str) -> Result<LanguageIdentifier, ConfigError> {
+    s.to_langid()
+        .or_else(|e| err(format!("invalid locale `{s}`: {e}")))
+}
+
+/// Turn an entry of a configuration file into rules.
+fn rules(key: &str, values: &[String]) -> Result<Vec<Rule>, ConfigError> {
+    if key == DIRECTIVES {
+        return (values.iter())
+            .map(|v| directive(v).map_or_else(|| err(format!("unknown directive `{v}`")), Ok))
+            .collect();
+    }
+    let to = values.iter().map(|v| locale(v)).collect::<Result<_, _>>()?;
+    if let Some(language) = key.strip_suffix("-*") {
+        let Ok(language) = language.parse() else {
+            return err(format!("invalid language `{language}`"));
+        };
+        return Ok(vec![Rule::PerLanguage { language, to }]);
+    }
+    Ok(vec![Rule::MapExact {
+        from: locale(key)?,
+        to,
+    }])
+}
+
+/// Parse a TOML configuration file, see [`crate::Rulebook::from_toml_str`].
+pub fn parse_toml(input: &str) -> Result<Vec<Rule>, ConfigError> {
+    let mut parsed = vec![];
+    for (line, key, values) in rules_file::parse_toml_entries(input)? {
+        let entry = rules(&key, &values).map_err(|e| ConfigError {
+            message: format!("line {line}: {}", e.message),
+        })?;
+        parsed.extend(entry);
+    }
+    Ok(parsed)
+}
+
+/// Parse a JSON configuration file, see [`crate::Rulebook::from_json_str`].
+pub fn parse_json(input: &str) -> Result<Vec<Rule>, ConfigError> {
+    let Value::Object(members) = Value::parse(input)? else {
+        return err("expected an object".to_owned());
+    };
+    let mut parsed = vec![];
+    for (key, value) in members {
+        let values = match value {
+            Value::String(s) => vec![s],
+            Value::Array(values) => (values.into_iter())
+                .map(|v| match v {
+                    Value::String(s) => Ok(s),
+                    _ => err(format!("`{key}`: expected a string")),
+                })
+                .collect::<Result<_, _>>()?,
+            _ => return err(format!("`{key}`: expected a string or an array")),
+        };
+        parsed.extend(rules(&key, &values)?);
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse_toml("directives = [\"strip-region\"]").unwrap(),
+            [Rule::StripRegion]
+        );
+        let err = parse_toml("\"arb\" = [\"ar\"]\n\"toolonglang-*\" = [\"ar\"]").unwrap_err();
+        assert!(err.message.starts_with("line 2: invalid language"), "{err}");
+        assert_eq!(parse_json("[]").unwrap_err().message, "expected an object");
+        assert_eq!(
+            parse_json(r#"{"arb": [1]}"#).unwrap_err().message,
+            "`arb`: expected a string"
+        );
+        let err = parse_json(r#"{"directives": ["strip-everything"]}"#).unwrap_err();
+        assert_eq!(err.message, "unknown directive `strip-everything`");
+    }
+}
@@ -5,6 +5,25 @@ use itertools::Itertools;
 /// [`crate::Rulebook`] function for the default recommended rule(s).
 #[inline]
 pub fn default_rulebook(l: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+    rules(l, true)
+}
+
+/// Same as [`default_rulebook`], but without [`crate::per_lang_default_rules`].
+#[inline]
+pub fn default_rulebook_without_per_lang(l: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+    rules(l, false)
+}
+
+#[cfg_attr(
+    not(feature = "per_lang_default_rules"),
+    allow(unused_variables, clippy::needless_pass_by_value)
+)]
+fn rules(l: &LanguageIdentifier, per_lang: bool) -> Vec<LanguageIdentifier> {
+    // the collective code of Berber languages, which is not in ISO 639-3
+    #[cfg(feature = "per_lang_default_rules")]
+    if per_lang && l.language == "ber" {
+        return crate::per_lang_default_rules::berber_rules(l);
+    }
     let Some(lang) = langid_to_isolang(l) else {
         return vec![];
     };
@@ -35,11 +54,17 @@ pub fn default_rulebook(l: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
     }
 
     #[cfg(feature = "per_lang_default_rules")]
-    #[allow(clippy::indexing_slicing)]
-    if let Some(f) = &crate::per_lang_default_rules::LANG_RULES[lang as usize] {
+    if let Some(f) = (crate::per_lang_default_rules::LANG_RULES.get(&lang)).filter(|_| per_lang) {
         rules.extend_from_slice(&f(l, &lang));
     }
 
+    // e.g. `yue-HK` → `zh-HK`, `afb` → `ar`
+    if let Some(prefix) = crate::canonicalize::extlang_prefix(l.language.as_str()) {
+        let mut rule = l.clone();
+        rule.language = prefix.parse().expect("cannot parse extlang prefix");
+        rules.push(rule);
+    }
+
     let new_rules = rules.iter().flat_map(find_rules_omit_optparts);
     let new_rules = new_rules.unique().collect_vec();
     #[cfg(feature = "tracing")]
@@ -49,24 +74,65 @@ pub fn default_rulebook(l: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
     rules
 }
 
-fn langid_to_isolang(l: &LanguageIdentifier) -> Option<Language> {
-    let lang = match l.language.as_str().len() {
-        2 => Language::from_639_1(l.language.as_str()),
-        3 => Language::from_639_3(l.language.as_str()),
-        #[allow(unused_variables)]
-        len => {
-            #[cfg(feature = "tracing")]
-            tracing::error!(?l, len, "invalid language code, expected length of 2 or 3");
-            return None;
-        }
+/// Rule stripping the script, see [`crate::Rulebook::strip_script`].
+pub fn strip_script(l: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+    let mut rule = l.clone();
+    rule.script.take().map(|_| rule).into_iter().collect()
+}
+
+/// Rule stripping the region, see [`crate::Rulebook::strip_region`].
+pub fn strip_region(l: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+    let mut rule = l.clone();
+    rule.region.take().map(|_| rule).into_iter().collect()
+}
+
+/// Rule stripping the variants, see [`crate::Rulebook::strip_variants`].
+pub fn strip_variants(l: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+    if l.variants().len() == 0 {
+        return vec![];
+    }
+    let mut rule = l.clone();
+    rule.clear_variants();
+    vec![rule]
+}
+
+/// Rule replacing an ISO 639-3 language with its ISO 639-1 equivalent, see
+/// [`crate::Rulebook::to_639_1`].
+pub fn to_639_1(l: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+    if l.language.as_str().len() != 3 {
+        return vec![];
+    }
+    let Some(two) = Language::from_639_3(l.language.as_str()).and_then(|lang| lang.to_639_1())
+    else {
+        return vec![];
     };
+    let mut rule = l.clone();
+    rule.language = two.parse().expect("cannot parse ISO 639-1 code");
+    vec![rule]
+}
+
+pub fn langid_to_isolang(l: &LanguageIdentifier) -> Option<Language> {
+    let lang = langid_to_isolang_silent(l);
     #[cfg(feature = "tracing")]
     if lang.is_none() {
-        tracing::error!(?l, "invalid language code, fail to parse with `isolang`");
+        match l.language.as_str().len() {
+            2 | 3 => tracing::error!(?l, "invalid language code, fail to parse with `isolang`"),
+            len => tracing::error!(?l, len, "invalid language code, expected length of 2 or 3"),
+        }
     }
     lang
 }
 
+/// Same as [`langid_to_isolang`], but without logging languages unknown to `isolang`, e.g. `und`
+/// and private-use languages, for lookups where they are expected.
+pub fn langid_to_isolang_silent(l: &LanguageIdentifier) -> Option<Language> {
+    match l.language.as_str().len() {
+        2 => Language::from_639_1(l.language.as_str()),
+        3 => Language::from_639_3(l.language.as_str()),
+        _ => None,
+    }
+}
+
 /// Generate a list of [`LanguageIdentifier`] without `script`, `region` and/or `variants` from
 /// the given `rule`.
 ///
@@ -106,3 +172,25 @@ fn find_rules_omit_optparts(rule: &LanguageIdentifier) -> impl Iterator<Item = L
         Some(r)
     })
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn omit_numeric_region() {
+        let rules = find_rules_omit_optparts(&crate::langid!("es-419")).collect_vec();
+        assert_eq!(rules, [crate::langid!("es")]);
+    }
+
+    #[cfg(feature = "per_lang_default_rules")]
+    #[test]
+    fn numeric_region_rules() {
+        let rules = default_rulebook(&crate::langid!("es-MX"));
+        assert!(rules.contains(&crate::langid!("es-419")));
+        let rules = default_rulebook(&crate::langid!("en-GB"));
+        assert!(rules.contains(&crate::langid!("en-001")));
+        let rules = default_rulebook(&crate::langid!("en-DE"));
+        assert!(rules.contains(&crate::langid!("en-150")));
+    }
+}
@@ -0,0 +1,12 @@
+//! Platform directories.
+
+/// Directory for user configurations, i.e. `$XDG_CONFIG_HOME` or `~/.config` on Unix and
+/// `%APPDATA%` on Windows.
+pub fn user_config_dir() -> Option<std::path::PathBuf> {
+    let var = |k| std::env::var_os(k).filter(|v| !v.is_empty());
+    if cfg!(windows) {
+        return var("APPDATA").map(std::path::PathBuf::from);
+    }
+    (var("XDG_CONFIG_HOME").map(std::path::PathBuf::from))
+        .or_else(|| var("HOME").map(|home| std::path::Path::new(&home).join(".config")))
+}
@@ -0,0 +1,197 @@
+//! Conversion of human-readable language names into [`LanguageIdentifier`]s.
+//!
+//! This module is gated behind the feature `display_names`.
+use isolang::Language;
+use unic_langid::LanguageIdentifier;
+
+/// Common English names of languages that differ from their ISO 639 names (or are missing from
+/// them), including names of language variants.
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("american english", "en-US"),
+    ("american spanish", "es-419"),
+    ("australian english", "en-AU"),
+    ("bokmal", "nb"),
+    ("bokmål", "nb"),
+    ("brazilian portuguese", "pt-BR"),
+    ("british english", "en-GB"),
+    ("canadian english", "en-CA"),
+    ("canadian french", "fr-CA"),
+    ("cantonese", "yue"),
+    ("castilian", "es-ES"),
+    ("european portuguese", "pt-PT"),
+    ("european spanish", "es-ES"),
+    ("farsi", "fa"),
+    ("flemish", "nl-BE"),
+    ("gaelic", "gd"),
+    ("greek", "el"),
+    ("haitian creole", "ht"),
+    ("hokkien", "nan"),
+    ("kyrgyz", "ky"),
+    ("latin american spanish", "es-419"),
+    ("mandarin", "zh"),
+    ("mexican spanish", "es-MX"),
+    ("norwegian bokmal", "nb"),
+    ("punjabi", "pa"),
+    ("pashto", "ps"),
+    ("simplified chinese", "zh-Hans"),
+    ("sinhalese", "si"),
+    ("slovene", "sl"),
+    ("swiss german", "gsw"),
+    ("taiwanese", "nan-TW"),
+    ("traditional chinese", "zh-Hant"),
+    ("uyghur", "ug"),
+];
+
+/// English names of scripts, used in qualifiers like `Chinese (Traditional)`.
+const SCRIPT_NAMES: &[(&str, &str)] = &[
+    ("arabic", "Arab"),
+    ("cyrillic", "Cyrl"),
+    ("devanagari", "Deva"),
+    ("latin", "Latn"),
+    ("simplified", "Hans"),
+    ("simplified han", "Hans"),
+    ("traditional", "Hant"),
+    ("traditional han", "Hant"),
+];
+
+/// English names of regions, used in qualifiers like `Portuguese (Brazil)`.
+const REGION_NAMES: &[(&str, &str)] = &[
+    ("argentina", "AR"),
+    ("australia", "AU"),
+    ("austria", "AT"),
+    ("belgium", "BE"),
+    ("bosnia and herzegovina", "BA"),
+    ("brazil", "BR"),
+    ("canada", "CA"),
+    ("chile", "CL"),
+    ("china", "CN"),
+    ("colombia", "CO"),
+    ("denmark", "DK"),
+    ("egypt", "EG"),
+    ("europe", "150"),
+    ("finland", "FI"),
+    ("france", "FR"),
+    ("germany", "DE"),
+    ("great britain", "GB"),
+    ("hong kong", "HK"),
+    ("india", "IN"),
+    ("indonesia", "ID"),
+    ("international", "001"),
+    ("iran", "IR"),
+    ("ireland", "IE"),
+    ("israel", "IL"),
+    ("italy", "IT"),
+    ("japan", "JP"),
+    ("korea", "KR"),
+    ("latin america", "419"),
+    ("macao", "MO"),
+    ("macau", "MO"),
+    ("malaysia", "MY"),
+    ("mexico", "MX"),
+    ("morocco", "MA"),
+    ("netherlands", "NL"),
+    ("new zealand", "NZ"),
+    ("norway", "NO"),
+    ("peru", "PE"),
+    ("philippines", "PH"),
+    ("poland", "PL"),
+    ("portugal", "PT"),
+    ("russia", "RU"),
+    ("saudi arabia", "SA"),
+    ("serbia", "RS"),
+    ("singapore", "SG"),
+    ("south africa", "ZA"),
+    ("south korea", "KR"),
+    ("spain", "ES"),
+    ("sweden", "SE"),
+    ("switzerland", "CH"),
+    ("taiwan", "TW"),
+    ("thailand", "TH"),
+    ("turkey", "TR"),
+    ("uk", "GB"),
+    ("ukraine", "UA"),
+    ("united kingdom", "GB"),
+    ("united states", "US"),
+    ("us", "US"),
+    ("usa", "US"),
+    ("vietnam", "VN"),
+    ("world", "001"),
+];
+
+fn lookup(table: &[(&str, &'static str)], name: &str) -> Option<&'static str> {
+    (table.iter())
+        .find(|(n, _)| *n == name)
+        .map(|(_, code)| *code)
+}
+
+/// Find the language with the given lowercased English name.
+fn language(name: &str) -> Option<LanguageIdentifier> {
+    if let Some(tag) = lookup(LANGUAGE_NAMES, name) {
+        return tag.parse().ok();
+    }
+    let lang = Language::from_name_lowercase(name)?;
+    lang.to_639_1()
+        .unwrap_or_else(|| lang.to_639_3())
+        .parse()
+        .ok()
+}
+
+/// Find the language identifier for a human-readable English language name.
+///
+/// Besides the ISO 639 names of languages (`Spanish`, `Norwegian Nynorsk`), common alternative
+/// names (`Farsi`, `Brazilian Portuguese`) are accepted. Scripts and regions may be given as
+/// qualifiers in parentheses or after a comma, e.g. `Chinese (Traditional)`,
+/// `English (United States)`, `Serbian (Latin, Serbia)` or `Chinese, Simplified`.
+///
+/// Matching is case-insensitive. Returns [`None`] if the name or any qualifier is unknown.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{display_names::from_display_name, langid};
+/// assert_eq!(from_display_name("Chinese (Traditional)"), Some(langid!("zh-Hant")));
+/// assert_eq!(from_display_name("english (united states)"), Some(langid!("en-US")));
+/// assert_eq!(from_display_name("Serbian (Latin, Serbia)"), Some(langid!("sr-Latn-RS")));
+/// assert_eq!(from_display_name("Chinese, Simplified"), Some(langid!("zh-Hans")));
+/// assert_eq!(from_display_name("Brazilian Portuguese"), Some(langid!("pt-BR")));
+/// assert_eq!(from_display_name("Standard Arabic"), Some(langid!("arb")));
+/// assert_eq!(from_display_name("Klingonese"), None);
+/// ```
+#[must_use]
+pub fn from_display_name(name: &str) -> Option<LanguageIdentifier> {
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    let name = name.to_lowercase();
+    if let Some(langid) = language(&name) {
+        return Some(langid);
+    }
+    let (base, qualifiers) = match name.split_once('(') {
+        Some((base, rest)) => (base, rest.strip_suffix(')')?),
+        None => name.split_once(',')?,
+    };
+    let mut langid = language(base.trim())?;
+    for qualifier in qualifiers.split(',').map(str::trim) {
+        if let Some(script) = lookup(SCRIPT_NAMES, qualifier) {
+            langid.script = Some(script.parse().ok()?);
+        } else {
+            langid.region = Some(lookup(REGION_NAMES, qualifier)?.parse().ok()?);
+        }
+    }
+    Some(langid)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tables_valid() {
+        for (_, tag) in LANGUAGE_NAMES {
+            tag.parse::<LanguageIdentifier>().unwrap();
+        }
+        for (_, script) in SCRIPT_NAMES {
+            script.parse::<unic_langid::subtags::Script>().unwrap();
+        }
+        for (_, region) in REGION_NAMES {
+            region.parse::<unic_langid::subtags::Region>().unwrap();
+        }
+    }
+}
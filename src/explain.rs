@@ -0,0 +1,118 @@
+//! Provenance of fallbacks, see [`crate::LocaleFallbackSolver::explain_locale`].
+use crate::{FnRulebook, LanguageIdentifier};
+use itertools::Itertools;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// How a fallback was found, see [`Explanation`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Derivation {
+    /// The fallback.
+    pub locale: LanguageIdentifier,
+    /// The locale the fallback was given for, i.e. the solved locale or an earlier fallback.
+    pub from: LanguageIdentifier,
+    /// The index of the rule that gave the fallback in [`crate::Rulebook::rules`].
+    pub rule: usize,
+}
+
+/// The fallbacks of a locale with their provenance, in the order of the fallback chain.
+///
+/// This is displayed as a derivation tree, e.g.
+///
+/// ```text
+/// de-AT
+/// ├─ de-DE (rule #0)
+/// │  └─ de (rule #1)
+/// └─ en (rule #2)
+/// ```
+///
+/// See [`crate::LocaleFallbackSolver::explain_locale`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Explanation {
+    /// The solved locale, the root of the tree.
+    pub locale: LanguageIdentifier,
+    /// How each fallback was found.
+    pub derivations: Vec<Derivation>,
+}
+
+impl Explanation {
+    /// The fallbacks, like [`crate::LocaleFallbackSolver::solve_locale`].
+    #[must_use]
+    pub fn chain(&self) -> Vec<LanguageIdentifier> {
+        self.derivations.iter().map(|d| d.locale.clone()).collect()
+    }
+
+    /// How `locale` was found, if it is a fallback.
+    #[must_use]
+    pub fn derivation(&self, locale: &LanguageIdentifier) -> Option<&Derivation> {
+        self.derivations.iter().find(|d| d.locale == *locale)
+    }
+
+    fn fmt_children(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        parent: &LanguageIdentifier,
+        prefix: &str,
+    ) -> std::fmt::Result {
+        let children = (self.derivations.iter())
+            .filter(|d| d.from == *parent)
+            .collect_vec();
+        for (i, d) in children.iter().enumerate() {
+            let last = i.saturating_add(1) == children.len();
+            let (branch, indent) = if last {
+                ("└─", "   ")
+            } else {
+                ("├─", "│  ")
+            };
+            writeln!(f, "{prefix}{branch} {} (rule #{})", d.locale, d.rule)?;
+            // the solved locale may be given as its own fallback
+            if d.locale != self.locale {
+                self.fmt_children(f, &d.locale, &format!("{prefix}{indent}"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Explanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.locale)?;
+        self.fmt_children(f, &self.locale, "")
+    }
+}
+
+/// See [`crate::LocaleFallbackSolver::explain_locale`].
+pub fn explain<F>(rules: &[F], locale: &LanguageIdentifier) -> Explanation
+where
+    F: std::ops::Deref<Target: Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier>>,
+{
+    // the first rule giving each locale, which is where the solver adds it to the chain
+    let origins: RefCell<HashMap<LanguageIdentifier, (LanguageIdentifier, usize)>> =
+        RefCell::default();
+    let rulebook = FnRulebook(|l: &LanguageIdentifier| {
+        let fallbacks = rules.iter().map(|f| f(l)).collect_vec();
+        let mut origins = origins.borrow_mut();
+        for (rule, fallback) in (fallbacks.iter().enumerate())
+            .flat_map(|(rule, fallbacks)| fallbacks.iter().map(move |f| (rule, f)))
+        {
+            (origins.entry(fallback.clone())).or_insert_with(|| (l.clone(), rule));
+        }
+        fallbacks.into_iter().flatten().collect_vec()
+    });
+    let chain = crate::solve(&rulebook, locale, &());
+    let mut origins = origins.into_inner();
+    let derivations = (chain.into_iter())
+        .filter_map(|l| {
+            let (from, rule) = origins.remove(&l)?;
+            Some(Derivation {
+                locale: l,
+                from,
+                rule,
+            })
+        })
+        .collect();
+    Explanation {
+        locale: locale.clone(),
+        derivations,
+    }
+}
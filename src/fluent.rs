@@ -0,0 +1,273 @@
+//! Layering of [Fluent](https://projectfluent.org) resources along fallback chains.
+//!
+//! This module is gated behind the feature `fluent`.
+//!
+//! Messages and terms are never merged partially: a message (with all of its attributes) comes
+//! entirely from one locale. Each locale in the chain gets its own resolved resource, in which
+//! entries missing from that locale are filled in from the locales after it. This way, terms and
+//! messages referenced by a message always resolve in the locale of that message first (so
+//! grammatical attributes like `-brand.gender` match the language of the message), and only fall
+//! back to later locales if the locale does not define them.
+use crate::LocaleSpelling;
+use std::collections::HashMap;
+use unic_langid::LanguageIdentifier;
+
+/// A message or term in an FTL resource.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FtlEntry {
+    id: String,
+    source: String,
+}
+
+impl FtlEntry {
+    /// Identifier of the entry, with the leading `-` for terms.
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Whether the entry is a term (`-brand = …`) rather than a message.
+    #[must_use]
+    pub fn is_term(&self) -> bool {
+        self.id.starts_with('-')
+    }
+
+    /// FTL source of the entry, including its comment.
+    #[must_use]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+/// The messages and terms of an FTL file.
+///
+/// Only the structure needed to split the file into entries is parsed; the patterns themselves
+/// are kept as source. Junk (lines that are not part of a message, term or comment) is skipped.
+/// If an identifier is defined more than once, the last definition wins.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FtlResource {
+    entries: Vec<FtlEntry>,
+    index: HashMap<String, usize>,
+}
+
+/// Identifier of the entry defined on `line`, which must not be indented.
+fn entry_id(line: &str) -> Option<&str> {
+    let (id, _) = line.split_once('=')?;
+    let id = id.trim_end();
+    let name = id.strip_prefix('-').unwrap_or(id);
+    let mut chars = name.chars();
+    (chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+    .then_some(id)
+}
+
+impl FtlResource {
+    /// Split FTL source into entries.
+    ///
+    /// # Examples
+    /// ```
+    /// use poly_l10n::fluent::FtlResource;
+    /// let res = FtlResource::parse(
+    ///     "## Group comment\n\n-brand = Firefox\n\n# Greeting\nhello = Hello from { -brand }!\n    .title = Hi\n",
+    /// );
+    /// assert_eq!(res.entries().len(), 2);
+    /// assert!(res.get("-brand").unwrap().is_term());
+    /// assert_eq!(
+    ///     res.get("hello").unwrap().source(),
+    ///     "# Greeting\nhello = Hello from { -brand }!\n    .title = Hi"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn parse(source: &str) -> Self {
+        let mut res = Self::default();
+        let mut comment: Vec<&str> = vec![];
+        let mut current: Option<(&str, Vec<&str>)> = None;
+        for line in source.lines() {
+            let continuation = line.starts_with([' ', '}']) || line.trim().is_empty();
+            if continuation {
+                if let Some((_, lines)) = &mut current {
+                    lines.push(line);
+                } else if line.trim().is_empty() {
+                    // a blank line detaches comments from the next entry
+                    comment.clear();
+                }
+                continue;
+            }
+            if let Some((id, lines)) = current.take() {
+                res.push(id, &lines);
+            }
+            if line.starts_with("# ") || line == "#" {
+                comment.push(line);
+            } else if line.starts_with('#') {
+                // group and resource comments
+                comment.clear();
+            } else if let Some(id) = entry_id(line) {
+                let mut lines = std::mem::take(&mut comment);
+                lines.push(line);
+                current = Some((id, lines));
+            } else {
+                comment.clear();
+            }
+        }
+        if let Some((id, lines)) = current {
+            res.push(id, &lines);
+        }
+        res
+    }
+
+    fn push(&mut self, id: &str, lines: &[&str]) {
+        let end = (lines.iter())
+            .rposition(|l| !l.trim().is_empty())
+            .map_or(0, |i| i.saturating_add(1));
+        let entry = FtlEntry {
+            id: id.to_owned(),
+            source: lines.get(..end).unwrap_or_default().join("\n"),
+        };
+        if let Some(&i) = self.index.get(id) {
+            if let Some(old) = self.entries.get_mut(i) {
+                *old = entry;
+            }
+            return;
+        }
+        self.index.insert(id.to_owned(), self.entries.len());
+        self.entries.push(entry);
+    }
+
+    /// The entries in order of definition.
+    #[must_use]
+    pub fn entries(&self) -> &[FtlEntry] {
+        &self.entries
+    }
+
+    /// The entry with the identifier `id` (with the leading `-` for terms).
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&FtlEntry> {
+        self.entries.get(*self.index.get(id)?)
+    }
+}
+
+impl std::fmt::Display for FtlResource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{}", entry.source)?;
+        }
+        Ok(())
+    }
+}
+
+/// FTL resources of the locales in a fallback chain, most preferred first.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{fluent::{FtlResource, LayeredFtl}, langid};
+/// let layers = LayeredFtl::new([
+///     (langid!("de"), FtlResource::parse("-brand = Feuerfuchs\n    .gender = masculine\nhello = Hallo von { -brand }")),
+///     (langid!("en"), FtlResource::parse("-brand = Firefox\nhello = Hello\nbye = Bye from { -brand }")),
+/// ]);
+/// // `hello` is translated, `bye` falls back to English
+/// assert_eq!(layers.locale_of("hello"), Some(&langid!("de")));
+/// assert_eq!(layers.locale_of("bye"), Some(&langid!("en")));
+/// // format `hello` with a bundle of the resolved German resource, which uses the German term
+/// let de = layers.resolve(&langid!("de")).unwrap();
+/// assert!(de.get("-brand").unwrap().source().contains("Feuerfuchs"));
+/// assert!(de.get("bye").is_some());
+/// // while `bye` is formatted in English with the English term
+/// let en = layers.resolve(&langid!("en")).unwrap();
+/// assert!(en.get("-brand").unwrap().source().contains("Firefox"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LayeredFtl {
+    pub layers: Vec<(LanguageIdentifier, FtlResource)>,
+}
+
+impl LayeredFtl {
+    /// Layer `layers`, most preferred first.
+    pub fn new<I: IntoIterator<Item = (LanguageIdentifier, FtlResource)>>(layers: I) -> Self {
+        Self {
+            layers: layers.into_iter().collect(),
+        }
+    }
+
+    /// Load the FTL file `file` of the locales in `chain` from the directory `dir`.
+    ///
+    /// For each locale, `{dir}/{locale}/{file}` is tried with the locale spelt as
+    /// [`LocaleSpelling::Tag`] (`zh-Hant-TW`) and [`LocaleSpelling::Underscored`] (`zh_Hant_TW`). Locales without a readable file are
+    /// skipped.
+    pub fn load<P: AsRef<std::path::Path>>(
+        dir: P,
+        chain: &[LanguageIdentifier],
+        file: &str,
+    ) -> Self {
+        let dir = dir.as_ref();
+        Self::new(chain.iter().filter_map(|locale| {
+            let spellings = [LocaleSpelling::Tag, LocaleSpelling::Underscored];
+            let content = crate::assets::spell_all(locale, &spellings)
+                .into_iter()
+                .find_map(|name| std::fs::read_to_string(dir.join(name).join(file)).ok())?;
+            Some((locale.clone(), FtlResource::parse(&content)))
+        }))
+    }
+
+    /// The first locale in the chain defining the message or term `id`, i.e. the locale to format
+    /// it in with the resource from [`Self::resolve`].
+    #[must_use]
+    pub fn locale_of(&self, id: &str) -> Option<&LanguageIdentifier> {
+        (self.layers.iter())
+            .find(|(_, res)| res.get(id).is_some())
+            .map(|(locale, _)| locale)
+    }
+
+    /// The message or term `id` from the first locale in the chain defining it.
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<(&LanguageIdentifier, &FtlEntry)> {
+        (self.layers.iter()).find_map(|(locale, res)| Some((locale, res.get(id)?)))
+    }
+
+    /// The resource of `locale` with the entries it lacks filled in from the locales after it in
+    /// the chain. Returns [`None`] if `locale` is not in the chain.
+    #[must_use]
+    pub fn resolve(&self, locale: &LanguageIdentifier) -> Option<FtlResource> {
+        let start = self.layers.iter().position(|(l, _)| l == locale)?;
+        let mut res = FtlResource::default();
+        for (_, layer) in self.layers.get(start..).unwrap_or_default() {
+            for entry in layer.entries() {
+                if res.get(&entry.id).is_none() {
+                    res.index.insert(entry.id.clone(), res.entries.len());
+                    res.entries.push(entry.clone());
+                }
+            }
+        }
+        Some(res)
+    }
+
+    /// The resolved resources ([`Self::resolve`]) of all locales in the chain.
+    #[must_use]
+    pub fn resolve_all(&self) -> Vec<(LanguageIdentifier, FtlResource)> {
+        (self.layers.iter())
+            .filter_map(|(locale, _)| Some((locale.clone(), self.resolve(locale)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_entries() {
+        let res = FtlResource::parse(
+            "### Resource\n\n# detached\n\nkey = { $n ->\n    [one] One\n\n   *[other] Many\n}\n\n\
+             junk line\n    .attr = junk\n-term = T\nkey = Override\n",
+        );
+        let ids: Vec<_> = res.entries().iter().map(FtlEntry::id).collect();
+        assert_eq!(ids, ["key", "-term"]);
+        assert_eq!(res.get("key").map(FtlEntry::source), Some("key = Override"));
+        assert_eq!(
+            FtlResource::parse("a = { $n ->\n    [one] One\n   *[other] Many\n}\n")
+                .get("a")
+                .map(FtlEntry::source),
+            Some("a = { $n ->\n    [one] One\n   *[other] Many\n}")
+        );
+        assert!(FtlResource::parse("1a = x\n-=x\n").entries().is_empty());
+    }
+}
@@ -0,0 +1,286 @@
+//! Selection of localized values in freedesktop.org files.
+use crate::LanguageIdentifier;
+use std::collections::HashMap;
+
+/// The `@modifier` freedesktop files use for `script`, e.g. `sr@latin`.
+pub(crate) fn script_modifier(script: &str) -> Option<&'static str> {
+    Some(match script {
+        "Latn" => "latin",
+        "Cyrl" => "cyrillic",
+        "Deva" => "devanagari",
+        "Arab" => "arabic",
+        _ => return None,
+    })
+}
+
+/// `(lang, COUNTRY, MODIFIER)` of `locale` in freedesktop spelling, or [`None`] if the locale has
+/// subtags that cannot be spelt (e.g. the script of `zh-Hant`).
+fn parts(locale: &LanguageIdentifier) -> Option<(String, Option<String>, Option<String>)> {
+    let mut variants = locale.variants();
+    let modifier = match (locale.script, variants.next()) {
+        (Some(script), None) => Some(script_modifier(script.as_str())?.to_owned()),
+        (None, Some(variant)) => Some(variant.as_str().to_ascii_lowercase()),
+        (None, None) => None,
+        (Some(_), Some(_)) => return None,
+    };
+    if variants.next().is_some() {
+        return None;
+    }
+    let region = locale.region.map(|r| r.as_str().to_owned());
+    Some((locale.language.as_str().to_owned(), region, modifier))
+}
+
+fn spell(lang: &str, country: Option<&str>, modifier: Option<&str>) -> String {
+    let country = country.map(|c| format!("_{c}")).unwrap_or_default();
+    let modifier = modifier.map(|m| format!("@{m}")).unwrap_or_default();
+    format!("{lang}{country}{modifier}")
+}
+
+/// The locale suffixes (`[…]`) to try for `chain`, most preferred first.
+///
+/// Each locale in `chain` is first tried as is (`sr_RS@latin`); the freedesktop matching rules
+/// (`lang_COUNTRY@MODIFIER`, `lang_COUNTRY`, `lang@MODIFIER`, `lang`) are only applied after the
+/// whole chain, so that the fallbacks from the solver take precedence over truncated locales.
+fn candidates(chain: &[LanguageIdentifier]) -> Vec<String> {
+    let parts: Vec<_> = chain.iter().filter_map(parts).collect();
+    let exact = (parts.iter()).map(|(l, c, m)| spell(l, c.as_deref(), m.as_deref()));
+    let truncated = parts.iter().flat_map(|(l, c, m)| {
+        let (c, m) = (c.as_deref(), m.as_deref());
+        [
+            c.and(m).map(|_| spell(l, c, m)),
+            c.map(|_| spell(l, c, None)),
+            m.map(|_| spell(l, None, m)),
+            Some(l.clone()),
+        ]
+        .into_iter()
+        .flatten()
+    });
+    let mut candidates = vec![];
+    for candidate in exact.chain(truncated) {
+        if !candidates.contains(&candidate) {
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
+
+/// Select the best localized value of `key` (e.g. `Name`) among the entries of a group of a
+/// `.desktop` file or other keyfile, where localized values have keys like `Name[de_AT]` or
+/// `Name[sr@latin]`.
+///
+/// The locales of `chain` are tried in order, then the freedesktop matching rules are applied to
+/// them (`sr_RS@latin` also matches `sr_RS`, `sr@latin` and `sr`), and finally the unlocalized
+/// `key` is used. Scripts are spelt as the modifiers `@latin`, `@cyrillic`, `@devanagari` and
+/// `@arabic`; locales with other scripts (like `zh-Hant`) are never truncated to the language.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{freedesktop::desktop_localized_value, langid};
+/// use std::collections::HashMap;
+/// let entries: HashMap<String, String> = [
+///     ("Name", "Files"),
+///     ("Name[de]", "Dateien"),
+///     ("Name[sr]", "Датотеке"),
+///     ("Name[sr@latin]", "Datoteke"),
+///     ("Name[zh_TW]", "檔案"),
+/// ]
+/// .into_iter()
+/// .map(|(k, v)| (k.to_owned(), v.to_owned()))
+/// .collect();
+/// let name = |chain: &[_]| desktop_localized_value(&entries, "Name", chain).map(String::as_str);
+/// assert_eq!(name(&[langid!("sr-Latn-RS")]), Some("Datoteke"));
+/// assert_eq!(name(&[langid!("sr-RS")]), Some("Датотеке"));
+/// assert_eq!(name(&[langid!("de-AT")]), Some("Dateien"));
+/// // the chain wins over truncating `zh-HK` to `zh`
+/// assert_eq!(name(&langid!["zh-HK", "zh-TW"]), Some("檔案"));
+/// assert_eq!(name(&[langid!("zh-Hant"), langid!("ko")]), Some("Files"));
+/// ```
+pub fn desktop_localized_value<'a, V, S: std::hash::BuildHasher>(
+    entries: &'a HashMap<String, V, S>,
+    key: &str,
+    chain: &[LanguageIdentifier],
+) -> Option<&'a V> {
+    (candidates(chain).iter())
+        .find_map(|locale| entries.get(&format!("{key}[{locale}]")))
+        .or_else(|| entries.get(key))
+}
+
+/// Error when parsing an `AppStream` file.
+#[cfg(feature = "appstream")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+}
+
+#[cfg(feature = "appstream")]
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+#[cfg(feature = "appstream")]
+impl std::error::Error for ParseError {}
+
+/// A block of an `AppStream` `<description>`.
+#[cfg(feature = "appstream")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DescriptionBlock {
+    /// A `<p>`.
+    Paragraph(String),
+    /// An `<li>` of a `<ul>` or `<ol>`.
+    ListItem(String),
+}
+
+/// An `AppStream` component, e.g. from a `.metainfo.xml` file, whose translated elements can be
+/// selected along a solved chain.
+///
+/// Translations are the elements with an `xml:lang` attribute following the untranslated element.
+/// They are matched like [`desktop_localized_value`]: the locales of the chain as is, then the
+/// freedesktop matching rules, then the untranslated element.
+///
+/// This type is gated behind the feature `appstream`.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{freedesktop::{DescriptionBlock, Metainfo}, langid};
+/// let metainfo = Metainfo::parse(r#"<?xml version="1.0" encoding="UTF-8"?>
+/// <component type="desktop-application">
+///   <id>org.example.Files</id>
+///   <name>Files</name>
+///   <name xml:lang="de">Dateien</name>
+///   <summary>Manage your files</summary>
+///   <summary xml:lang="zh_TW">管理檔案</summary>
+///   <description>
+///     <p>Browse files.</p>
+///     <p xml:lang="de">Dateien durchsuchen.</p>
+///     <ul>
+///       <li>Fast</li>
+///       <li xml:lang="de">Schnell</li>
+///     </ul>
+///   </description>
+/// </component>"#).unwrap();
+/// let chain = [langid!("de-AT"), langid!("en")];
+/// assert_eq!(metainfo.localized("name", &chain).as_deref(), Some("Dateien"));
+/// assert_eq!(metainfo.localized("summary", &chain).as_deref(), Some("Manage your files"));
+/// assert_eq!(
+///     metainfo.localized("summary", &[langid!("zh-TW")]).as_deref(),
+///     Some("管理檔案")
+/// );
+/// assert_eq!(
+///     metainfo.description(&chain),
+///     [
+///         DescriptionBlock::Paragraph("Dateien durchsuchen.".to_owned()),
+///         DescriptionBlock::ListItem("Schnell".to_owned()),
+///     ]
+/// );
+/// ```
+#[cfg(feature = "appstream")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Metainfo {
+    component: crate::xml::Element,
+}
+
+/// The `xml:lang` of `element` in freedesktop spelling, or [`None`] if it is untranslated.
+#[cfg(feature = "appstream")]
+fn xml_lang(element: &crate::xml::Element) -> Option<String> {
+    element.attr("xml:lang").map(|lang| lang.replace('-', "_"))
+}
+
+/// The best translation among `elements` for the `candidates` from [`candidates`].
+#[cfg(feature = "appstream")]
+fn best<'a>(
+    elements: &[&'a crate::xml::Element],
+    candidates: &[String],
+) -> Option<&'a crate::xml::Element> {
+    (candidates.iter())
+        .find_map(|c| elements.iter().find(|e| xml_lang(e).as_ref() == Some(c)))
+        .or_else(|| elements.iter().find(|e| xml_lang(e).is_none()))
+        .copied()
+}
+
+/// Group `elements` into an untranslated element followed by its translations.
+#[cfg(feature = "appstream")]
+fn units<'a, I: IntoIterator<Item = &'a crate::xml::Element>>(
+    elements: I,
+) -> Vec<Vec<&'a crate::xml::Element>> {
+    let mut units: Vec<Vec<_>> = vec![];
+    for element in elements {
+        match units.last_mut() {
+            Some(unit) if xml_lang(element).is_some() => unit.push(element),
+            _ => units.push(vec![element]),
+        }
+    }
+    units
+}
+
+#[cfg(feature = "appstream")]
+fn normalized_text(element: &crate::xml::Element) -> String {
+    element
+        .text()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(feature = "appstream")]
+impl Metainfo {
+    /// Parse an `AppStream` metainfo file, whose root element is `<component>`.
+    ///
+    /// # Errors
+    /// Fails if `xml` is not well-formed or its root element is not `<component>`.
+    pub fn parse(xml: &str) -> Result<Self, ParseError> {
+        let component = crate::xml::Element::parse(xml).map_err(|err| ParseError {
+            message: err.to_string(),
+        })?;
+        if component.local_name() != "component" {
+            return Err(ParseError {
+                message: format!("expected `<component>`, found `<{}>`", component.name),
+            });
+        }
+        Ok(Self { component })
+    }
+
+    /// The text of the best translation of the child element `name` of the component, such as
+    /// `name`, `summary` or `developer_name`, with whitespace collapsed.
+    #[must_use]
+    pub fn localized(&self, name: &str, chain: &[LanguageIdentifier]) -> Option<String> {
+        let elements: Vec<_> = self
+            .component
+            .elements()
+            .filter(|e| e.local_name() == name)
+            .collect();
+        best(&elements, &candidates(chain)).map(normalized_text)
+    }
+
+    /// The best translation of each paragraph and list item of the description of the
+    /// component, in order. A description translated as a whole (`<description xml:lang="…">`)
+    /// is also supported.
+    #[must_use]
+    pub fn description(&self, chain: &[LanguageIdentifier]) -> Vec<DescriptionBlock> {
+        let candidates = candidates(chain);
+        let descriptions: Vec<_> = (self.component.elements())
+            .filter(|e| e.local_name() == "description")
+            .collect();
+        let Some(description) = best(&descriptions, &candidates) else {
+            return vec![];
+        };
+        let mut blocks = vec![];
+        for unit in units(description.elements()) {
+            let Some(element) = best(&unit, &candidates) else {
+                continue;
+            };
+            match element.local_name() {
+                "p" => blocks.push(DescriptionBlock::Paragraph(normalized_text(element))),
+                "ul" | "ol" => blocks.extend(
+                    units(element.elements().filter(|e| e.local_name() == "li"))
+                        .iter()
+                        .filter_map(|item| best(item, &candidates))
+                        .map(|item| DescriptionBlock::ListItem(normalized_text(item))),
+                ),
+                _ => {}
+            }
+        }
+        blocks
+    }
+}
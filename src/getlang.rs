@@ -1,10 +1,16 @@
 //! Get started by [`system_want_langids()`].
 //!
-//! This module is gated behind the feature `getlang` (enabled by default).
+//! This module is gated behind the feature `getlang` (enabled by default). On Windows, it uses the
+//! `windows` crate for the system APIs. The feature `getlang_lite` provides this module with
+//! minimal hand-written bindings instead, which are much faster to compile; `getlang` takes
+//! precedence if both are enabled.
 
+#[cfg(unix)]
+use crate::macros::IntoLangIdAble;
 #[cfg_attr(not(test), cfg(not(windows)))]
 use itertools::Itertools;
-use std::str::FromStr;
+#[cfg(unix)]
+use std::{collections::HashMap, ffi::OsString};
 use unic_langid::LanguageIdentifier;
 
 /// Obtain a list of [`LanguageIdentifier`]s the user prefers.
@@ -54,22 +60,392 @@ pub fn system_want_langids() -> impl Iterator<Item = LanguageIdentifier> {
 /// - Windows (`cfg!(windows)`): [`windows_system_want_langids`]
 ///
 /// Note that this function is available even on Mac OS X, and is used in combination.
+///
+/// Each locale is converted by [`unix_locale_to_langid`], so aliases and legacy names are
+/// understood. For the stricter semantics of GNU gettext, see [`gettext_system_want_langids`].
 #[cfg(unix)]
 pub fn unix_system_want_langids() -> impl Iterator<Item = LanguageIdentifier> {
-    ["LC_ALL", "LC_MESSAGES", "LANG", "LANGUAGE", "LANGUAGES"]
-        .into_iter()
-        .flat_map(|env| {
-            std::env::var(env).ok().into_iter().flat_map(|locales| {
-                locales
-                    .split(':')
-                    .filter_map(|locale| LanguageIdentifier::from_str(locale).ok())
+    let env: HashMap<_, _> = std::env::vars_os().collect();
+    unix_want_langids_from(&env).into_iter()
+}
+
+/// Same as [`unix_system_want_langids`], but looking up the variables in `env`.
+///
+/// Unlike [`unix_system_want_langids`], this does not read the environment of the current
+/// process, e.g. to resolve the locales of a child process whose environment is being
+/// constructed.
+///
+/// # Examples
+/// ```
+/// # #[cfg(unix)] {
+/// use poly_l10n::{getlang::unix_want_langids_from, langid};
+/// use std::{collections::HashMap, ffi::OsString};
+/// let env: HashMap<OsString, OsString> = [("LANG", "C.UTF-8"), ("LANGUAGE", "pt_BR:de_AT")]
+///     .into_iter()
+///     .map(|(k, v)| (k.into(), v.into()))
+///     .collect();
+/// assert_eq!(unix_want_langids_from(&env), langid!["pt-BR", "de-AT"]);
+/// # }
+/// ```
+#[cfg(unix)]
+#[must_use]
+pub fn unix_want_langids_from<S: std::hash::BuildHasher>(
+    env: &HashMap<OsString, OsString, S>,
+) -> Vec<LanguageIdentifier> {
+    (UNIX_ENV_VARS.iter())
+        .filter_map(|var| env.get(std::ffi::OsStr::new(var))?.to_str())
+        .flat_map(parse_locale_list)
+        .collect()
+}
+
+/// Obtain the [`LanguageIdentifier`]s the user prefers for messages, following GNU gettext.
+///
+/// Unlike [`unix_system_want_langids`], which collects the locales of all variables, gettext only
+/// honours `LANGUAGE` when the base locale of messages (the first non-empty one of `LC_ALL`,
+/// `LC_MESSAGES` and `LANG`) is not `C` or `POSIX`:
+///
+/// - If the base locale is `C`, `POSIX` or unset, no locale is wanted (messages stay
+///   untranslated), even if `LANGUAGE` is set.
+/// - Otherwise, the locales of `LANGUAGE` are wanted if it is non-empty, else the base locale.
+///
+/// Use this so that applications behave identically to gettext-based ones on the same system.
+///
+/// This function is only available on `cfg!(unix)`.
+#[cfg(unix)]
+pub fn gettext_system_want_langids() -> impl Iterator<Item = LanguageIdentifier> {
+    let env: HashMap<_, _> = std::env::vars_os().collect();
+    gettext_want_langids_from(&env).into_iter()
+}
+
+/// Same as [`gettext_system_want_langids`], but looking up the variables in `env`.
+///
+/// # Examples
+/// ```
+/// # #[cfg(unix)] {
+/// use poly_l10n::{getlang::gettext_want_langids_from, langid};
+/// use std::{collections::HashMap, ffi::OsString};
+/// let env = |vars: &[(&str, &str)]| -> HashMap<OsString, OsString> {
+///     vars.iter().map(|(k, v)| (k.into(), v.into())).collect()
+/// };
+/// let language = ("LANGUAGE", "pt_BR:de_AT");
+/// assert_eq!(
+///     gettext_want_langids_from(&env(&[("LANG", "fr_FR.UTF-8"), language])),
+///     langid!["pt-BR", "de-AT"]
+/// );
+/// assert!(gettext_want_langids_from(&env(&[("LANG", "C"), language])).is_empty());
+/// assert!(gettext_want_langids_from(&env(&[("LC_ALL", "POSIX"), language])).is_empty());
+/// assert!(gettext_want_langids_from(&env(&[language])).is_empty());
+/// assert_eq!(
+///     gettext_want_langids_from(&env(&[("LC_MESSAGES", "fr_FR"), ("LANG", "C")])),
+///     [langid!("fr-FR")]
+/// );
+/// # }
+/// ```
+#[cfg(unix)]
+#[must_use]
+pub fn gettext_want_langids_from<S: std::hash::BuildHasher>(
+    env: &HashMap<OsString, OsString, S>,
+) -> Vec<LanguageIdentifier> {
+    let var = |name: &str| {
+        (env.get(std::ffi::OsStr::new(name)))
+            .and_then(|value| value.to_str())
+            .filter(|value| !value.is_empty())
+    };
+    let base = ["LC_ALL", "LC_MESSAGES", "LANG"].into_iter().find_map(var);
+    match base {
+        None | Some("C" | "POSIX") => vec![],
+        Some(base) => var("LANGUAGE")
+            .map(parse_locale_list)
+            .filter(|langids| !langids.is_empty())
+            .unwrap_or_else(|| parse_locale_list(base)),
+    }
+}
+
+/// Environment variables consulted by [`unix_system_want_langids`], in order.
+#[cfg(unix)]
+const UNIX_ENV_VARS: [&str; 5] = ["LC_ALL", "LC_MESSAGES", "LANG", "LANGUAGE", "LANGUAGES"];
+
+/// Parse a colon-separated list of locales, e.g. the value of `LANGUAGE` or a line of a file.
+fn parse_locale_list(locales: &str) -> Vec<LanguageIdentifier> {
+    #[cfg(unix)]
+    let parse = unix_locale_to_langid;
+    #[cfg(not(unix))]
+    let parse = |l: &str| crate::macros::IntoLangIdAble::to_langid(l).ok();
+    locales
+        .split([':', '\n'])
+        .filter(|l| !l.trim().is_empty() && !l.trim_start().starts_with('#'))
+        .filter_map(parse)
+        .collect()
+}
+
+/// The locales in the environment variable `var`, a colon-separated list.
+fn env_want_langids(var: &str) -> Vec<LanguageIdentifier> {
+    (std::env::var(var).ok().as_deref())
+        .map(parse_locale_list)
+        .unwrap_or_default()
+}
+
+/// A source of the locales the user prefers, see [`Detector`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DetectionSource {
+    /// An environment variable with a colon-separated list of locales, e.g. `LANGUAGE`.
+    Env(String),
+    /// A file with locales separated by colons or newlines, e.g. an application's configuration.
+    /// Lines starting with `#` are ignored.
+    File(std::path::PathBuf),
+    /// The `AppleLanguages` user default (macOS only).
+    #[cfg(target_os = "macos")]
+    AppleLanguages,
+    /// `GetUserPreferredUILanguages` (Windows only).
+    #[cfg(windows)]
+    UserPreferredUILanguages,
+    /// Fixed locales.
+    Fixed(Vec<LanguageIdentifier>),
+}
+
+impl DetectionSource {
+    fn detect(&self) -> Vec<LanguageIdentifier> {
+        match self {
+            Self::Env(var) => env_want_langids(var),
+            Self::File(path) => (std::fs::read_to_string(path).ok().as_deref())
+                .map(parse_locale_list)
+                .unwrap_or_default(),
+            #[cfg(target_os = "macos")]
+            Self::AppleLanguages => macos_apple_languages().collect(),
+            #[cfg(windows)]
+            Self::UserPreferredUILanguages => windows_system_want_langids().collect(),
+            Self::Fixed(langids) => langids.clone(),
+        }
+    }
+}
+
+/// Detection of the locales the user prefers from an ordered, configurable list of sources.
+///
+/// [`Self::default`] has the sources used by [`system_want_langids`] on the current platform;
+/// they can be reordered, removed or supplemented, e.g. so that a kiosk application prefers its
+/// configuration file over the environment of the login session. The locales of all sources are
+/// concatenated in order, without duplicates.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{getlang::{DetectionSource, Detector}, langid};
+/// let config = std::env::temp_dir().join("poly_l10n_detector_doctest");
+/// std::fs::write(&config, "# kiosk locale\nde_CH:fr_CH\n").unwrap();
+/// let detector = Detector::default()
+///     .prepend(DetectionSource::File(config.clone()))
+///     .with_source(DetectionSource::Fixed(vec![langid!("en")]));
+/// let langids = detector.detect();
+/// assert_eq!(langids[..2], langid!["de-CH", "fr-CH"]);
+/// assert_eq!(langids.last(), Some(&langid!("en")));
+///
+/// let only_config = Detector::new().with_source(DetectionSource::File(config.clone()));
+/// assert_eq!(only_config.detect(), langid!["de-CH", "fr-CH"]);
+/// # std::fs::remove_file(&config).unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct Detector {
+    pub sources: Vec<DetectionSource>,
+}
+
+impl Default for Detector {
+    /// The platform sources, in the order used by [`system_want_langids`].
+    fn default() -> Self {
+        let mut sources = vec![];
+        #[cfg(target_os = "macos")]
+        sources.push(DetectionSource::AppleLanguages);
+        #[cfg(unix)]
+        sources.extend(UNIX_ENV_VARS.map(|var| DetectionSource::Env(var.to_owned())));
+        #[cfg(windows)]
+        sources.push(DetectionSource::UserPreferredUILanguages);
+        Self { sources }
+    }
+}
+
+impl Detector {
+    /// A detector without sources.
+    pub const fn new() -> Self {
+        Self { sources: vec![] }
+    }
+
+    /// Add `source` with the lowest priority.
+    pub fn with_source(mut self, source: DetectionSource) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Add `source` with the highest priority.
+    pub fn prepend(mut self, source: DetectionSource) -> Self {
+        self.sources.insert(0, source);
+        self
+    }
+
+    /// Remove `source`.
+    pub fn without(mut self, source: &DetectionSource) -> Self {
+        self.sources.retain(|s| s != source);
+        self
+    }
+
+    /// The locales from all sources, in order of priority, without duplicates.
+    #[must_use]
+    pub fn detect(&self) -> Vec<LanguageIdentifier> {
+        let mut langids = vec![];
+        for langid in self.sources.iter().flat_map(DetectionSource::detect) {
+            if !langids.contains(&langid) {
+                langids.push(langid);
+            }
+        }
+        langids
+    }
+}
+
+/// Locale aliases shipped in glibc's `locale.alias` and legacy locale names, used in addition to
+/// the aliases found on the system. Codesets are omitted.
+#[cfg(unix)]
+const LOCALE_ALIASES: &[(&str, &str)] = &[
+    ("bokmal", "nb_NO"),
+    ("bokmål", "nb_NO"),
+    ("catalan", "ca_ES"),
+    ("croatian", "hr_HR"),
+    ("czech", "cs_CZ"),
+    ("danish", "da_DK"),
+    ("dansk", "da_DK"),
+    ("deutsch", "de_DE"),
+    ("dutch", "nl_NL"),
+    ("eesti", "et_EE"),
+    ("estonian", "et_EE"),
+    ("finnish", "fi_FI"),
+    ("français", "fr_FR"),
+    ("french", "fr_FR"),
+    ("galego", "gl_ES"),
+    ("galician", "gl_ES"),
+    ("german", "de_DE"),
+    ("greek", "el_GR"),
+    ("hebrew", "he_IL"),
+    ("hrvatski", "hr_HR"),
+    ("hungarian", "hu_HU"),
+    ("icelandic", "is_IS"),
+    ("italian", "it_IT"),
+    ("japanese", "ja_JP"),
+    ("korean", "ko_KR"),
+    ("lithuanian", "lt_LT"),
+    ("no_no", "nb_NO"),
+    ("no_ny", "nn_NO"),
+    ("norwegian", "nb_NO"),
+    ("nynorsk", "nn_NO"),
+    ("polish", "pl_PL"),
+    ("portuguese", "pt_PT"),
+    ("romanian", "ro_RO"),
+    ("russian", "ru_RU"),
+    ("slovak", "sk_SK"),
+    ("slovene", "sl_SI"),
+    ("slovenian", "sl_SI"),
+    ("spanish", "es_ES"),
+    ("swedish", "sv_SE"),
+    ("thai", "th_TH"),
+    ("turkish", "tr_TR"),
+];
+
+/// Locale alias files of glibc and X11.
+#[cfg(unix)]
+const LOCALE_ALIAS_PATHS: &[&str] = &[
+    "/usr/share/locale/locale.alias",
+    "/etc/locale.alias",
+    "/usr/share/X11/locale/locale.alias",
+];
+
+/// Aliases from [`LOCALE_ALIAS_PATHS`], with lowercased names.
+#[cfg(unix)]
+static SYSTEM_LOCALE_ALIASES: std::sync::LazyLock<std::collections::HashMap<String, String>> =
+    std::sync::LazyLock::new(|| {
+        (LOCALE_ALIAS_PATHS.iter())
+            .filter_map(|path| std::fs::read_to_string(path).ok())
+            .flat_map(|content| {
+                parse_locale_alias(&content)
+                    .map(|(name, locale)| (name.to_lowercase(), locale.to_owned()))
                     .collect_vec()
             })
+            .collect()
+    });
+
+/// Parse a `locale.alias` file, giving pairs of alias names and locales.
+///
+/// Each line consists of an alias and a locale separated by whitespaces. Lines starting with `#`
+/// are comments. An alias and its locale may also be separated by `:` as in X11 alias files.
+///
+/// # Examples
+/// ```
+/// # #[cfg(unix)] {
+/// let content = "# comment\nfrench\tfr_FR.ISO-8859-1\ndeutsch de_DE.ISO-8859-1\n";
+/// let aliases: Vec<_> = poly_l10n::getlang::parse_locale_alias(content).collect();
+/// assert_eq!(aliases, [("french", "fr_FR.ISO-8859-1"), ("deutsch", "de_DE.ISO-8859-1")]);
+/// # }
+/// ```
+#[cfg(unix)]
+pub fn parse_locale_alias(content: &str) -> impl Iterator<Item = (&str, &str)> {
+    content.lines().filter_map(|line| {
+        let line = line.trim();
+        if line.starts_with('#') {
+            return None;
+        }
+        let mut parts = line.split(|c: char| c.is_whitespace() || c == ':');
+        let name = parts.next()?;
+        let locale = parts.find(|p| !p.is_empty())?;
+        Some((name, locale))
+    })
+}
+
+/// Convert a locale from an environment variable (e.g. `LANG`) into a [`LanguageIdentifier`].
+///
+/// In addition to POSIX locales (`zh_TW.Big5`), aliases from the system's `locale.alias` files
+/// and common legacy names (`french`, `deutsch`, `no_NY`) are understood, and deprecated language
+/// codes (`iw_IL`) are replaced by their modern equivalents. The `C` and `POSIX` locales give
+/// [`None`] since they do not specify any language.
+///
+/// # Examples
+/// ```
+/// # #[cfg(unix)] {
+/// use poly_l10n::{getlang::unix_locale_to_langid, langid};
+/// assert_eq!(unix_locale_to_langid("zh_TW.Big5"), Some(langid!("zh-TW")));
+/// assert_eq!(unix_locale_to_langid("iw_IL"), Some(langid!("he-IL")));
+/// assert_eq!(unix_locale_to_langid("no_NY"), Some(langid!("nn-NO")));
+/// assert_eq!(unix_locale_to_langid("deutsch"), Some(langid!("de-DE")));
+/// assert_eq!(unix_locale_to_langid("C.UTF-8"), None);
+/// # }
+/// ```
+#[cfg(unix)]
+#[must_use]
+pub fn unix_locale_to_langid(locale: &str) -> Option<LanguageIdentifier> {
+    let locale = locale.trim();
+    let name = locale.split(['.', '@']).next().unwrap_or(locale);
+    if name.is_empty() || name == "C" || name == "POSIX" {
+        return None;
+    }
+    let find_alias = |key: &str| {
+        let key = key.to_lowercase();
+        (SYSTEM_LOCALE_ALIASES.get(&key).map(String::as_str)).or_else(|| {
+            (LOCALE_ALIASES.iter())
+                .find(|(alias, _)| *alias == key)
+                .map(|(_, locale)| *locale)
         })
+    };
+    let locale = find_alias(locale)
+        .or_else(|| find_alias(name))
+        .unwrap_or(locale);
+    let mut langid = locale.to_langid().ok()?;
+    crate::canonicalize::replace_deprecated_language(&mut langid);
+    Some(langid)
 }
 
 #[cfg(target_os = "macos")]
 pub fn macos_system_want_langids() -> impl Iterator<Item = LanguageIdentifier> {
+    macos_apple_languages().chain(unix_system_want_langids())
+}
+
+/// The locales in the `AppleLanguages` user default.
+#[cfg(target_os = "macos")]
+fn macos_apple_languages() -> Box<dyn Iterator<Item = LanguageIdentifier>> {
     //? https://stackoverflow.com/questions/14908180/know-currently-logged-in-users-language-in-mac-via-shell-script#comment21002995_14908268
     let res = match std::process::Command::new("defaults")
         .args(["read", "NSGlobalDomain", "AppleLanguages"])
@@ -81,50 +457,67 @@ pub fn macos_system_want_langids() -> impl Iterator<Item = LanguageIdentifier> {
         Err(err) => {
             #[cfg(feature = "tracing")]
             tracing::error!(?err, "cannot execute `defaults`");
-            return Box::new(unix_system_want_langids()) as Box<dyn Iterator<Item = _>>;
+            return Box::new(std::iter::empty());
         }
     };
-    Box::new(macos_parse_want_langids(res.stdout).chain(unix_system_want_langids()))
+    Box::new(macos_parse_want_langids(res.stdout))
 }
 
-#[cfg(target_os = "macos")]
+/// Parse the output of `defaults read NSGlobalDomain AppleLanguages`, an array in the old-style
+/// property list format like `("en-GB", "zh-Hant-TW")`, into locales.
+///
+/// Invalid entries are skipped. This is available on all platforms, e.g. for parsing output
+/// collected from a Mac elsewhere.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{getlang::macos_parse_want_langids, langid};
+/// let stdout = b"(\n    \"en-GB\",\n    \"zh-Hant-TW\",\n    ja\n)\n".to_vec();
+/// assert_eq!(
+///     macos_parse_want_langids(stdout).collect::<Vec<_>>(),
+///     langid!["en-GB", "zh-Hant-TW", "ja"]
+/// );
+/// ```
 pub fn macos_parse_want_langids(stdout: Vec<u8>) -> impl Iterator<Item = LanguageIdentifier> {
     MacSysLangidsIterator {
-        positions: stdout.iter().positions(|&b| b == b',').collect_vec(),
         stdout,
-        i: 0,
+        start: Some(0),
     }
 }
 
-#[cfg(target_os = "macos")]
+/// Iterator over the locales in the output of `defaults read`, see [`macos_parse_want_langids`].
+#[derive(Clone, Debug)]
 pub struct MacSysLangidsIterator {
     stdout: Vec<u8>,
-    positions: Vec<usize>,
-    i: usize,
+    /// Start of the next entry, or [`None`] after the last one.
+    start: Option<usize>,
 }
 
-#[cfg(target_os = "macos")]
 impl Iterator for MacSysLangidsIterator {
     type Item = LanguageIdentifier;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some(&lc) = self.positions.get(self.i) {
-            self.i += 1;
-            let lc =
-                &self.stdout[lc + 1..*self.positions.get(self.i).unwrap_or(&self.stdout.len())];
-            let lc = lc.strip_prefix(b"(").unwrap_or(lc).trim_ascii_end();
+        while let Some(start) = self.start {
+            let rest = self.stdout.get(start..).unwrap_or_default();
+            let comma = rest.iter().position(|&b| b == b',');
+            let lc = rest.get(..comma.unwrap_or(rest.len())).unwrap_or_default();
+            self.start = comma.map(|comma| start.saturating_add(comma).saturating_add(1));
+            let lc = lc.trim_ascii();
+            let lc = lc.strip_prefix(b"(").unwrap_or(lc).trim_ascii();
             let lc = lc.strip_suffix(b")").unwrap_or(lc).trim_ascii();
             let lc = lc
                 .strip_prefix(b"\"")
                 .and_then(|lc| lc.strip_suffix(b"\""))
                 .unwrap_or(lc);
+            if lc.is_empty() {
+                continue;
+            }
             match LanguageIdentifier::from_bytes(lc) {
                 Ok(l) => return Some(l),
                 #[allow(unused_variables)]
                 Err(e) => {
                     #[cfg(feature = "tracing")]
                     tracing::error!(?lc, ?e, "invalid locale (AppleLanguages)");
-                    continue;
                 }
             }
         }
@@ -134,67 +527,366 @@ impl Iterator for MacSysLangidsIterator {
 
 #[cfg(windows)]
 pub fn windows_system_want_langids() -> impl Iterator<Item = LanguageIdentifier> {
-    (get_system_locales().into_iter()).filter_map(|locale| {
-        match LanguageIdentifier::from_str(&locale) {
-            Ok(l) => return Some(l),
-            Err(_) if !cfg!(feature = "tracing") => {}
-            Err(err) => tracing::error!(?locale, ?err, "cannot convert to langid"),
-        }
-        None
+    windows_parse_locales(get_system_locales())
+}
+
+fn windows_parse_locales(locales: Vec<String>) -> impl Iterator<Item = LanguageIdentifier> {
+    locales.into_iter().filter_map(|locale| {
+        #[allow(unused_variables)]
+        locale
+            .parse()
+            .inspect_err(|err| {
+                #[cfg(feature = "tracing")]
+                tracing::error!(?locale, ?err, "cannot convert to langid");
+            })
+            .ok()
     })
 }
 
+/// Split a buffer of null-separated UTF-16 strings, as returned by MUI functions.
+fn multi_sz_strings(buffer: &[u16]) -> Vec<String> {
+    buffer
+        .split(|&c| c == 0) // split on \0
+        .filter(|s| !s.is_empty()) // skip last empty slice
+        .filter_map(|s| {
+            #[allow(unused_variables)]
+            String::from_utf16(s)
+                .inspect_err(|err| {
+                    #[cfg(feature = "tracing")]
+                    tracing::error!(?err, "cannot convert utf16");
+                })
+                .ok()
+        })
+        .collect()
+}
+
+/// Parse the buffer filled by `GetUserPreferredUILanguages` with `MUI_LANGUAGE_NAME`, i.e.
+/// null-separated UTF-16 language names, into [`LanguageIdentifier`]s.
+///
+/// This is the parsing part of [`windows_system_want_langids`], available on all platforms.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{getlang::windows_parse_want_langids, langid};
+/// let buffer: Vec<u16> = "zh-TW\0en-US\0\0".encode_utf16().collect();
+/// assert_eq!(windows_parse_want_langids(&buffer), langid!["zh-TW", "en-US"]);
+/// ```
+#[must_use]
+pub fn windows_parse_want_langids(buffer: &[u16]) -> Vec<LanguageIdentifier> {
+    windows_parse_locales(multi_sz_strings(buffer)).collect()
+}
+
 #[cfg(windows)]
 fn get_system_locales() -> Vec<String> {
+    get_mui_languages(
+        "GetUserPreferredUILanguages",
+        mui::get_user_preferred_ui_languages,
+    )
+}
+
+/// Bindings of the MUI functions, from the `windows` crate with the feature `getlang`, or
+/// hand-written otherwise. Buffers may be [`None`] to query their size, which is written to
+/// `buffer_size` in characters.
+#[cfg(windows)]
+mod mui {
+    use std::io::Result;
+
+    /// The pointer to `buffer` and its size in characters, or null if there is no buffer.
+    fn raw_buffer(buffer: Option<&mut [u16]>) -> (*mut u16, u32) {
+        buffer.map_or((std::ptr::null_mut(), 0), |buffer| {
+            let size = u32::try_from(buffer.len()).unwrap_or(u32::MAX);
+            (buffer.as_mut_ptr(), size)
+        })
+    }
+
+    /// `language` as a double-null-terminated UTF-16 multi-string.
+    fn multi_sz(language: &str) -> Vec<u16> {
+        language.encode_utf16().chain([0, 0]).collect()
+    }
+
+    #[cfg(feature = "getlang")]
+    fn pwstr(buffer: *mut u16) -> Option<windows::core::PWSTR> {
+        (!buffer.is_null()).then_some(windows::core::PWSTR(buffer))
+    }
+
+    #[cfg(feature = "getlang")]
+    pub fn get_user_preferred_ui_languages(
+        num_langs: &mut u32,
+        buffer: Option<&mut [u16]>,
+        buffer_size: &mut u32,
+    ) -> Result<()> {
+        let (buffer, size) = raw_buffer(buffer);
+        *buffer_size = size;
+        // SAFETY: `buffer` is null or valid for `buffer_size` characters
+        unsafe {
+            windows::Win32::Globalization::GetUserPreferredUILanguages(
+                windows::Win32::Globalization::MUI_LANGUAGE_NAME,
+                num_langs,
+                pwstr(buffer),
+                buffer_size,
+            )
+        }
+        .map_err(std::io::Error::other)
+    }
+
+    #[cfg(feature = "getlang")]
+    pub fn get_system_preferred_ui_languages(
+        num_langs: &mut u32,
+        buffer: Option<&mut [u16]>,
+        buffer_size: &mut u32,
+    ) -> Result<()> {
+        let (buffer, size) = raw_buffer(buffer);
+        *buffer_size = size;
+        // SAFETY: same contract as `GetUserPreferredUILanguages`
+        unsafe {
+            windows::Win32::Globalization::GetSystemPreferredUILanguages(
+                windows::Win32::Globalization::MUI_LANGUAGE_NAME,
+                num_langs,
+                pwstr(buffer),
+                buffer_size,
+            )
+        }
+        .map_err(std::io::Error::other)
+    }
+
+    #[cfg(feature = "getlang")]
+    pub fn get_ui_language_info(
+        language: &str,
+        buffer: Option<&mut [u16]>,
+        buffer_size: &mut u32,
+        attributes: &mut u32,
+    ) -> Result<()> {
+        let language = multi_sz(language);
+        let (buffer, size) = raw_buffer(buffer);
+        *buffer_size = size;
+        // SAFETY: `language` is a double-null-terminated multi-string that outlives the call, and
+        // `buffer` is null or valid for `buffer_size` characters
+        unsafe {
+            windows::Win32::Globalization::GetUILanguageInfo(
+                windows::Win32::Globalization::MUI_LANGUAGE_NAME,
+                windows::core::PCWSTR(language.as_ptr()),
+                pwstr(buffer),
+                Some(std::ptr::from_mut(buffer_size)),
+                attributes,
+            )
+        }
+        .map_err(std::io::Error::other)
+    }
+
+    #[cfg(not(feature = "getlang"))]
+    const MUI_LANGUAGE_NAME: u32 = 0x8;
+
+    #[cfg(not(feature = "getlang"))]
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetUserPreferredUILanguages(
+            flags: u32,
+            num_langs: *mut u32,
+            buffer: *mut u16,
+            buffer_size: *mut u32,
+        ) -> i32;
+        fn GetSystemPreferredUILanguages(
+            flags: u32,
+            num_langs: *mut u32,
+            buffer: *mut u16,
+            buffer_size: *mut u32,
+        ) -> i32;
+        fn GetUILanguageInfo(
+            flags: u32,
+            language: *const u16,
+            buffer: *mut u16,
+            buffer_size: *mut u32,
+            attributes: *mut u32,
+        ) -> i32;
+    }
+
+    /// Turn the `BOOL` returned by a Win32 function into a [`Result`].
+    #[cfg(not(feature = "getlang"))]
+    fn check(ret: i32) -> Result<()> {
+        if ret == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "getlang"))]
+    pub fn get_user_preferred_ui_languages(
+        num_langs: &mut u32,
+        buffer: Option<&mut [u16]>,
+        buffer_size: &mut u32,
+    ) -> Result<()> {
+        let (buffer, size) = raw_buffer(buffer);
+        *buffer_size = size;
+        // SAFETY: `buffer` is null or valid for `buffer_size` characters
+        check(unsafe {
+            GetUserPreferredUILanguages(MUI_LANGUAGE_NAME, num_langs, buffer, buffer_size)
+        })
+    }
+
+    #[cfg(not(feature = "getlang"))]
+    pub fn get_system_preferred_ui_languages(
+        num_langs: &mut u32,
+        buffer: Option<&mut [u16]>,
+        buffer_size: &mut u32,
+    ) -> Result<()> {
+        let (buffer, size) = raw_buffer(buffer);
+        *buffer_size = size;
+        // SAFETY: same contract as `GetUserPreferredUILanguages`
+        check(unsafe {
+            GetSystemPreferredUILanguages(MUI_LANGUAGE_NAME, num_langs, buffer, buffer_size)
+        })
+    }
+
+    #[cfg(not(feature = "getlang"))]
+    pub fn get_ui_language_info(
+        language: &str,
+        buffer: Option<&mut [u16]>,
+        buffer_size: &mut u32,
+        attributes: &mut u32,
+    ) -> Result<()> {
+        let language = multi_sz(language);
+        let (buffer, size) = raw_buffer(buffer);
+        *buffer_size = size;
+        // SAFETY: `language` is a double-null-terminated multi-string that outlives the call, and
+        // `buffer` is null or valid for `buffer_size` characters
+        check(unsafe {
+            GetUILanguageInfo(
+                MUI_LANGUAGE_NAME,
+                language.as_ptr(),
+                buffer,
+                buffer_size,
+                attributes,
+            )
+        })
+    }
+}
+
+/// Call an MUI function returning a multi-string of language names twice: first to get the
+/// buffer size, then to retrieve the data.
+#[cfg(windows)]
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+fn get_mui_languages<F>(api: &str, mut call: F) -> Vec<String>
+where
+    F: FnMut(&mut u32, Option<&mut [u16]>, &mut u32) -> std::io::Result<()>,
+{
     let mut num_langs = 0;
     let mut buffer_size = 0;
 
-    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
-    // SAFETY: Well we're using this API correctly :3
-    if let Err(err) = unsafe {
-        windows::Win32::Globalization::GetUserPreferredUILanguages(
-            windows::Win32::Globalization::MUI_LANGUAGE_NAME,
-            &mut num_langs,
-            None,
-            &mut buffer_size,
-        )
-    } {
+    if let Err(err) = call(&mut num_langs, None, &mut buffer_size) {
         #[cfg(feature = "tracing")]
-        tracing::error!(?err, "fail to get bufsize from GetUserPreferredUILanguages");
+        tracing::error!(?err, api, "fail to get bufsize");
         return vec![];
     }
     let mut buffer = vec![0u16; buffer_size as usize];
 
-    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
-    // SAFETY: Second call to retrieve the actual data
-    if let Err(err) = unsafe {
-        windows::Win32::Globalization::GetUserPreferredUILanguages(
-            windows::Win32::Globalization::MUI_LANGUAGE_NAME,
-            &mut num_langs,
-            Some(windows::core::PWSTR(buffer.as_mut_ptr())),
-            &mut buffer_size,
-        )
-    } {
+    if let Err(err) = call(
+        &mut num_langs,
+        Some(buffer.as_mut_slice()),
+        &mut buffer_size,
+    ) {
         #[cfg(feature = "tracing")]
-        tracing::error!(?err, "GetUserPreferredUILanguages failed");
+        tracing::error!(?err, api, "fail to get languages");
         return vec![];
     }
 
-    let locales = buffer
-        .split(|&c| c == 0) // split on \0
-        .filter(|s| !s.is_empty()) // skip last empty slice
-        .filter_map(|s| {
-            #[allow(unused_variables)]
-            String::from_utf16(s)
-                .inspect_err(|err| {
-                    #[cfg(feature = "tracing")]
-                    tracing::error!(?err, "cannot convert utf16");
-                })
-                .ok()
-        })
-        .collect();
+    multi_sz_strings(&buffer)
+}
 
-    locales
+/// The UI languages Windows falls back to for the UI language `locale`, most preferred first
+/// (`GetUILanguageInfo`, the per-language part of `GetUILanguageFallbackList`).
+///
+/// On other platforms, this returns an empty list.
+#[must_use]
+#[cfg_attr(not(windows), allow(clippy::missing_const_for_fn))]
+pub fn native_ui_fallbacks(locale: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+    #[cfg(windows)]
+    {
+        let language = locale.to_string();
+        let mut attributes = 0;
+        let fallbacks = get_mui_languages("GetUILanguageInfo", |_, buffer, buffer_size| {
+            mui::get_ui_language_info(&language, buffer, buffer_size, &mut attributes)
+        });
+        windows_parse_locales(fallbacks)
+            .filter(|l| l != locale)
+            .collect()
+    }
+    #[cfg(not(windows))]
+    {
+        _ = locale;
+        vec![]
+    }
+}
+
+/// The UI languages of the system, most preferred first (`GetSystemPreferredUILanguages`).
+///
+/// On other platforms, this returns an empty list.
+#[must_use]
+#[cfg_attr(not(windows), allow(clippy::missing_const_for_fn))]
+pub fn native_system_ui_languages() -> Vec<LanguageIdentifier> {
+    #[cfg(windows)]
+    {
+        let locales = get_mui_languages(
+            "GetSystemPreferredUILanguages",
+            mui::get_system_preferred_ui_languages,
+        );
+        windows_parse_locales(locales).collect()
+    }
+    #[cfg(not(windows))]
+    {
+        vec![]
+    }
+}
+
+/// A [`crate::LocaleSolver`] merging the fallbacks computed natively by the platform into the
+/// chain solved by `solver`, so that the result never falls short of what native applications
+/// would show.
+///
+/// The native fallbacks of the locale ([`native_ui_fallbacks`]) are appended to the solved chain,
+/// followed by the system UI languages ([`native_system_ui_languages`]) if
+/// [`Self::system_languages`] is set, skipping duplicates. Only Windows provides native
+/// fallbacks; on other platforms the chain of `solver` is returned as is.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{getlang::NativeFallbackSolver, langid, LocaleFallbackSolver, LocaleSolver};
+/// let base: LocaleFallbackSolver = Default::default();
+/// let solver = NativeFallbackSolver::new(&base);
+/// let chain = LocaleSolver::solve_locale(&solver, &langid!("pt-BR"));
+/// // the chain of `base` comes first, native fallbacks (if any) after it
+/// assert!(chain.starts_with(&base.solve_locale(langid!("pt-BR"))));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct NativeFallbackSolver<S> {
+    pub solver: S,
+    /// Whether to also append the system UI languages.
+    pub system_languages: bool,
+}
+
+impl<S: crate::LocaleSolver> NativeFallbackSolver<S> {
+    /// Merge the native fallbacks, including the system UI languages, into the chains of `solver`.
+    pub const fn new(solver: S) -> Self {
+        Self {
+            solver,
+            system_languages: true,
+        }
+    }
+}
+
+impl<S: crate::LocaleSolver> crate::LocaleSolver for NativeFallbackSolver<S> {
+    fn solve_locale(&self, locale: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+        let mut chain = self.solver.solve_locale(locale);
+        let system = if self.system_languages {
+            native_system_ui_languages()
+        } else {
+            vec![]
+        };
+        for fallback in native_ui_fallbacks(locale).into_iter().chain(system) {
+            if &fallback != locale && !chain.contains(&fallback) {
+                chain.push(fallback);
+            }
+        }
+        chain
+    }
 }
 
 #[cfg(not(unix))]
@@ -209,4 +901,13 @@ mod test {
     fn langids() {
         println!("{:?}", system_want_langids().collect_vec());
     }
+
+    #[test]
+    fn macos_parse() {
+        let parse = |s: &str| macos_parse_want_langids(s.as_bytes().to_vec()).collect_vec();
+        assert_eq!(parse("(\n    \"de-AT\"\n)\n"), [crate::langid!("de-AT")]);
+        assert_eq!(parse("(en, \"!!\", fr)"), crate::langid!["en", "fr"]);
+        assert!(parse("(\n)\n").is_empty());
+        assert!(parse("").is_empty());
+    }
 }
@@ -0,0 +1,931 @@
+//! Support for GNU gettext conventions.
+//!
+//! This module is gated behind the feature `gettext`.
+use crate::LocaleSpelling;
+use std::collections::HashMap;
+use unic_langid::LanguageIdentifier;
+
+/// Error when parsing a gettext file or header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn err<T, S: Into<String>>(message: S) -> Result<T, ParseError> {
+    Err(ParseError {
+        message: message.into(),
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BinOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl BinOp {
+    /// Operators grouped by precedence, from the lowest. Longer operators come first so that e.g.
+    /// `<=` is not parsed as `<`.
+    const LEVELS: &[&[(&str, Self)]] = &[
+        &[("||", Self::Or)],
+        &[("&&", Self::And)],
+        &[("==", Self::Eq), ("!=", Self::Ne)],
+        &[
+            ("<=", Self::Le),
+            (">=", Self::Ge),
+            ("<", Self::Lt),
+            (">", Self::Gt),
+        ],
+        &[("+", Self::Add), ("-", Self::Sub)],
+        &[("*", Self::Mul), ("/", Self::Div), ("%", Self::Rem)],
+    ];
+
+    fn apply(self, a: u64, b: u64) -> u64 {
+        match self {
+            Self::Or => u64::from(a != 0 || b != 0),
+            Self::And => u64::from(a != 0 && b != 0),
+            Self::Eq => u64::from(a == b),
+            Self::Ne => u64::from(a != b),
+            Self::Lt => u64::from(a < b),
+            Self::Le => u64::from(a <= b),
+            Self::Gt => u64::from(a > b),
+            Self::Ge => u64::from(a >= b),
+            Self::Add => a.wrapping_add(b),
+            Self::Sub => a.wrapping_sub(b),
+            Self::Mul => a.wrapping_mul(b),
+            Self::Div => a.checked_div(b).unwrap_or_default(),
+            Self::Rem => a.checked_rem(b).unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Expr {
+    N,
+    Num(u64),
+    Not(Box<Self>),
+    Bin(BinOp, Box<Self>, Box<Self>),
+    Cond(Box<Self>, Box<Self>, Box<Self>),
+}
+
+impl Expr {
+    fn eval(&self, n: u64) -> u64 {
+        match self {
+            Self::N => n,
+            Self::Num(x) => *x,
+            Self::Not(e) => u64::from(e.eval(n) == 0),
+            Self::Bin(op, a, b) => match op {
+                // short-circuit like C
+                BinOp::Or if a.eval(n) != 0 => 1,
+                BinOp::And if a.eval(n) == 0 => 0,
+                _ => op.apply(a.eval(n), b.eval(n)),
+            },
+            Self::Cond(c, a, b) if c.eval(n) != 0 => a.eval(n),
+            Self::Cond(_, _, b) => b.eval(n),
+        }
+    }
+}
+
+/// Recursive descent parser for the C-like `plural` expressions.
+struct ExprParser<'a> {
+    rest: &'a str,
+    /// Remaining nesting depth, to avoid overflowing the stack on malicious input.
+    depth: usize,
+}
+
+impl ExprParser<'_> {
+    fn eat(&mut self, token: &str) -> bool {
+        self.rest = self.rest.trim_start();
+        match self.rest.strip_prefix(token) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn cond(&mut self) -> Result<Expr, ParseError> {
+        let Some(depth) = self.depth.checked_sub(1) else {
+            return err("plural expression nested too deeply");
+        };
+        self.depth = depth;
+        let c = self.binary(0)?;
+        if !self.eat("?") {
+            self.depth = self.depth.saturating_add(1);
+            return Ok(c);
+        }
+        let a = self.cond()?;
+        if !self.eat(":") {
+            return err("expected `:` in plural expression");
+        }
+        let b = self.cond()?;
+        self.depth = self.depth.saturating_add(1);
+        Ok(Expr::Cond(Box::new(c), Box::new(a), Box::new(b)))
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Expr, ParseError> {
+        let Some(ops) = BinOp::LEVELS.get(level) else {
+            return self.unary();
+        };
+        let next = level.saturating_add(1);
+        let mut lhs = self.binary(next)?;
+        'ops: loop {
+            for (token, op) in *ops {
+                if self.eat(token) {
+                    let rhs = self.binary(next)?;
+                    lhs = Expr::Bin(*op, Box::new(lhs), Box::new(rhs));
+                    continue 'ops;
+                }
+            }
+            return Ok(lhs);
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.eat("!") {
+            // `!!!x` is the same as `!x`, so long chains need not be nested
+            let mut double = false;
+            while self.eat("!") {
+                double = !double;
+            }
+            let mut e = Expr::Not(Box::new(self.unary()?));
+            if double {
+                e = Expr::Not(Box::new(e));
+            }
+            return Ok(e);
+        }
+        if self.eat("(") {
+            let e = self.cond()?;
+            if !self.eat(")") {
+                return err("expected `)` in plural expression");
+            }
+            return Ok(e);
+        }
+        if self.eat("n") {
+            return Ok(Expr::N);
+        }
+        let digits = (self.rest)
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest.len());
+        let (num, rest) = self.rest.split_at(digits);
+        let Ok(num) = num.parse() else {
+            return err("unexpected token in plural expression");
+        };
+        self.rest = rest;
+        Ok(Expr::Num(num))
+    }
+}
+
+/// A parsed gettext `Plural-Forms` header, which selects the plural form of a message for a
+/// number.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::gettext::PluralForms;
+/// let ru: PluralForms = "nplurals=3; plural=(n%10==1 && n%100!=11 ? 0 : \
+///     n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2);"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(ru.nplurals(), 3);
+/// assert_eq!([1, 2, 5, 11, 21, 22].map(|n| ru.index(n)), [0, 1, 2, 2, 0, 1]);
+///
+/// let ja: PluralForms = "nplurals=1; plural=0;".parse().unwrap();
+/// assert_eq!(ja.index(42), 0);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PluralForms {
+    nplurals: usize,
+    plural: Expr,
+}
+
+impl Default for PluralForms {
+    /// The Germanic plural forms, `nplurals=2; plural=(n != 1);`, which gettext assumes without a
+    /// `Plural-Forms` header.
+    fn default() -> Self {
+        Self {
+            nplurals: 2,
+            plural: Expr::Bin(BinOp::Ne, Box::new(Expr::N), Box::new(Expr::Num(1))),
+        }
+    }
+}
+
+impl std::str::FromStr for PluralForms {
+    type Err = ParseError;
+
+    /// Parse the value of a `Plural-Forms` header, e.g. `nplurals=2; plural=(n != 1);`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mut nplurals, mut plural) = (None, None);
+        for field in s.split(';').map(str::trim).filter(|f| !f.is_empty()) {
+            let Some((key, value)) = field.split_once('=') else {
+                return err(format!("invalid field `{field}` in Plural-Forms"));
+            };
+            match key.trim() {
+                "nplurals" => {
+                    let Ok(n) = value.trim().parse() else {
+                        return err(format!("invalid nplurals `{value}`"));
+                    };
+                    nplurals = Some(n);
+                }
+                // bounds the size of the expression tree, which is evaluated recursively
+                "plural" if value.len() > 1024 => return err("plural expression too long"),
+                "plural" => {
+                    let mut parser = ExprParser {
+                        rest: value,
+                        depth: 64,
+                    };
+                    let expr = parser.cond()?;
+                    if !parser.rest.trim().is_empty() {
+                        return err(format!("trailing `{}` in plural expression", parser.rest));
+                    }
+                    plural = Some(expr);
+                }
+                _ => {}
+            }
+        }
+        match (nplurals, plural) {
+            (Some(0), _) => err("nplurals must be positive"),
+            (Some(nplurals), Some(plural)) => Ok(Self { nplurals, plural }),
+            _ => err("missing nplurals or plural in Plural-Forms"),
+        }
+    }
+}
+
+impl PluralForms {
+    /// Number of plural forms.
+    #[must_use]
+    pub const fn nplurals(&self) -> usize {
+        self.nplurals
+    }
+
+    /// Index of the plural form for the number `n`.
+    ///
+    /// Like gettext, out-of-range results of the `plural` expression give `0`.
+    #[must_use]
+    pub fn index(&self, n: u64) -> usize {
+        usize::try_from(self.plural.eval(n))
+            .ok()
+            .filter(|&i| i < self.nplurals)
+            .unwrap_or_default()
+    }
+}
+
+/// Separator between the context and the message ID in catalog keys, as in `.mo` files.
+const CONTEXT_SEP: char = '\u{4}';
+
+fn key(ctxt: Option<&str>, msgid: &str) -> String {
+    ctxt.map_or_else(|| msgid.to_owned(), |c| format!("{c}{CONTEXT_SEP}{msgid}"))
+}
+
+/// Translations of a single locale, e.g. loaded from a `.po` file.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::gettext::Catalog;
+/// let po = r#"
+/// msgid ""
+/// msgstr "Plural-Forms: nplurals=2; plural=(n > 1);\n"
+///
+/// msgid "Open"
+/// msgstr "Ouvrir"
+///
+/// msgctxt "menu"
+/// msgid "File"
+/// msgstr "Fichier"
+///
+/// msgid "one file"
+/// msgid_plural "%d files"
+/// msgstr[0] "%d fichier"
+/// msgstr[1] "%d fichiers"
+/// "#;
+/// let catalog = Catalog::parse_po(po).unwrap();
+/// assert_eq!(catalog.gettext("Open"), Some("Ouvrir"));
+/// assert_eq!(catalog.pgettext("menu", "File"), Some("Fichier"));
+/// assert_eq!(catalog.gettext("File"), None);
+/// assert_eq!(catalog.ngettext("one file", 0), Some("%d fichier"));
+/// assert_eq!(catalog.ngettext("one file", 2), Some("%d fichiers"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Catalog {
+    pub plural_forms: PluralForms,
+    messages: HashMap<String, Vec<String>>,
+}
+
+/// The entry being parsed in a `.po` file.
+#[derive(Default)]
+struct PoEntry {
+    fuzzy: bool,
+    msgctxt: Option<String>,
+    msgid: Option<String>,
+    msgstr: Vec<String>,
+}
+
+/// The string of a [`PoEntry`] that continuation lines are appended to.
+#[derive(Clone, Copy)]
+enum PoField {
+    Msgctxt,
+    Msgid,
+    MsgidPlural,
+    Msgstr(usize),
+}
+
+impl PoEntry {
+    fn field(&mut self, field: PoField) -> Option<&mut String> {
+        match field {
+            PoField::Msgctxt => self.msgctxt.as_mut(),
+            PoField::Msgid => self.msgid.as_mut(),
+            PoField::MsgidPlural => None,
+            PoField::Msgstr(n) => self.msgstr.get_mut(n),
+        }
+    }
+}
+
+/// Unescape a C-like string literal in a `.po` file, including the quotes.
+fn po_string(s: &str) -> Result<String, ParseError> {
+    let Some(s) = (s.trim().strip_prefix('"')).and_then(|s| s.strip_suffix('"')) else {
+        return err("expected a string");
+    };
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some(c @ ('"' | '\\')) => out.push(c),
+            _ => return err("invalid escape sequence"),
+        }
+    }
+    Ok(out)
+}
+
+impl Catalog {
+    /// Parse the content of a `.po` file.
+    ///
+    /// Fuzzy and obsolete entries are ignored, as are untranslated ones. The plural forms are read
+    /// from the `Plural-Forms` field of the header entry.
+    ///
+    /// # Errors
+    /// Returns [`ParseError`] if the file is malformed.
+    pub fn parse_po(content: &str) -> Result<Self, ParseError> {
+        let mut entries = vec![];
+        let mut entry = PoEntry::default();
+        let mut field = None;
+        // flags comments precede the entry they apply to
+        let mut fuzzy = false;
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            let at = |e: ParseError| ParseError {
+                message: format!("line {}: {}", i.saturating_add(1), e.message),
+            };
+            if line.is_empty() || line.starts_with('#') {
+                if line.starts_with("#,") && line.contains("fuzzy") {
+                    fuzzy = true;
+                }
+                continue;
+            }
+            if line.starts_with('"') {
+                let s = po_string(line).map_err(at)?;
+                let Some(last) = field else {
+                    return Err(at(ParseError {
+                        message: "unexpected string".into(),
+                    }));
+                };
+                if let Some(last) = entry.field(last) {
+                    last.push_str(&s);
+                }
+                continue;
+            }
+            let (keyword, value) = line.split_once([' ', '\t']).unwrap_or((line, ""));
+            let value = po_string(value).map_err(at)?;
+            // a new entry starts at `msgctxt`, or `msgid` if there is no context
+            if (keyword == "msgctxt" || keyword == "msgid") && entry.msgid.is_some() {
+                entries.push(std::mem::take(&mut entry));
+            }
+            if keyword == "msgctxt" || (keyword == "msgid" && entry.msgctxt.is_none()) {
+                entry.fuzzy = std::mem::take(&mut fuzzy);
+            }
+            field = Some(match keyword {
+                "msgctxt" => {
+                    entry.msgctxt = Some(value);
+                    PoField::Msgctxt
+                }
+                "msgid" => {
+                    entry.msgid = Some(value);
+                    PoField::Msgid
+                }
+                // the plural message ID is not needed for lookups
+                "msgid_plural" => PoField::MsgidPlural,
+                "msgstr" => {
+                    entry.msgstr = vec![value];
+                    PoField::Msgstr(0)
+                }
+                _ => {
+                    let Some(n) = (keyword.strip_prefix("msgstr["))
+                        .and_then(|k| k.strip_suffix(']'))
+                        .and_then(|n| n.parse::<usize>().ok())
+                        .filter(|&n| n < 256)
+                    else {
+                        return Err(at(ParseError {
+                            message: format!("unknown keyword `{keyword}`"),
+                        }));
+                    };
+                    if entry.msgstr.len() <= n {
+                        entry.msgstr.resize(n.saturating_add(1), String::new());
+                    }
+                    if let Some(slot) = entry.msgstr.get_mut(n) {
+                        *slot = value;
+                    }
+                    PoField::Msgstr(n)
+                }
+            });
+        }
+        entries.push(entry);
+
+        let mut catalog = Self::default();
+        for entry in entries {
+            let Some(msgid) = entry.msgid else { continue };
+            if msgid.is_empty() && entry.msgctxt.is_none() {
+                let header = entry.msgstr.first().map_or("", String::as_str);
+                if let Some(pf) =
+                    (header.lines()).find_map(|l| l.trim().strip_prefix("Plural-Forms:"))
+                {
+                    catalog.plural_forms = pf.parse()?;
+                }
+                continue;
+            }
+            if entry.fuzzy || entry.msgstr.iter().all(String::is_empty) {
+                continue;
+            }
+            (catalog.messages).insert(key(entry.msgctxt.as_deref(), &msgid), entry.msgstr);
+        }
+        Ok(catalog)
+    }
+
+    /// Translation of `msgid`.
+    #[must_use]
+    pub fn gettext(&self, msgid: &str) -> Option<&str> {
+        self.lookup(&key(None, msgid), 0)
+    }
+
+    /// Translation of `msgid` in the context `msgctxt`.
+    #[must_use]
+    pub fn pgettext(&self, msgctxt: &str, msgid: &str) -> Option<&str> {
+        self.lookup(&key(Some(msgctxt), msgid), 0)
+    }
+
+    /// Translation of the plural message `msgid` (the singular form) for the number `n`.
+    #[must_use]
+    pub fn ngettext(&self, msgid: &str, n: u64) -> Option<&str> {
+        self.lookup(&key(None, msgid), self.plural_forms.index(n))
+    }
+
+    /// Translation of the plural message `msgid` (the singular form) in the context `msgctxt` for
+    /// the number `n`.
+    #[must_use]
+    pub fn npgettext(&self, msgctxt: &str, msgid: &str, n: u64) -> Option<&str> {
+        self.lookup(&key(Some(msgctxt), msgid), self.plural_forms.index(n))
+    }
+
+    fn lookup(&self, key: &str, index: usize) -> Option<&str> {
+        let forms = self.messages.get(key)?;
+        forms
+            .get(index)
+            .filter(|s| !s.is_empty())
+            .map(String::as_str)
+    }
+
+    /// Number of translated messages.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+}
+
+/// The spellings of a locale tried in `.po` file names, see [`ChainedCatalog::load_po`].
+const PO_SPELLINGS: [LocaleSpelling; 3] = [
+    LocaleSpelling::Posix,
+    LocaleSpelling::Underscored,
+    LocaleSpelling::Tag,
+];
+
+/// Catalogs of the locales in a fallback chain. Each message is looked up in the catalogs in order,
+/// so later locales fill in messages missing from earlier ones.
+///
+/// # Examples
+/// ```no_run
+/// use poly_l10n::{gettext::ChainedCatalog, langid, LocaleFallbackSolver};
+/// let solver = LocaleFallbackSolver::<poly_l10n::Rulebook>::default();
+/// let mut chain = vec![langid!("pt-BR")];
+/// chain.extend(solver.solve_locale(langid!("pt-BR")));
+/// // loads e.g. `po/pt_BR.po` and `po/pt.po`
+/// let catalog = ChainedCatalog::load_po("po", &chain);
+/// println!("{}", catalog.gettext("Open").unwrap_or("Open"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ChainedCatalog {
+    pub catalogs: Vec<(LanguageIdentifier, Catalog)>,
+}
+
+impl ChainedCatalog {
+    /// Load the `.po` files of the locales in `chain` from the directory `dir`.
+    ///
+    /// For each locale, `{dir}/{locale}.po` is tried with the locale spelt as gettext names
+    /// catalogs, [`LocaleSpelling::Posix`] (`zh_TW`, `sr@latin`), then
+    /// [`LocaleSpelling::Underscored`] (`zh_Hant_TW`) and [`LocaleSpelling::Tag`] (`zh-Hant-TW`). Locales without a readable and valid `.po`
+    /// file are skipped (with an error logged if the feature `tracing` is enabled).
+    pub fn load_po<P: AsRef<std::path::Path>>(dir: P, chain: &[LanguageIdentifier]) -> Self {
+        let dir = dir.as_ref();
+        let catalogs = chain.iter().filter_map(|locale| {
+            let content = crate::assets::spell_all(locale, &PO_SPELLINGS)
+                .into_iter()
+                .find_map(|name| std::fs::read_to_string(dir.join(format!("{name}.po"))).ok())?;
+            match Catalog::parse_po(&content) {
+                Ok(catalog) => Some((locale.clone(), catalog)),
+                #[allow(unused_variables)]
+                Err(err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::error!(?dir, %locale, %err, "cannot parse .po file");
+                    None
+                }
+            }
+        });
+        Self {
+            catalogs: catalogs.collect(),
+        }
+    }
+
+    fn find<'a, F: Fn(&'a Catalog) -> Option<&'a str>>(&'a self, f: F) -> Option<&'a str> {
+        self.catalogs.iter().find_map(|(_, catalog)| f(catalog))
+    }
+
+    /// See [`Catalog::gettext`].
+    #[must_use]
+    pub fn gettext(&self, msgid: &str) -> Option<&str> {
+        self.find(|c| c.gettext(msgid))
+    }
+
+    /// See [`Catalog::pgettext`].
+    #[must_use]
+    pub fn pgettext(&self, msgctxt: &str, msgid: &str) -> Option<&str> {
+        self.find(|c| c.pgettext(msgctxt, msgid))
+    }
+
+    /// See [`Catalog::ngettext`]. The plural forms of the catalog providing the translation are
+    /// used.
+    #[must_use]
+    pub fn ngettext(&self, msgid: &str, n: u64) -> Option<&str> {
+        self.find(|c| c.ngettext(msgid, n))
+    }
+
+    /// See [`Catalog::npgettext`].
+    #[must_use]
+    pub fn npgettext(&self, msgctxt: &str, msgid: &str, n: u64) -> Option<&str> {
+        self.find(|c| c.npgettext(msgctxt, msgid, n))
+    }
+}
+
+/// Parse a locale in a gettext file or directory name, like `pt_BR` or `sr@latin`.
+fn locale_of_name(name: &str) -> Option<LanguageIdentifier> {
+    let name = name.split(['.', '@']).next()?;
+    name.replace('_', "-").parse().ok()
+}
+
+/// Several textdomains (e.g. an application and its plugins) translated with one solver.
+///
+/// Each domain has its own set of available locales. A message is looked up along the fallback
+/// chain of the current locale ([`Self::set_locale`]), skipping the locales the domain of the
+/// message does not have.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{gettext::{Catalog, Domains}, langid, LocaleFallbackSolver, Rulebook};
+/// let mut domains = Domains::new(LocaleFallbackSolver {
+///     rulebook: Rulebook::from_fn(|l| match l.to_string().as_str() {
+///         "fr-CA" => vec![langid!("fr")],
+///         _ => vec![],
+///     }),
+/// });
+/// let po = |msgstr: &str| Catalog::parse_po(&format!("msgid \"Save\"\nmsgstr \"{msgstr}\"")).unwrap();
+/// domains.add("app", langid!("fr-CA"), po("Sauvegarder"));
+/// domains.add("app", langid!("fr"), po("Enregistrer"));
+/// domains.add("plugin", langid!("fr"), po("Enregistrer (extension)"));
+/// domains.set_locale(langid!("fr-CA"));
+/// assert_eq!(domains.dgettext("app", "Save"), Some("Sauvegarder"));
+/// assert_eq!(domains.dgettext("plugin", "Save"), Some("Enregistrer (extension)"));
+/// assert_eq!(domains.chain("plugin"), [&langid!("fr")]);
+/// assert_eq!(domains.dgettext("missing", "Save"), None);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Domains<S> {
+    pub solver: S,
+    chain: Vec<LanguageIdentifier>,
+    catalogs: HashMap<String, HashMap<LanguageIdentifier, Catalog>>,
+}
+
+impl<S: crate::LocaleSolver> Domains<S> {
+    /// Create an empty set of domains, with no current locale.
+    pub fn new(solver: S) -> Self {
+        Self {
+            solver,
+            chain: vec![],
+            catalogs: HashMap::new(),
+        }
+    }
+
+    /// Set the current locale, solving its fallback chain.
+    pub fn set_locale(&mut self, locale: LanguageIdentifier) {
+        let fallbacks = self.solver.solve_locale(&locale);
+        self.chain = std::iter::once(locale).chain(fallbacks).collect();
+    }
+
+    /// Add or replace the catalog of `locale` in `domain`, making `locale` available in `domain`.
+    pub fn add<D: Into<String>>(
+        &mut self,
+        domain: D,
+        locale: LanguageIdentifier,
+        catalog: Catalog,
+    ) {
+        (self.catalogs.entry(domain.into()).or_default()).insert(locale, catalog);
+    }
+
+    /// Load the `.po` files of `domain` from `dir`, i.e. both `{dir}/{locale}.po` and
+    /// `{dir}/{locale}/LC_MESSAGES/{domain}.po`, with locales spelt like `pt_BR`.
+    ///
+    /// Files whose names are not locales are ignored, and files that cannot be parsed are skipped
+    /// with an error logged if the feature `tracing` is enabled.
+    ///
+    /// # Errors
+    /// Returns an error if `dir` cannot be read.
+    pub fn load_po<P: AsRef<std::path::Path>>(
+        &mut self,
+        domain: &str,
+        dir: P,
+    ) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let (name, path) = if path.is_dir() {
+                let po = path.join("LC_MESSAGES").join(format!("{domain}.po"));
+                (path.file_name(), po)
+            } else if path.extension().is_some_and(|ext| ext == "po") {
+                (path.file_stem(), path.clone())
+            } else {
+                continue;
+            };
+            let Some(locale) = name.and_then(|n| locale_of_name(n.to_str()?)) else {
+                continue;
+            };
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            match Catalog::parse_po(&content) {
+                Ok(catalog) => self.add(domain, locale, catalog),
+                #[allow(unused_variables)]
+                Err(err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::error!(?path, %err, "cannot parse .po file");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The locales available in `domain`.
+    pub fn available(&self, domain: &str) -> impl Iterator<Item = &LanguageIdentifier> {
+        self.catalogs
+            .get(domain)
+            .into_iter()
+            .flat_map(HashMap::keys)
+    }
+
+    /// The fallback chain of the current locale, restricted to the locales available in `domain`.
+    #[must_use]
+    pub fn chain(&self, domain: &str) -> Vec<&LanguageIdentifier> {
+        let Some(catalogs) = self.catalogs.get(domain) else {
+            return vec![];
+        };
+        self.chain
+            .iter()
+            .filter(|l| catalogs.contains_key(*l))
+            .collect()
+    }
+
+    fn find<'a, F: Fn(&'a Catalog) -> Option<&'a str>>(
+        &'a self,
+        domain: &str,
+        f: F,
+    ) -> Option<&'a str> {
+        let catalogs = self.catalogs.get(domain)?;
+        (self.chain.iter()).find_map(|locale| f(catalogs.get(locale)?))
+    }
+
+    /// Translation of `msgid` in `domain`. See [`Catalog::gettext`].
+    #[must_use]
+    pub fn dgettext(&self, domain: &str, msgid: &str) -> Option<&str> {
+        self.find(domain, |c| c.gettext(msgid))
+    }
+
+    /// See [`Self::dgettext`] and [`Catalog::pgettext`].
+    #[must_use]
+    pub fn dpgettext(&self, domain: &str, msgctxt: &str, msgid: &str) -> Option<&str> {
+        self.find(domain, |c| c.pgettext(msgctxt, msgid))
+    }
+
+    /// See [`Self::dgettext`] and [`Catalog::ngettext`].
+    #[must_use]
+    pub fn dngettext(&self, domain: &str, msgid: &str, n: u64) -> Option<&str> {
+        self.find(domain, |c| c.ngettext(msgid, n))
+    }
+
+    /// See [`Self::dgettext`] and [`Catalog::npgettext`].
+    #[must_use]
+    pub fn dnpgettext(&self, domain: &str, msgctxt: &str, msgid: &str, n: u64) -> Option<&str> {
+        self.find(domain, |c| c.npgettext(msgctxt, msgid, n))
+    }
+}
+
+/// Parse a `LINGUAS` file, the list of available translations of a gettext project.
+///
+/// Entries are separated by whitespace (usually one per line) and `#` starts a comment. Entries
+/// are locale names like `pt_BR` or `sr@latin`; invalid ones are skipped, duplicates are removed.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{gettext::parse_linguas, langid};
+/// let linguas = "# please keep this list sorted\nde fr\npt_BR\nsr@latin # Serbian (Latin)\nde\n";
+/// assert_eq!(parse_linguas(linguas), langid!["de", "fr", "pt-BR", "sr"]);
+/// ```
+#[must_use]
+pub fn parse_linguas(content: &str) -> Vec<LanguageIdentifier> {
+    let mut locales = vec![];
+    let names = (content.lines()).flat_map(|line| {
+        line.split('#')
+            .next()
+            .unwrap_or_default()
+            .split_whitespace()
+    });
+    for locale in names.filter_map(locale_of_name) {
+        if !locales.contains(&locale) {
+            locales.push(locale);
+        }
+    }
+    locales
+}
+
+/// Read the available translations of the gettext project in `dir` from its `LINGUAS` file, i.e.
+/// `{dir}/LINGUAS` or else `{dir}/po/LINGUAS`. See [`parse_linguas`].
+///
+/// # Errors
+/// Fails if neither file can be read.
+pub fn read_linguas<P: AsRef<std::path::Path>>(dir: P) -> std::io::Result<Vec<LanguageIdentifier>> {
+    let dir = dir.as_ref();
+    let content = std::fs::read_to_string(dir.join("LINGUAS"))
+        .or_else(|_| std::fs::read_to_string(dir.join("po").join("LINGUAS")))?;
+    Ok(parse_linguas(&content))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::langid;
+
+    #[test]
+    fn plural_forms() {
+        let parse = |s: &str| s.parse::<PluralForms>().unwrap();
+        let fr = parse("nplurals=2; plural=(n > 1);");
+        assert_eq!([0, 1, 2].map(|n| fr.index(n)), [0, 0, 1]);
+        let ar = parse(
+            "nplurals=6; plural=n==0 ? 0 : n==1 ? 1 : n==2 ? 2 : n%100>=3 && n%100<=10 ? 3 \
+             : n%100>=11 ? 4 : 5;",
+        );
+        assert_eq!(
+            [0, 1, 2, 5, 11, 100].map(|n| ar.index(n)),
+            [0, 1, 2, 3, 4, 5]
+        );
+        let not = parse("nplurals=2; plural=!(n == 1);");
+        assert_eq!([1, 2].map(|n| not.index(n)), [0, 1]);
+        assert_eq!(
+            PluralForms::default(),
+            parse("nplurals=2; plural=(n != 1);")
+        );
+        // out of range
+        assert_eq!(parse("nplurals=2; plural=n;").index(5), 0);
+        assert_eq!(parse("nplurals=2; plural=n/0;").index(5), 0);
+    }
+
+    #[test]
+    fn plural_forms_errors() {
+        for s in [
+            "nplurals=2;",
+            "nplurals=2; plural=(n",
+            "nplurals=x; plural=n",
+            "plural=n ? 1",
+        ] {
+            assert!(s.parse::<PluralForms>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn parse_po() {
+        let po = r#"
+msgid ""
+msgstr "Plural-Forms: nplurals=2; plural=(n != 1);\n"
+
+#, fuzzy
+msgid "Fuzzy"
+msgstr "Flou"
+
+msgid ""
+"Multi"
+"line"
+msgstr "Multi\n"
+"ligne \"x\""
+
+msgid "Untranslated"
+msgstr ""
+"#;
+        let catalog = Catalog::parse_po(po).unwrap();
+        assert_eq!(catalog.len(), 1);
+        assert_eq!(catalog.gettext("Fuzzy"), None);
+        assert_eq!(catalog.gettext("Multiline"), Some("Multi\nligne \"x\""));
+        assert_eq!(catalog.gettext("Untranslated"), None);
+        Catalog::parse_po("msgid \"a\nmsgstr \"b\"").unwrap_err();
+        Catalog::parse_po("\"orphan\"").unwrap_err();
+        Catalog::parse_po("msgfoo \"a\"").unwrap_err();
+    }
+
+    #[test]
+    fn domains_load_po() {
+        let dir = std::env::temp_dir().join("poly_l10n_domains_test");
+        std::fs::create_dir_all(dir.join("de_AT/LC_MESSAGES")).unwrap();
+        let po = |msgstr: &str| format!("msgid \"Yes\"\nmsgstr \"{msgstr}\"\n");
+        std::fs::write(dir.join("de_AT/LC_MESSAGES/app.po"), po("Jo")).unwrap();
+        std::fs::write(dir.join("de.po"), po("Ja")).unwrap();
+        std::fs::write(dir.join("README"), "").unwrap();
+        let solver = crate::LocaleFallbackSolver {
+            rulebook: crate::Rulebook::from_fn(|_| vec![crate::langid!("de")]),
+        };
+        let mut domains = Domains::new(solver);
+        domains.load_po("app", &dir).unwrap();
+        assert_eq!(domains.available("app").count(), 2);
+        domains.set_locale(crate::langid!("de-CH"));
+        assert_eq!(domains.dgettext("app", "Yes"), Some("Ja"));
+        domains.set_locale(crate::langid!("de-AT"));
+        assert_eq!(domains.dgettext("app", "Yes"), Some("Jo"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn chained_load_po() {
+        let dir = std::env::temp_dir().join("poly_l10n_chained_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let po = |msgstr: &str| format!("msgid \"Yes\"\nmsgstr \"{msgstr}\"\n");
+        std::fs::write(dir.join("sr@latin.po"), po("Da")).unwrap();
+        std::fs::write(dir.join("zh_TW.po"), po("是")).unwrap();
+        let catalog = ChainedCatalog::load_po(&dir, &langid!["sr-Latn", "zh-Hant-TW", "ja"]);
+        let locales: Vec<_> = catalog.catalogs.iter().map(|(l, _)| l.clone()).collect();
+        assert_eq!(locales, langid!["sr-Latn", "zh-Hant-TW"]);
+        assert_eq!(catalog.gettext("Yes"), Some("Da"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn read_linguas_po() {
+        let dir = std::env::temp_dir().join("poly_l10n_test_linguas");
+        std::fs::create_dir_all(dir.join("po")).unwrap();
+        std::fs::write(dir.join("po").join("LINGUAS"), "ja zh_TW\n").unwrap();
+        assert_eq!(read_linguas(&dir).unwrap(), langid!["ja", "zh-TW"]);
+        std::fs::write(dir.join("LINGUAS"), "ko").unwrap();
+        assert_eq!(read_linguas(&dir).unwrap(), [langid!("ko")]);
+        std::fs::remove_dir_all(&dir).unwrap();
+        read_linguas(&dir).unwrap_err();
+    }
+}
@@ -0,0 +1,197 @@
+//! Detection of runaway expansions, see [`ExpansionGuard`].
+use crate::LanguageIdentifier;
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+use unic_langid::subtags::Language;
+
+/// A suspicious growth of the fallback chain detected by an [`ExpansionGuard`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RunawayExpansion {
+    /// The chain grew longer than [`ExpansionGuard::with_max_chain_len`].
+    ChainTooLong {
+        /// The locale being solved.
+        locale: LanguageIdentifier,
+        /// The length of the chain when this was detected.
+        len: usize,
+    },
+    /// The chain contains more locales of the same language than
+    /// [`ExpansionGuard::with_max_near_duplicates`], e.g. a rule that keeps generating new
+    /// regions.
+    NearDuplicates {
+        /// The locale being solved.
+        locale: LanguageIdentifier,
+        /// The repeated language subtag.
+        language: Language,
+        /// Number of locales in the chain with this language subtag.
+        count: usize,
+    },
+}
+
+impl std::fmt::Display for RunawayExpansion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ChainTooLong { locale, len } => {
+                write!(f, "fallback chain of `{locale}` grew to {len} locales")
+            }
+            Self::NearDuplicates {
+                locale,
+                language,
+                count,
+            } => write!(
+                f,
+                "fallback chain of `{locale}` has {count} locales with the language `{language}`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RunawayExpansion {}
+
+type Hook = Arc<dyn Fn(&RunawayExpansion) + Send + Sync>;
+
+/// Detection of suspicious growth of fallback chains, which usually indicates a buggy custom
+/// rulebook. Used with [`crate::LocaleFallbackSolver::solve_locale_guarded`].
+///
+/// Each kind of [`RunawayExpansion`] is reported at most once per solve: it is passed to the hook
+/// set with [`Self::on_warning`] and logged as a warning if the feature `tracing` is enabled. With
+/// [`Self::with_hard_error`], solving stops at the first detection and returns it as an error.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, ExpansionGuard, FnRulebook, LanguageIdentifier, LocaleFallbackSolver};
+/// // generates a new variant for every locale, forever
+/// let rulebook = FnRulebook(|l: &LanguageIdentifier| {
+///     let n: u32 = l.variants().next().map_or(0, |v| v.as_str()[3..].parse().unwrap());
+///     let variant = format!("var{:05}", n + 1).parse().unwrap();
+///     let mut l = l.clone();
+///     l.set_variants(&[variant]);
+///     [l]
+/// });
+/// let solver = LocaleFallbackSolver { rulebook };
+/// let guard = ExpansionGuard::default().with_max_chain_len(10).with_hard_error(true);
+/// let err = solver.solve_locale_guarded(langid!("en"), &guard).unwrap_err();
+/// assert!(err.to_string().contains("grew to"));
+/// ```
+#[derive(Clone)]
+#[must_use]
+pub struct ExpansionGuard {
+    max_chain_len: usize,
+    max_near_duplicates: usize,
+    hard_error: bool,
+    hook: Option<Hook>,
+}
+
+impl Default for ExpansionGuard {
+    /// Warn about chains longer than 64 locales or with more than 32 locales of the same language.
+    fn default() -> Self {
+        Self {
+            max_chain_len: 64,
+            max_near_duplicates: 32,
+            hard_error: false,
+            hook: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for ExpansionGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExpansionGuard")
+            .field("max_chain_len", &self.max_chain_len)
+            .field("max_near_duplicates", &self.max_near_duplicates)
+            .field("hard_error", &self.hard_error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ExpansionGuard {
+    /// Report chains longer than `len` locales.
+    pub const fn with_max_chain_len(mut self, len: usize) -> Self {
+        self.max_chain_len = len;
+        self
+    }
+
+    /// Report chains with more than `count` locales sharing the same language subtag.
+    pub const fn with_max_near_duplicates(mut self, count: usize) -> Self {
+        self.max_near_duplicates = count;
+        self
+    }
+
+    /// Whether to stop solving and return an error on detection, instead of only warning.
+    pub const fn with_hard_error(mut self, hard_error: bool) -> Self {
+        self.hard_error = hard_error;
+        self
+    }
+
+    /// Call `hook` on each detection.
+    pub fn on_warning<F: Fn(&RunawayExpansion) + Send + Sync + 'static>(mut self, hook: F) -> Self {
+        self.hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Create the per-solve checker, to be called with the chain each time it grows.
+    pub(crate) fn checker<'a>(
+        &'a self,
+        locale: &'a LanguageIdentifier,
+    ) -> impl FnMut(&[LanguageIdentifier]) -> ControlFlow<RunawayExpansion> + 'a {
+        let mut detect = self.detector(locale);
+        move |chain| {
+            for runaway in detect(chain) {
+                self.report(runaway)?;
+            }
+            ControlFlow::Continue(())
+        }
+    }
+
+    /// Create the per-solve detector, to be called with the chain each time it grows. Returns
+    /// the kinds of [`RunawayExpansion`] detected for the first time.
+    pub(crate) fn detector<'a>(
+        &'a self,
+        locale: &'a LanguageIdentifier,
+    ) -> impl FnMut(&[LanguageIdentifier]) -> Vec<RunawayExpansion> + 'a {
+        let mut languages: HashMap<Language, usize> = HashMap::new();
+        let (mut too_long, mut near_duplicates) = (false, false);
+        move |chain| {
+            let mut detected = vec![];
+            let Some(last) = chain.last() else {
+                return detected;
+            };
+            let count = languages.entry(last.language).or_default();
+            *count = count.saturating_add(1);
+            if !too_long && chain.len() > self.max_chain_len {
+                too_long = true;
+                detected.push(RunawayExpansion::ChainTooLong {
+                    locale: locale.clone(),
+                    len: chain.len(),
+                });
+            }
+            if !near_duplicates && *count > self.max_near_duplicates {
+                near_duplicates = true;
+                detected.push(RunawayExpansion::NearDuplicates {
+                    locale: locale.clone(),
+                    language: last.language,
+                    count: *count,
+                });
+            }
+            detected
+        }
+    }
+
+    /// Log `runaway` and pass it to the hook.
+    pub(crate) fn warn(&self, runaway: &RunawayExpansion) {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(%runaway, "runaway fallback expansion");
+        if let Some(hook) = &self.hook {
+            hook(runaway);
+        }
+    }
+
+    fn report(&self, runaway: RunawayExpansion) -> ControlFlow<RunawayExpansion> {
+        self.warn(&runaway);
+        if self.hard_error {
+            return ControlFlow::Break(runaway);
+        }
+        ControlFlow::Continue(())
+    }
+}
@@ -0,0 +1,298 @@
+//! Conversion between [`LanguageIdentifier`]s and the slightly-off locale codes used by other
+//! software.
+use crate::macros::IntoLangIdAble;
+use unic_langid::LanguageIdentifier;
+
+/// Normalize a language tag reported by a web browser, e.g. in the `Accept-Language` header or
+/// `navigator.languages`, into a tag the solver and translation platforms agree on.
+///
+/// Besides lenient parsing (see [`crate::langid!`]), these browser quirks are handled:
+/// - Firefox on macOS reports Japanese as `ja-JP-mac`, which becomes `ja-JP`;
+/// - some browsers report Norwegian Bokmål as `no` (or `no-NO`), which becomes `nb` (`nb-NO`);
+/// - Latin American Spanish is sometimes reported as `es-XL` or `es-LA`, which becomes `es-419`;
+/// - `en-UK` becomes `en-GB`, and `sh` (Serbo-Croatian) becomes `sr-Latn`;
+/// - deprecated language codes such as `iw` are replaced by their modern equivalents.
+///
+/// Returns [`None`] for the wildcard `*` and tags that cannot be parsed.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{interop::from_browser_tag, langid};
+/// assert_eq!(from_browser_tag("ja-JP-mac"), Some(langid!("ja-JP")));
+/// assert_eq!(from_browser_tag("no-NO"), Some(langid!("nb-NO")));
+/// assert_eq!(from_browser_tag("es-XL"), Some(langid!("es-419")));
+/// assert_eq!(from_browser_tag("iw"), Some(langid!("he")));
+/// assert_eq!(from_browser_tag("zh-TW"), Some(langid!("zh-TW")));
+/// assert_eq!(from_browser_tag("*"), None);
+/// ```
+#[must_use]
+pub fn from_browser_tag(tag: &str) -> Option<LanguageIdentifier> {
+    let tag = tag.trim();
+    if tag == "*" {
+        return None;
+    }
+    // `mac` is not even a valid variant subtag
+    let tag = tag.strip_suffix("-mac").unwrap_or(tag);
+    let mut langid = tag.to_langid().ok()?;
+    if langid.language == "no" {
+        langid.language = "nb".parse().ok()?;
+    } else if langid.language == "sh" {
+        langid.language = "sr".parse().ok()?;
+        langid.script.get_or_insert("Latn".parse().ok()?);
+    }
+    crate::canonicalize::replace_deprecated_language(&mut langid);
+    let region = langid
+        .region
+        .as_ref()
+        .map(unic_langid::subtags::Region::as_str);
+    match (langid.language.as_str(), region) {
+        ("es", Some("XL" | "LA")) => langid.region = Some("419".parse().ok()?),
+        ("en", Some("UK")) => langid.region = Some("GB".parse().ok()?),
+        _ => {}
+    }
+    Some(langid)
+}
+
+/// Translation platforms and resource conventions with their own spellings of locale codes.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{interop::TranslationPlatform, langid};
+/// let zh_hant = langid!("zh-Hant");
+/// assert_eq!(TranslationPlatform::Crowdin.to_code(&zh_hant), "zh-TW");
+/// assert_eq!(TranslationPlatform::Weblate.to_code(&zh_hant), "zh_Hant");
+/// assert_eq!(TranslationPlatform::Transifex.to_code(&zh_hant), "zh_TW");
+/// assert_eq!(TranslationPlatform::Android.to_code(&langid!("pt-BR")), "pt-rBR");
+///
+/// assert_eq!(TranslationPlatform::Crowdin.from_code("sr-CS"), Some(langid!("sr-Latn")));
+/// assert_eq!(TranslationPlatform::Transifex.from_code("sr@latin"), Some(langid!("sr-Latn")));
+/// assert_eq!(TranslationPlatform::Android.from_code("values-b+sr+Latn"), Some(langid!("sr-Latn")));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TranslationPlatform {
+    /// [Crowdin](https://crowdin.com), e.g. `zh-CN`, `pt-BR`, `sr-CS`.
+    Crowdin,
+    /// [Weblate](https://weblate.org), e.g. `zh_Hans`, `pt_BR`, `nb_NO`.
+    Weblate,
+    /// [Transifex](https://www.transifex.com), e.g. `zh_CN`, `pt_BR`, `sr@latin`.
+    Transifex,
+    /// Android resource qualifiers, e.g. `pt-rBR` and `b+sr+Latn`, with or without `values-`.
+    Android,
+}
+
+impl TranslationPlatform {
+    /// Codes which do not follow the generic conventions of each platform. The
+    /// [`LanguageIdentifier`]s are in BCP-47 form.
+    const fn exceptions(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Crowdin => &[
+                ("zh-Hans", "zh-CN"),
+                ("zh-Hant", "zh-TW"),
+                ("sr-Latn", "sr-CS"),
+                ("sr-Cyrl", "sr"),
+            ],
+            Self::Weblate => &[("nb", "nb_NO")],
+            Self::Transifex => &[
+                ("zh-Hans", "zh_CN"),
+                ("zh-Hant", "zh_TW"),
+                ("sr-Latn", "sr@latin"),
+                ("sr-Latn-RS", "sr_RS@latin"),
+            ],
+            Self::Android => &[],
+        }
+    }
+
+    /// Convert a [`LanguageIdentifier`] into the code used by the platform.
+    #[must_use]
+    pub fn to_code(self, langid: &LanguageIdentifier) -> String {
+        let tag = langid.to_string();
+        if let Some((_, code)) = (self.exceptions().iter()).find(|(l, _)| *l == tag) {
+            return (*code).to_owned();
+        }
+        match self {
+            Self::Crowdin => tag,
+            Self::Weblate | Self::Transifex => tag.replace('-', "_"),
+            Self::Android if langid.script.is_some() || langid.variants().len() != 0 => {
+                format!("b+{}", tag.replace('-', "+"))
+            }
+            Self::Android => langid.region.map_or_else(
+                || langid.language.to_string(),
+                |region| format!("{}-r{region}", langid.language),
+            ),
+        }
+    }
+
+    /// Convert a code used by the platform into a [`LanguageIdentifier`].
+    ///
+    /// Codes in the generic BCP-47 or POSIX forms are also accepted, as long as they do not conflict
+    /// with the conventions of the platform.
+    #[must_use]
+    pub fn from_code(self, code: &str) -> Option<LanguageIdentifier> {
+        let code = code.trim();
+        if let Some((l, _)) = (self.exceptions().iter()).find(|(_, c)| c.eq_ignore_ascii_case(code))
+        {
+            return l.parse().ok();
+        }
+        match self {
+            Self::Crowdin | Self::Weblate => code.to_langid().ok(),
+            Self::Transifex => {
+                let (code, modifier) = code.split_once('@').unwrap_or((code, ""));
+                let mut langid = code.to_langid().ok()?;
+                match modifier {
+                    "latin" => langid.script = Some("Latn".parse().ok()?),
+                    "cyrillic" => langid.script = Some("Cyrl".parse().ok()?),
+                    _ => {}
+                }
+                Some(langid)
+            }
+            Self::Android => {
+                let code = code.strip_prefix("values-").unwrap_or(code);
+                if let Some(tag) = code.strip_prefix("b+") {
+                    return tag.replace('+', "-").to_langid().ok();
+                }
+                match code.split_once("-r") {
+                    Some((lang, region)) => format!("{lang}-{region}").to_langid().ok(),
+                    None => code.to_langid().ok(),
+                }
+            }
+        }
+    }
+}
+
+/// Legacy .NET culture names and their BCP-47 equivalents.
+const DOTNET_CULTURES: &[(&str, &str)] = &[
+    ("zh-CHS", "zh-Hans"),
+    ("zh-CHT", "zh-Hant"),
+    ("no-NO", "nb-NO"),
+    ("sr-SP-Latn", "sr-Latn-RS"),
+    ("sr-SP-Cyrl", "sr-Cyrl-RS"),
+    ("az-AZ-Latn", "az-Latn-AZ"),
+    ("az-AZ-Cyrl", "az-Cyrl-AZ"),
+    ("uz-UZ-Latn", "uz-Latn-UZ"),
+    ("uz-UZ-Cyrl", "uz-Cyrl-UZ"),
+];
+
+/// Convert a .NET culture name into a [`LanguageIdentifier`], including legacy names such as
+/// `zh-CHT` and `sr-SP-Latn`.
+///
+/// Returns [`None`] for the invariant culture (an empty name).
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{interop::from_dotnet_culture, langid};
+/// assert_eq!(from_dotnet_culture("zh-CHT"), Some(langid!("zh-Hant")));
+/// assert_eq!(from_dotnet_culture("sr-SP-Latn"), Some(langid!("sr-Latn-RS")));
+/// assert_eq!(from_dotnet_culture("en-US"), Some(langid!("en-US")));
+/// assert_eq!(from_dotnet_culture(""), None);
+/// ```
+#[must_use]
+pub fn from_dotnet_culture(name: &str) -> Option<LanguageIdentifier> {
+    let name = name.trim();
+    (DOTNET_CULTURES.iter())
+        .find(|(legacy, _)| legacy.eq_ignore_ascii_case(name))
+        .map_or(name, |(_, tag)| *tag)
+        .to_langid()
+        .ok()
+}
+
+/// Convert a [`LanguageIdentifier`] into a .NET culture name, using the legacy names (`zh-CHS`,
+/// `zh-CHT`) understood by both .NET Framework and modern .NET.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{interop::to_dotnet_culture, langid};
+/// assert_eq!(to_dotnet_culture(&langid!("zh-Hans")), "zh-CHS");
+/// assert_eq!(to_dotnet_culture(&langid!("de-AT")), "de-AT");
+/// ```
+#[must_use]
+pub fn to_dotnet_culture(langid: &LanguageIdentifier) -> String {
+    let tag = langid.to_string();
+    match tag.as_str() {
+        "zh-Hans" => "zh-CHS".to_owned(),
+        "zh-Hant" => "zh-CHT".to_owned(),
+        _ => tag,
+    }
+}
+
+/// Language codes used by Java before JDK 17 in place of the modern ones.
+const JAVA_LANGUAGES: &[(&str, &str)] = &[("iw", "he"), ("in", "id"), ("ji", "yi")];
+
+/// Convert a Java locale string (the output of `Locale.toString()`) into a [`LanguageIdentifier`].
+///
+/// The legacy language codes `iw`, `in` and `ji` are replaced by their modern equivalents, scripts
+/// in the `sr_RS_#Latn` form are understood, and the special variants of `ja_JP_JP`, `th_TH_TH` and
+/// `no_NO_NY` are handled. Extensions (after `_#` or `-u-`) other than the script are ignored.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{interop::from_java_locale, langid};
+/// assert_eq!(from_java_locale("iw_IL"), Some(langid!("he-IL")));
+/// assert_eq!(from_java_locale("in"), Some(langid!("id")));
+/// assert_eq!(from_java_locale("sr_RS_#Latn"), Some(langid!("sr-Latn-RS")));
+/// assert_eq!(from_java_locale("no_NO_NY"), Some(langid!("nn-NO")));
+/// assert_eq!(from_java_locale("ja_JP_JP_#u-ca-japanese"), Some(langid!("ja-JP")));
+/// ```
+#[must_use]
+pub fn from_java_locale(locale: &str) -> Option<LanguageIdentifier> {
+    let locale = locale.trim();
+    let (base, ext) = locale.split_once('#').unwrap_or((locale, ""));
+    let mut subtags = base.split('_');
+    let (lang, region) = (subtags.next()?, subtags.next().unwrap_or_default());
+    let variant = subtags.next().unwrap_or_default();
+    let mut langid = match (lang, region, variant) {
+        ("no", "NO", "NY") => "nn-NO".to_langid(),
+        (_, "", _) => lang.to_langid(),
+        // the variants only select the Japanese imperial calendar and Thai digits
+        ("ja", "JP", "JP") | ("th", "TH", "TH") | (_, _, "") => {
+            format!("{lang}-{region}").to_langid()
+        }
+        _ => format!("{lang}-{region}-{variant}").to_langid(),
+    }
+    .ok()?;
+    crate::canonicalize::replace_deprecated_language(&mut langid);
+    if let Some(script) = ext.split('-').next().filter(|s| s.len() == 4) {
+        langid.script = Some(script.parse().ok()?);
+    }
+    Some(langid)
+}
+
+/// Convert a [`LanguageIdentifier`] into a Java locale string in the form of `Locale.toString()`,
+/// using the legacy language codes (`iw`, `in`, `ji`) understood by all Java versions.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{interop::to_java_locale, langid};
+/// assert_eq!(to_java_locale(&langid!("he-IL")), "iw_IL");
+/// assert_eq!(to_java_locale(&langid!("sr-Latn-RS")), "sr_RS_#Latn");
+/// assert_eq!(to_java_locale(&langid!("de")), "de");
+/// ```
+#[must_use]
+pub fn to_java_locale(langid: &LanguageIdentifier) -> String {
+    let lang = langid.language.as_str();
+    let mut out = (JAVA_LANGUAGES.iter())
+        .find(|(_, modern)| *modern == lang)
+        .map_or(lang, |(legacy, _)| *legacy)
+        .to_owned();
+    let region = langid
+        .region
+        .as_ref()
+        .map(unic_langid::subtags::Region::as_str);
+    let variants = langid
+        .variants()
+        .map(unic_langid::subtags::Variant::as_str)
+        .collect::<Vec<_>>();
+    if region.is_some() || !variants.is_empty() || langid.script.is_some() {
+        out.push('_');
+        out.push_str(region.unwrap_or_default());
+    }
+    if !variants.is_empty() {
+        out.push('_');
+        out.push_str(&variants.join("_"));
+    }
+    if let Some(script) = langid.script {
+        out.push_str(if variants.is_empty() { "_#" } else { "#" });
+        out.push_str(script.as_str());
+    }
+    out
+}
@@ -0,0 +1,256 @@
+//! A minimal JSON parser for translation and rule files.
+
+/// Maximum nesting of arrays and objects.
+const MAX_DEPTH: usize = 128;
+
+/// A parsed JSON value. Numbers are kept as their source text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Self>),
+    /// Members in order of appearance.
+    Object(Vec<(String, Self)>),
+}
+
+/// Error when parsing JSON.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    /// Byte offset of the error in the input.
+    pub offset: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at byte {}: {}", self.offset, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Value {
+    /// Parse a JSON document.
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+        let mut parser = Parser { input, rest: input };
+        let value = parser.value(0)?;
+        parser.skip_ws();
+        if !parser.rest.is_empty() {
+            return parser.err("trailing characters");
+        }
+        Ok(value)
+    }
+
+    /// The value as text, if it is a string, number or boolean.
+    #[cfg_attr(not(feature = "catalog"), allow(dead_code))]
+    pub fn as_text(&self) -> Option<String> {
+        match self {
+            Self::String(s) | Self::Number(s) => Some(s.clone()),
+            Self::Bool(b) => Some(b.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Member `key` of an object.
+    #[cfg_attr(not(feature = "catalog"), allow(dead_code))]
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        let Self::Object(members) = self else {
+            return None;
+        };
+        members.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    rest: &'a str,
+}
+
+impl Parser<'_> {
+    fn err<T>(&self, message: &str) -> Result<T, Error> {
+        Err(Error {
+            offset: self.input.len().saturating_sub(self.rest.len()),
+            message: message.to_owned(),
+        })
+    }
+
+    fn skip_ws(&mut self) {
+        self.rest = self.rest.trim_start_matches([' ', '\t', '\n', '\r']);
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.rest.strip_prefix(token).is_some_and(|rest| {
+            self.rest = rest;
+            true
+        })
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, Error> {
+        if depth > MAX_DEPTH {
+            return self.err("nested too deeply");
+        }
+        self.skip_ws();
+        let depth = depth.saturating_add(1);
+        if self.eat("null") {
+            Ok(Value::Null)
+        } else if self.eat("true") {
+            Ok(Value::Bool(true))
+        } else if self.eat("false") {
+            Ok(Value::Bool(false))
+        } else if self.rest.starts_with('"') {
+            self.string().map(Value::String)
+        } else if self.eat("[") {
+            let mut items = vec![];
+            self.skip_ws();
+            if self.eat("]") {
+                return Ok(Value::Array(items));
+            }
+            loop {
+                items.push(self.value(depth)?);
+                self.skip_ws();
+                if self.eat("]") {
+                    return Ok(Value::Array(items));
+                }
+                if !self.eat(",") {
+                    return self.err("expected `,` or `]`");
+                }
+            }
+        } else if self.eat("{") {
+            let mut members = vec![];
+            self.skip_ws();
+            if self.eat("}") {
+                return Ok(Value::Object(members));
+            }
+            loop {
+                self.skip_ws();
+                let key = self.string()?;
+                self.skip_ws();
+                if !self.eat(":") {
+                    return self.err("expected `:`");
+                }
+                members.push((key, self.value(depth)?));
+                self.skip_ws();
+                if self.eat("}") {
+                    return Ok(Value::Object(members));
+                }
+                if !self.eat(",") {
+                    return self.err("expected `,` or `}`");
+                }
+            }
+        } else {
+            self.number()
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, Error> {
+        let end = (self.rest)
+            .find(|c: char| !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E'))
+            .unwrap_or(self.rest.len());
+        let (number, rest) = self.rest.split_at(end);
+        if number.is_empty() || number.starts_with('+') || number.parse::<f64>().is_err() {
+            return self.err("expected a value");
+        }
+        self.rest = rest;
+        Ok(Value::Number(number.to_owned()))
+    }
+
+    fn hex4(&mut self) -> Result<u32, Error> {
+        let Some(hex) = self.rest.get(..4) else {
+            return self.err("invalid unicode escape");
+        };
+        let Ok(n) = u32::from_str_radix(hex, 16) else {
+            return self.err("invalid unicode escape");
+        };
+        self.rest = self.rest.get(4..).unwrap_or_default();
+        Ok(n)
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        if !self.eat("\"") {
+            return self.err("expected a string");
+        }
+        let mut out = String::new();
+        loop {
+            let end = (self.rest)
+                .find(|c: char| c == '"' || c == '\\' || c < ' ')
+                .unwrap_or(self.rest.len());
+            let (text, rest) = self.rest.split_at(end);
+            out.push_str(text);
+            self.rest = rest;
+            if self.eat("\"") {
+                return Ok(out);
+            }
+            if !self.eat("\\") {
+                return self.err("unterminated string");
+            }
+            let mut chars = self.rest.chars();
+            let escape = chars.next();
+            self.rest = chars.as_str();
+            match escape {
+                Some('"') => out.push('"'),
+                Some('\\') => out.push('\\'),
+                Some('/') => out.push('/'),
+                Some('b') => out.push('\u{8}'),
+                Some('f') => out.push('\u{c}'),
+                Some('n') => out.push('\n'),
+                Some('r') => out.push('\r'),
+                Some('t') => out.push('\t'),
+                Some('u') => {
+                    let mut c = self.hex4()?;
+                    if (0xd800..0xdc00).contains(&c) && self.eat("\\u") {
+                        let low = self.hex4()?;
+                        if !(0xdc00..0xe000).contains(&low) {
+                            return self.err("invalid surrogate pair");
+                        }
+                        c = 0x10000 | ((c & 0x3ff) << 10) | (low & 0x3ff);
+                    }
+                    let Some(c) = char::from_u32(c) else {
+                        return self.err("invalid unicode escape");
+                    };
+                    out.push(c);
+                }
+                _ => return self.err("invalid escape sequence"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let value = Value::parse(
+            r#" {"a": [1, -2.5e3, true, null], "b": {"c": "x\"\u00e9\ud83d\ude00\n"}} "#,
+        )
+        .unwrap();
+        assert_eq!(
+            value.get("a"),
+            Some(&Value::Array(vec![
+                Value::Number("1".into()),
+                Value::Number("-2.5e3".into()),
+                Value::Bool(true),
+                Value::Null,
+            ]))
+        );
+        let c = value.get("b").and_then(|b| b.get("c"));
+        assert_eq!(c, Some(&Value::String("x\"é😀\n".into())));
+        for bad in [
+            "",
+            "{",
+            "[1,]",
+            "{\"a\" 1}",
+            "\"\\x\"",
+            "01a",
+            "[] []",
+            "+1",
+        ] {
+            Value::parse(bad).unwrap_err();
+        }
+        Value::parse(&"[".repeat(1000)).unwrap_err();
+    }
+}
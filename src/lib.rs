@@ -27,19 +27,90 @@
 //!    You should have received a copy of the GNU General Public License
 //!    along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+mod assets;
+mod builder;
+mod cache;
+pub mod canonicalize;
+#[cfg(feature = "catalog")]
+pub mod catalog;
+#[cfg(feature = "persist")]
+mod choice;
+#[cfg(feature = "config")]
+mod config;
 mod default_rulebook;
-#[cfg(feature = "getlang")]
+#[cfg(any(feature = "user_rules", feature = "persist"))]
+mod dirs;
+#[cfg(feature = "display_names")]
+pub mod display_names;
+mod explain;
+#[cfg(feature = "fluent")]
+pub mod fluent;
+pub mod freedesktop;
+#[cfg(feature = "getlang_lite")]
 pub mod getlang;
+#[cfg(feature = "gettext")]
+pub mod gettext;
+mod guard;
+pub mod interop;
+#[cfg(any(feature = "catalog", feature = "config"))]
+mod json;
+mod lint;
+mod locale;
 pub mod macros;
+mod named;
+pub mod negotiate;
+#[cfg(feature = "parallel")]
+mod parallel;
 #[cfg(feature = "per_lang_default_rules")]
 pub mod per_lang_default_rules;
+#[cfg(feature = "profile")]
+mod profile;
+mod report;
+mod resolver;
+mod rule;
+mod rule_state;
+mod rulebooks;
+#[cfg(any(feature = "user_rules", feature = "watch", feature = "config"))]
+mod rules_file;
+mod solve;
+#[cfg(feature = "watch")]
+mod watched;
+mod weighted;
+#[cfg(any(feature = "catalog", feature = "appstream"))]
+mod xml;
 
 use std::{rc::Rc, sync::Arc};
 
-#[cfg(feature = "getlang")]
-pub use getlang::system_want_langids;
+pub use assets::{
+    LocaleSpelling, format_as, pick_localized_dir, pick_localized_dir_with, resolve_asset,
+};
+pub use builder::{ARulebookBuilder, RulebookBuilder};
+pub use cache::CachedSolver;
+#[cfg(feature = "persist")]
+pub use choice::ChoiceStore;
+#[cfg(feature = "config")]
+pub use config::ConfigError;
+pub use explain::{Derivation, Explanation};
+#[cfg(feature = "getlang_lite")]
+pub use getlang::{DetectionSource, Detector, NativeFallbackSolver, system_want_langids};
+pub use guard::{ExpansionGuard, RunawayExpansion};
+pub use isolang;
 use itertools::Itertools;
+pub use lint::RuleDiagnostic;
+pub use locale::{ExtendedLocale, Extensions, Locale, RegionOverride, parse_lossy};
+pub use named::{NamedRule, RuleSwitches};
+#[cfg(feature = "profile")]
+pub use profile::{RuleProfiler, RuleStats};
+pub use report::SolveReport;
+pub use resolver::{LOCALE_ENV_VAR, LocaleResolver, LocaleSource};
+pub use rule::{CustomRule, Rule};
+pub use rule_state::RuleState;
+pub use rulebooks::*;
+pub use solve::{BoundedChain, FallbackChain, SolveIter, SolverOptions};
 pub use unic_langid::{self, LanguageIdentifier};
+#[cfg(feature = "watch")]
+pub use watched::WatchedRulebook;
+pub use weighted::RuleWeights;
 
 /// Entry point of `poly_l10n`.
 ///
@@ -53,7 +124,7 @@ pub use unic_langid::{self, LanguageIdentifier};
 /// assert_eq!(solver.solve_locale(poly_l10n::langid!("arb")), poly_l10n::langid!["arb", "ar-AE", "ara-AE", "arb-AE", "ar", "ara"]);
 /// ```
 #[derive(Clone, Copy, Debug, Default)]
-pub struct LocaleFallbackSolver<R: for<'a> PolyL10nRulebook<'a> = ARulebook> {
+pub struct LocaleFallbackSolver<R = ARulebook> {
     pub rulebook: R,
 }
 
@@ -61,41 +132,675 @@ impl<R: for<'a> PolyL10nRulebook<'a>> LocaleFallbackSolver<R> {
     /// Find alternative fallbacks for the given `locale` as specified by the `rulebook`. This
     /// operation is recursive and expensive.
     ///
+    /// The work done is bounded by [`SOLVE_MAX_LOCALES`] and related limits.
+    ///
+    /// With the feature `tracing`, each call is wrapped in a `solve_locale` span at the `DEBUG`
+    /// level, recording the input locale, the type of the rulebook, the length of the resulting
+    /// chain and the time spent.
+    ///
     /// ```
     /// let solver = poly_l10n::LocaleFallbackSolver::<poly_l10n::Rulebook>::default();
     /// # #[cfg(feature = "per_lang_default_rules")]
     /// assert_eq!(solver.solve_locale(poly_l10n::langid!("arb")), poly_l10n::langid!["arb", "ar-AE", "ara-AE", "arb-AE", "ar", "ara"]);
     /// ```
-    pub fn solve_locale<L: AsRef<LanguageIdentifier>>(&self, locale: L) -> Vec<LanguageIdentifier> {
-        use std::hash::{Hash, Hasher};
+    pub fn solve_locale<L: AsRef<LanguageIdentifier>>(&self, locale: L) -> FallbackChain {
+        FallbackChain(self.solve_locale_with_context(locale, &()))
+    }
+
+    /// Same as [`Self::solve_locale`], but bounded by `options`, e.g. to limit how deep custom
+    /// rulebooks with long chains are followed. See [`SolverOptions`].
+    pub fn solve_locale_with_options<L: AsRef<LanguageIdentifier>>(
+        &self,
+        locale: L,
+        options: &SolverOptions,
+    ) -> Vec<LanguageIdentifier> {
+        SolveIter::new(&self.rulebook, locale.as_ref(), &())
+            .with_options(*options)
+            .collect()
+    }
+
+    /// Same as [`Self::solve_locale_with_options`], but also tells whether the budget of
+    /// `options` ([`SolverOptions::with_max_results`] and [`SolverOptions::with_time_budget`]) or
+    /// the hard limits stopped solving, so that the chain is incomplete.
+    ///
+    /// ```
+    /// use poly_l10n::{langid, LocaleFallbackSolver, SolverOptions};
+    /// let solver = LocaleFallbackSolver::from_fn(|l| match l.to_string().as_str() {
+    ///     "de-AT" => vec![langid!("de-DE"), langid!("de")],
+    ///     _ => vec![],
+    /// });
+    /// let options = SolverOptions::default().with_max_results(1);
+    /// let bounded = solver.solve_locale_bounded(langid!("de-AT"), &options);
+    /// assert_eq!(bounded.chain, [langid!("de-DE")]);
+    /// assert!(bounded.truncated);
+    /// let options = SolverOptions::default().with_max_results(2);
+    /// assert!(!solver.solve_locale_bounded(langid!("de-AT"), &options).truncated);
+    /// ```
+    pub fn solve_locale_bounded<L: AsRef<LanguageIdentifier>>(
+        &self,
+        locale: L,
+        options: &SolverOptions,
+    ) -> BoundedChain {
+        let mut iter = SolveIter::new(&self.rulebook, locale.as_ref(), &()).with_options(*options);
+        let chain = iter.by_ref().collect();
+        BoundedChain {
+            chain,
+            truncated: iter.is_truncated(),
+        }
+    }
+
+    /// Same as [`Self::solve_locale`], but the chain starts with `locale` itself, so that it can
+    /// be used for lookups directly.
+    ///
+    /// `locale` is not repeated if the rulebook gives it as a fallback of itself.
+    ///
+    /// ```
+    /// use poly_l10n::{langid, LocaleFallbackSolver, Rulebook};
+    /// let solver = LocaleFallbackSolver {
+    ///     rulebook: Rulebook::from_fn(|l| match l.to_string().as_str() {
+    ///         "de-AT" => vec![langid!("de-AT"), langid!("de")],
+    ///         _ => vec![],
+    ///     }),
+    /// };
+    /// assert_eq!(solver.solve_locale_with_self(langid!("de-AT")), langid!["de-AT", "de"]);
+    /// assert_eq!(solver.solve_locale_with_self(langid!("fr")), [langid!("fr")]);
+    /// ```
+    pub fn solve_locale_with_self<L: AsRef<LanguageIdentifier>>(
+        &self,
+        locale: L,
+    ) -> Vec<LanguageIdentifier> {
+        let locale = locale.as_ref();
+        std::iter::once(locale.clone())
+            .chain(
+                self.solve_locale(locale)
+                    .into_iter()
+                    .filter(|l| l != locale),
+            )
+            .collect()
+    }
+
+    /// Merge the chains of a list of preferred locales (see [`Self::solve_locale_with_self`]),
+    /// e.g. from `system_want_langids()`, into one chain without duplicates.
+    ///
+    /// All fallbacks of a locale come before those of the less preferred locales, and a locale
+    /// preferred explicitly is never pushed back by being a fallback of an earlier one. See
+    /// [`Self::solve_locales_with`] for other orders.
+    ///
+    /// ```
+    /// use poly_l10n::{langid, LocaleFallbackSolver};
+    /// let solver = LocaleFallbackSolver::from_fn(|l| {
+    ///     l.region.map(|_| langid!(l.language.as_str())).into_iter().collect::<Vec<_>>()
+    /// });
+    /// assert_eq!(
+    ///     solver.solve_locales(langid!["en-GB", "fr-FR", "en-US"]),
+    ///     langid!["en-GB", "en", "fr-FR", "fr", "en-US"],
+    /// );
+    /// ```
+    pub fn solve_locales<I>(&self, locales: I) -> FallbackChain
+    where
+        I: IntoIterator<Item: AsRef<LanguageIdentifier>>,
+    {
+        self.solve_locales_with(locales, MergePolicy::FirstWins)
+    }
+
+    /// Same as [`Self::solve_locales`], but the chains are merged with `policy`, e.g.
+    /// [`MergePolicy::Interleave`] to try the closest fallback of each preferred locale first.
+    ///
+    /// ```
+    /// use poly_l10n::{langid, LocaleFallbackSolver, MergePolicy};
+    /// let solver = LocaleFallbackSolver::from_fn(|l| {
+    ///     l.region.map(|_| langid!(l.language.as_str())).into_iter().collect::<Vec<_>>()
+    /// });
+    /// assert_eq!(
+    ///     solver.solve_locales_with(langid!["en-GB", "fr-FR"], MergePolicy::Interleave),
+    ///     langid!["en-GB", "fr-FR", "en", "fr"],
+    /// );
+    /// ```
+    pub fn solve_locales_with<I>(&self, locales: I, policy: MergePolicy) -> FallbackChain
+    where
+        I: IntoIterator<Item: AsRef<LanguageIdentifier>>,
+    {
+        let locales = locales.into_iter().collect_vec();
+        let chains = locales.iter().enumerate().map(|(i, locale)| {
+            let later = locales.get(i.saturating_add(1)..).unwrap_or_default();
+            let mut chain = self.solve_locale_with_self(locale);
+            // keep the less preferred locales at their own position
+            chain.retain(|l| l == locale.as_ref() || !later.iter().any(|p| p.as_ref() == l));
+            chain
+        });
+        policy.merge(chains).into()
+    }
+
+    /// Same as [`Self::solve_locale`], but the fallbacks are written to `buf`, which is cleared
+    /// first, so that its allocation can be reused across calls, e.g. per HTTP request.
+    ///
+    /// ```
+    /// use poly_l10n::{langid, LocaleFallbackSolver, Rulebook};
+    /// let solver = LocaleFallbackSolver {
+    ///     rulebook: Rulebook::from_fn(|l| match l.to_string().as_str() {
+    ///         "de-AT" => vec![langid!("de")],
+    ///         "fr-CA" => vec![langid!("fr")],
+    ///         _ => vec![],
+    ///     }),
+    /// };
+    /// let mut buf = vec![];
+    /// solver.solve_locale_into(langid!("de-AT"), &mut buf);
+    /// assert_eq!(buf, [langid!("de")]);
+    /// solver.solve_locale_into(langid!("fr-CA"), &mut buf);
+    /// assert_eq!(buf, [langid!("fr")]);
+    /// ```
+    pub fn solve_locale_into<L: AsRef<LanguageIdentifier>>(
+        &self,
+        locale: L,
+        buf: &mut Vec<LanguageIdentifier>,
+    ) {
+        solve_into_with(&self.rulebook, locale.as_ref(), &(), buf, |_| {
+            std::ops::ControlFlow::<std::convert::Infallible>::Continue(())
+        });
+    }
+
+    /// Same as [`Self::solve_locale`], but the fallbacks are found lazily, expanding the rulebook
+    /// breadth-first only as far as the iterator is consumed.
+    ///
+    /// This is cheaper when only the first suitable fallback is needed, e.g. the first one with a
+    /// translation.
+    ///
+    /// ```
+    /// use poly_l10n::{langid, LocaleFallbackSolver, Rulebook};
+    /// let solver = LocaleFallbackSolver {
+    ///     rulebook: Rulebook::from_fn(|l| match l.to_string().as_str() {
+    ///         "de-AT" => vec![langid!("de-DE"), langid!("de")],
+    ///         "de" => unreachable!("not expanded"),
+    ///         _ => vec![],
+    ///     }),
+    /// };
+    /// let mut fallbacks = solver.solve_locale_iter(langid!("de-AT"));
+    /// assert_eq!(fallbacks.find(|l| l.region.is_none()), Some(langid!("de")));
+    /// ```
+    pub fn solve_locale_iter<L: AsRef<LanguageIdentifier>>(&self, locale: L) -> SolveIter<'_, R> {
+        SolveIter::new(&self.rulebook, locale.as_ref(), &())
+    }
+
+    /// `locale` itself or else its first fallback satisfying `predicate`, expanding the rulebook
+    /// only as far as needed. See [`Self::solve_locale_iter`].
+    ///
+    /// ```
+    /// use poly_l10n::{langid, LocaleFallbackSolver};
+    /// let solver = LocaleFallbackSolver::from_fn(|l| match l.to_string().as_str() {
+    ///     "de-AT" => vec![langid!("de-DE"), langid!("de")],
+    ///     "de-DE" => unreachable!("not expanded"),
+    ///     _ => vec![],
+    /// });
+    /// let available = langid!["en", "de"];
+    /// let found = solver.solve_until(langid!("de-AT"), |l| available.contains(l));
+    /// assert_eq!(found, Some(langid!("de")));
+    /// let found = solver.solve_until(langid!("de"), |l| available.contains(l));
+    /// assert_eq!(found, Some(langid!("de")));
+    /// ```
+    pub fn solve_until<L, P>(&self, locale: L, mut predicate: P) -> Option<LanguageIdentifier>
+    where
+        L: AsRef<LanguageIdentifier>,
+        P: FnMut(&LanguageIdentifier) -> bool,
+    {
         let locale = locale.as_ref();
-        let mut locales = self.rulebook.find_fallback_locale(locale).collect_vec();
-        let h = |l: &LanguageIdentifier| {
-            let mut hasher = std::hash::DefaultHasher::default();
-            l.hash(&mut hasher);
-            hasher.finish()
+        if predicate(locale) {
+            return Some(locale.clone());
+        }
+        self.solve_locale_iter(locale).find(|l| predicate(l))
+    }
+
+    /// The locale in `available` that is `locale` itself or else its earliest fallback, if any.
+    /// Same as [`Self::solve_until`] with `available.contains()`, but returns the element of
+    /// `available`.
+    ///
+    /// ```
+    /// use poly_l10n::{langid, LocaleFallbackSolver};
+    /// let solver = LocaleFallbackSolver::from_fn(|l| match l.to_string().as_str() {
+    ///     "de-AT" => vec![langid!("de-DE"), langid!("de")],
+    ///     _ => vec![],
+    /// });
+    /// let available = langid!["en", "de"];
+    /// assert_eq!(solver.first_available(langid!("de-AT"), &available), Some(&langid!("de")));
+    /// assert_eq!(solver.first_available(langid!("fr"), &available), None);
+    /// let available = langid!["de-AT", "de"];
+    /// assert_eq!(solver.first_available(langid!("de-AT"), &available), Some(&langid!("de-AT")));
+    /// ```
+    pub fn first_available<'a, L, A>(&self, locale: L, available: &'a [A]) -> Option<&'a A>
+    where
+        L: AsRef<LanguageIdentifier>,
+        A: AsRef<LanguageIdentifier>,
+    {
+        let locale = locale.as_ref();
+        let find = |locale: &LanguageIdentifier| available.iter().find(|a| a.as_ref() == locale);
+        find(locale).or_else(|| self.solve_locale_iter(locale).find_map(|l| find(&l)))
+    }
+
+    /// Find alternative fallbacks for the given `locale` as specified by the `rulebook`, keeping
+    /// the [`Extensions`] of `locale` on each fallback.
+    ///
+    /// ```
+    /// use poly_l10n::{langid, ExtendedLocale, FnRulebook, LocaleFallbackSolver};
+    /// let solver = LocaleFallbackSolver { rulebook: FnRulebook(|_: &_| [langid!("en")]) };
+    /// let locale: ExtendedLocale = "en-GB-u-ca-buddhist".parse().unwrap();
+    /// let fallbacks = solver.solve_locale_with_extensions(&locale);
+    /// assert_eq!(fallbacks[0].to_string(), "en-u-ca-buddhist");
+    /// ```
+    ///
+    /// If `locale` has a region override (`-u-rg-`, see [`Extensions::region_override`]), the
+    /// rulebook is given a [`RegionOverride`] context, and the chain is followed by the locale
+    /// with the region replaced by the override and its own fallbacks.
+    ///
+    /// ```
+    /// use poly_l10n::{langid, ExtendedLocale, LocaleFallbackSolver, Rulebook};
+    /// let solver = LocaleFallbackSolver {
+    ///     rulebook: Rulebook::from_fn(|l| match l.to_string().as_str() {
+    ///         "en-GB" => vec![langid!("en-150")],
+    ///         "en-US" => vec![langid!("en-Latn-US")],
+    ///         _ => vec![],
+    ///     }),
+    /// };
+    /// let locale: ExtendedLocale = "en-GB-u-rg-uszzzz".parse().unwrap();
+    /// let chain: Vec<_> = (solver.solve_locale_with_extensions(&locale).into_iter())
+    ///     .map(|l| l.id)
+    ///     .collect();
+    /// assert_eq!(chain, langid!["en-150", "en-US", "en-Latn-US"]);
+    /// ```
+    pub fn solve_locale_with_extensions(&self, locale: &ExtendedLocale) -> Vec<ExtendedLocale>
+    where
+        R: for<'a> PolyL10nRulebook<'a, RegionOverride>,
+    {
+        let chain = match locale.extensions.region_override() {
+            Some(region) if locale.id.region != Some(region) => {
+                let ctx = RegionOverride(region);
+                let mut overridden = locale.id.clone();
+                overridden.region = Some(region);
+                let mut chain = self.solve_locale_with_context(&locale.id, &ctx);
+                for id in std::iter::once(overridden.clone())
+                    .chain(self.solve_locale_with_context(&overridden, &ctx))
+                {
+                    if id != locale.id && !chain.contains(&id) {
+                        chain.push(id);
+                    }
+                }
+                chain
+            }
+            Some(region) => self.solve_locale_with_context(&locale.id, &RegionOverride(region)),
+            None => self.solve_locale(&locale.id).0,
+        };
+        (chain.into_iter())
+            .map(|id| ExtendedLocale {
+                id,
+                extensions: locale.extensions.clone(),
+            })
+            .collect()
+    }
+
+    /// Same as [`Self::solve_locale_with_extensions`], but a locale with a subdivision (`-u-sd-`,
+    /// see [`Extensions::subdivision`]) first falls back to its parent region without the
+    /// subdivision, e.g. `es-ES-u-sd-escn` (Spanish of the Canary Islands) to `es-ES`, and
+    /// `ca-u-sd-esvc` (Catalan of the Valencian Community) to `ca-ES`.
+    ///
+    /// ```
+    /// use poly_l10n::{langid, ExtendedLocale, LocaleFallbackSolver, Rulebook};
+    /// let solver = LocaleFallbackSolver {
+    ///     rulebook: Rulebook::from_fn(|l| match l.to_string().as_str() {
+    ///         "es-ES" => vec![langid!("es")],
+    ///         _ => vec![],
+    ///     }),
+    /// };
+    /// let locale: ExtendedLocale = "es-ES-u-nu-latn-sd-escn".parse().unwrap();
+    /// let chain: Vec<_> = (solver.solve_locale_with_subdivisions(&locale).iter())
+    ///     .map(ToString::to_string)
+    ///     .collect();
+    /// assert_eq!(chain, ["es-ES-u-nu-latn", "es-u-nu-latn"]);
+    /// ```
+    pub fn solve_locale_with_subdivisions(&self, locale: &ExtendedLocale) -> Vec<ExtendedLocale>
+    where
+        R: for<'a> PolyL10nRulebook<'a, RegionOverride>,
+    {
+        let Some((_, region)) = locale.extensions.subdivision() else {
+            return self.solve_locale_with_extensions(locale);
+        };
+        let mut parent = ExtendedLocale {
+            id: locale.id.clone(),
+            extensions: locale.extensions.without_unicode_keyword("sd"),
         };
-        let mut locale_hashes = locales.iter().map(h).collect_vec();
-        let mut old_len = 0;
-        while old_len != locales.len() {
-            #[allow(clippy::indexing_slicing)]
-            let new_locales = locales[old_len..]
-                .iter()
-                .flat_map(|locale| {
-                    self.rulebook.find_fallback_locale(locale).chain(
-                        self.rulebook
-                            .find_fallback_locale_ref(locale)
-                            .map(Clone::clone),
-                    )
-                })
-                .filter(|l| !locale_hashes.contains(&h(l)))
-                .unique()
-                .collect_vec();
-            old_len = locales.len();
-            locales.extend_from_slice(&new_locales);
-            locale_hashes.extend(new_locales.iter().map(h));
+        parent.id.region = Some(region);
+        let mut chain = vec![];
+        for fallback in
+            std::iter::once(parent.clone()).chain(self.solve_locale_with_extensions(&parent))
+        {
+            if fallback != *locale && !chain.contains(&fallback) {
+                chain.push(fallback);
+            }
+        }
+        chain
+    }
+
+    /// Find alternative fallbacks for the given `locale` as specified by the `rulebook`, while
+    /// watching for suspicious growth of the chain. See [`ExpansionGuard`].
+    ///
+    /// # Errors
+    /// Returns the first [`RunawayExpansion`] detected if [`ExpansionGuard::with_hard_error`] is
+    /// set. Otherwise, this never fails.
+    pub fn solve_locale_guarded<L: AsRef<LanguageIdentifier>>(
+        &self,
+        locale: L,
+        guard: &ExpansionGuard,
+    ) -> Result<Vec<LanguageIdentifier>, RunawayExpansion> {
+        let locale = locale.as_ref();
+        match solve_with(&self.rulebook, locale, &(), guard.checker(locale)) {
+            (_, Some(runaway)) => Err(runaway),
+            (locales, None) => Ok(locales),
+        }
+    }
+}
+
+impl<R> LocaleFallbackSolver<R> {
+    /// Find alternative fallbacks for the given `locale` as specified by the `rulebook`, under the
+    /// given application-supplied context of type `C`.
+    ///
+    /// The context is passed to [`PolyL10nRulebook::find_fallback_locale_in`] so that a rulebook
+    /// may answer differently per use, see [`ContextRulebook`] and [`Contextual`]. The rulebooks
+    /// of this crate accept any context, except the context-aware ones, which only accept their
+    /// own type of context.
+    ///
+    /// [`Self::solve_locale`] uses `()` as the context.
+    pub fn solve_locale_with_context<L, C>(&self, locale: L, ctx: &C) -> Vec<LanguageIdentifier>
+    where
+        L: AsRef<LanguageIdentifier>,
+        R: for<'a> PolyL10nRulebook<'a, C>,
+    {
+        solve(&self.rulebook, locale.as_ref(), ctx)
+    }
+}
+
+impl<F, I> LocaleFallbackSolver<FnRulebook<F>>
+where
+    F: Fn(&LanguageIdentifier) -> I,
+    I: IntoIterator<Item = LanguageIdentifier>,
+{
+    /// A solver using the closure `f` as its rulebook, see [`FnRulebook`].
+    ///
+    /// Closures cannot implement [`PolyL10nRulebook`] directly since that would conflict with the
+    /// [`std::ops::Index`]-based implementation, but this spares the wrapping and the type
+    /// annotations, e.g. for one-off solvers in tests.
+    ///
+    /// ```
+    /// use poly_l10n::{langid, LocaleFallbackSolver};
+    /// let solver = LocaleFallbackSolver::from_fn(|l| match l.to_string().as_str() {
+    ///     "de-AT" => vec![langid!("de")],
+    ///     _ => vec![],
+    /// });
+    /// assert_eq!(solver.solve_locale(langid!("de-AT")), [langid!("de")]);
+    /// ```
+    pub const fn from_fn(f: F) -> Self {
+        Self {
+            rulebook: FnRulebook(f),
+        }
+    }
+}
+
+impl<A> LocaleFallbackSolver<Rulebook<A>> {
+    /// Same as [`Self::solve_locale`], but also records which rule gave each fallback and for
+    /// which locale, for debugging bad fallback chains. See [`Explanation`].
+    ///
+    /// ```
+    /// use poly_l10n::{langid, LocaleFallbackSolver, Rulebook};
+    /// let solver = LocaleFallbackSolver {
+    ///     rulebook: Rulebook::from_fns(vec![
+    ///         std::rc::Rc::new(|l| match l.to_string().as_str() {
+    ///             "de-AT" => vec![langid!("de-DE")],
+    ///             _ => vec![],
+    ///         }),
+    ///         std::rc::Rc::new(|l| l.region.map(|_| langid!(l.language.as_str())).into_iter().collect()),
+    ///         std::rc::Rc::new(|l| match l.language.as_str() {
+    ///             "de" => vec![langid!("en")],
+    ///             _ => vec![],
+    ///         }),
+    ///     ]),
+    /// };
+    /// let explanation = solver.explain_locale(langid!("de-AT"));
+    /// assert_eq!(explanation.chain(), solver.solve_locale(langid!("de-AT")));
+    /// let derivation = explanation.derivation(&langid!("en")).unwrap();
+    /// assert_eq!((&derivation.from, derivation.rule), (&langid!("de-AT"), 2));
+    /// assert_eq!(explanation.to_string(), "\
+    /// de-AT
+    /// ├─ de-DE (rule #0)
+    /// ├─ de (rule #1)
+    /// └─ en (rule #2)
+    /// ");
+    /// ```
+    pub fn explain_locale<L: AsRef<LanguageIdentifier>>(&self, locale: L) -> Explanation {
+        explain::explain(&self.rulebook.rules, locale.as_ref())
+    }
+}
+
+impl<A> LocaleFallbackSolver<ARulebook<A>> {
+    /// See [`LocaleFallbackSolver::explain_locale`].
+    pub fn explain_locale<L: AsRef<LanguageIdentifier>>(&self, locale: L) -> Explanation {
+        explain::explain(&self.rulebook.rules, locale.as_ref())
+    }
+}
+
+impl<R: for<'a> PolyL10nRulebook<'a>> LocaleFallbackSolver<DomainRulebooks<R>> {
+    /// Find alternative fallbacks for the given `locale` as specified by the rulebook of the given
+    /// `domain`. See [`DomainRulebooks`].
+    pub fn solve_locale_in_domain<L: AsRef<LanguageIdentifier>>(
+        &self,
+        domain: &str,
+        locale: L,
+    ) -> Vec<LanguageIdentifier> {
+        solve(self.rulebook.get(domain), locale.as_ref(), &())
+    }
+}
+
+/// Hard upper bound on the number of locales returned by a single solve.
+///
+/// Together with [`SOLVE_MAX_EXPANSIONS`] and [`SOLVE_MAX_CANDIDATES`], this bounds the work done
+/// by [`LocaleFallbackSolver`] regardless of the rulebook, including rulebooks generating endless
+/// chains or infinite iterators. Solving stops early once any of them is reached, returning the
+/// chain found so far. These limits are always enforced and cannot be raised, so it is safe to
+/// solve locales from untrusted inputs (e.g. HTTP headers) as long as each rulebook call itself
+/// terminates quickly, which holds for the rulebooks provided by this crate.
+pub const SOLVE_MAX_LOCALES: usize = 1024;
+/// Hard upper bound on the number of locales expanded, i.e. queried from the rulebook, by a single
+/// solve. See [`SOLVE_MAX_LOCALES`].
+pub const SOLVE_MAX_EXPANSIONS: usize = 4096;
+/// Hard upper bound on the total number of fallbacks taken from the rulebook by a single solve,
+/// including duplicates. See [`SOLVE_MAX_LOCALES`].
+pub const SOLVE_MAX_CANDIDATES: usize = 65536;
+
+/// Solve the fallbacks of `locale` with the given `rulebook` breadth-first.
+fn solve<R: for<'a> PolyL10nRulebook<'a, C>, C>(
+    rulebook: &R,
+    locale: &LanguageIdentifier,
+    ctx: &C,
+) -> Vec<LanguageIdentifier> {
+    solve_with(rulebook, locale, ctx, |_| {
+        std::ops::ControlFlow::<std::convert::Infallible>::Continue(())
+    })
+    .0
+}
+
+/// Same as [`solve()`], but `inspect` is called with the chain so far each time a locale is added
+/// to it. If `inspect` breaks, solving stops and the partial chain is returned along with the
+/// break value.
+fn solve_with<R, C, B, F>(
+    rulebook: &R,
+    locale: &LanguageIdentifier,
+    ctx: &C,
+    inspect: F,
+) -> (Vec<LanguageIdentifier>, Option<B>)
+where
+    R: for<'a> PolyL10nRulebook<'a, C>,
+    F: FnMut(&[LanguageIdentifier]) -> std::ops::ControlFlow<B>,
+{
+    let mut locales = Vec::with_capacity(solve::CHAIN_CAPACITY);
+    let broken = solve_into_with(rulebook, locale, ctx, &mut locales, inspect);
+    (locales, broken)
+}
+
+/// Same as [`solve_with()`], but the chain is written to `locales`, which is cleared first.
+fn solve_into_with<R, C, B, F>(
+    rulebook: &R,
+    locale: &LanguageIdentifier,
+    ctx: &C,
+    locales: &mut Vec<LanguageIdentifier>,
+    mut inspect: F,
+) -> Option<B>
+where
+    R: for<'a> PolyL10nRulebook<'a, C>,
+    F: FnMut(&[LanguageIdentifier]) -> std::ops::ControlFlow<B>,
+{
+    #[cfg(feature = "tracing")]
+    let (span, start) = (
+        tracing::debug_span!(
+            "solve_locale",
+            %locale,
+            rulebook = std::any::type_name::<R>(),
+            chain_len = tracing::field::Empty,
+            duration = tracing::field::Empty,
+        )
+        .entered(),
+        std::time::Instant::now(),
+    );
+    locales.clear();
+    let mut broken = None;
+    for l in SolveIter::new(rulebook, locale, ctx) {
+        locales.push(l);
+        if let std::ops::ControlFlow::Break(b) = inspect(locales) {
+            broken = Some(b);
+            break;
         }
-        locales.into_iter().unique().collect_vec()
+    }
+    #[cfg(feature = "tracing")]
+    span.record("chain_len", locales.len())
+        .record("duration", tracing::field::debug(start.elapsed()));
+    broken
+}
+
+/// A solver that finds the fallbacks of locales.
+///
+/// This is implemented by [`LocaleFallbackSolver`]. Libraries may accept `impl LocaleSolver`
+/// instead of a concrete solver so that it can be substituted, e.g. by a deterministic stub in
+/// tests. This trait is dyn-compatible.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, LanguageIdentifier, LocaleSolver};
+///
+/// struct Stub;
+/// impl LocaleSolver for Stub {
+///     fn solve_locale(&self, _: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+///         vec![langid!("en")]
+///     }
+/// }
+///
+/// fn falls_back_to(solver: &impl LocaleSolver, from: &LanguageIdentifier, to: &LanguageIdentifier) -> bool {
+///     solver.solve_locale(from).contains(to)
+/// }
+///
+/// assert!(falls_back_to(&Stub, &langid!("ja"), &langid!("en")));
+/// let solver = poly_l10n::LocaleFallbackSolver::<poly_l10n::Rulebook>::default();
+/// # #[cfg(feature = "per_lang_default_rules")]
+/// assert!(falls_back_to(&solver, &langid!("zh-TW"), &langid!("zh-Hant-TW")));
+/// ```
+pub trait LocaleSolver {
+    /// Find alternative fallbacks for the given `locale`.
+    ///
+    /// See [`LocaleFallbackSolver::solve_locale`].
+    fn solve_locale(&self, locale: &LanguageIdentifier) -> Vec<LanguageIdentifier>;
+}
+
+impl<R: for<'a> PolyL10nRulebook<'a>> LocaleSolver for LocaleFallbackSolver<R> {
+    fn solve_locale(&self, locale: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+        Self::solve_locale(self, locale).0
+    }
+}
+
+impl<S: LocaleSolver + ?Sized> LocaleSolver for &S {
+    fn solve_locale(&self, locale: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+        (**self).solve_locale(locale)
+    }
+}
+impl<S: LocaleSolver + ?Sized> LocaleSolver for Box<S> {
+    fn solve_locale(&self, locale: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+        (**self).solve_locale(locale)
+    }
+}
+impl<S: LocaleSolver + ?Sized> LocaleSolver for Rc<S> {
+    fn solve_locale(&self, locale: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+        (**self).solve_locale(locale)
+    }
+}
+impl<S: LocaleSolver + ?Sized> LocaleSolver for Arc<S> {
+    fn solve_locale(&self, locale: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+        (**self).solve_locale(locale)
+    }
+}
+
+/// A type-erased [`LocaleSolver`].
+///
+/// This allows storing solvers with different rulebook types uniformly, e.g. in plugin systems.
+///
+/// For the thread-safe version, see [`ADynSolver`].
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{DynSolver, LocaleFallbackSolver, LocaleSolver, Rulebook};
+/// let solvers = [
+///     DynSolver::new(LocaleFallbackSolver::<Rulebook>::default()),
+///     DynSolver::new(LocaleFallbackSolver {
+///         rulebook: Rulebook::from_fn(|_| vec![poly_l10n::langid!("en")]),
+///     }),
+/// ];
+/// for solver in &solvers {
+///     assert!(!solver.solve_locale(&poly_l10n::langid!("en-GB")).is_empty());
+/// }
+/// ```
+pub struct DynSolver(Box<dyn LocaleSolver>);
+
+impl DynSolver {
+    #[must_use]
+    pub fn new<S: LocaleSolver + 'static>(solver: S) -> Self {
+        Self(Box::new(solver))
+    }
+}
+
+impl LocaleSolver for DynSolver {
+    fn solve_locale(&self, locale: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+        self.0.solve_locale(locale)
+    }
+}
+
+impl std::fmt::Debug for DynSolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynSolver").finish_non_exhaustive()
+    }
+}
+
+/// A type-erased [`LocaleSolver`].
+///
+/// This is the thread-safe version of [`DynSolver`].
+pub struct ADynSolver(Box<dyn LocaleSolver + Send + Sync>);
+
+impl ADynSolver {
+    #[must_use]
+    pub fn new<S: LocaleSolver + Send + Sync + 'static>(solver: S) -> Self {
+        Self(Box::new(solver))
+    }
+}
+
+impl LocaleSolver for ADynSolver {
+    fn solve_locale(&self, locale: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+        self.0.solve_locale(locale)
+    }
+}
+
+impl std::fmt::Debug for ADynSolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ADynSolver").finish_non_exhaustive()
     }
 }
 
@@ -121,7 +826,16 @@ impl<R: for<'a> PolyL10nRulebook<'a>> LocaleFallbackSolver<R> {
 /// no performance difference between the two.
 ///
 /// If both functions are implemented, the solver will [`Iterator::chain`] them together.
-pub trait PolyL10nRulebook<'s> {
+///
+/// Fallbacks from [`PolyL10nRulebook::find_terminal_fallback_locale`] are *terminal*: the solver
+/// includes them in the results but does not look for their fallbacks. Wrap a rulebook with
+/// [`Terminal`] to mark all of its fallbacks as terminal.
+///
+/// The `*_in` variants receive the context of type `C` given to
+/// [`LocaleFallbackSolver::solve_locale_with_context`], which is `()` by default. Rulebooks that do
+/// not care about contexts need not implement them, but should implement this trait for any `C` so
+/// that they can be used under any context, e.g. wrapped in a [`Contextual`].
+pub trait PolyL10nRulebook<'s, C = ()> {
     fn find_fallback_locale(
         &self,
         _: &LanguageIdentifier,
@@ -135,6 +849,32 @@ pub trait PolyL10nRulebook<'s> {
     ) -> impl Iterator<Item = &'s LanguageIdentifier> {
         std::iter::empty()
     }
+
+    fn find_terminal_fallback_locale(
+        &'s self,
+        _: &LanguageIdentifier,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        std::iter::empty()
+    }
+
+    /// Find additional fallbacks depending on the given context.
+    fn find_fallback_locale_in(
+        &'s self,
+        _: &LanguageIdentifier,
+        _ctx: &C,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        std::iter::empty()
+    }
+
+    /// Find terminal fallbacks under the given context. By default this is the same as
+    /// [`PolyL10nRulebook::find_terminal_fallback_locale`].
+    fn find_terminal_fallback_locale_in(
+        &'s self,
+        locale: &LanguageIdentifier,
+        _ctx: &C,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        self.find_terminal_fallback_locale(locale)
+    }
 }
 
 // NOTE: rust disallows multiple blanket impls, so unfortunately we need to choose one
@@ -152,7 +892,7 @@ where
 }
 */
 
-impl<'s, M, LS: 's> PolyL10nRulebook<'s> for M
+impl<'s, C, M, LS: 's> PolyL10nRulebook<'s, C> for M
 where
     M: for<'a> std::ops::Index<&'a LanguageIdentifier, Output = LS>,
     &'s LS: IntoIterator<Item = &'s LanguageIdentifier>,
@@ -165,20 +905,149 @@ where
     }
 }
 
-pub type FnRules = Vec<Box<dyn Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier>>>;
+/// Rulebook trait for rulebooks that lend out their fallbacks.
+///
+/// Unlike [`PolyL10nRulebook::find_fallback_locale_ref`], the lifetime of the borrow is tied to the
+/// method call via a generic associated type instead of the whole trait, so implementing it does
+/// not require any lifetime gymnastics. Use [`Borrowing`] to plug it into a
+/// [`LocaleFallbackSolver`]; the solver only clones fallbacks it has not seen yet.
+///
+/// This is implemented for [`std::collections::HashMap`] and [`std::collections::BTreeMap`] with
+/// [`LanguageIdentifier`] keys. Unlike the [`std::ops::Index`]-based [`PolyL10nRulebook`]
+/// implementation, missing keys simply have no fallbacks instead of panicking.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, Borrowing, LocaleFallbackSolver};
+/// let map = std::collections::HashMap::from([
+///     (langid!("arb"), vec![langid!("ar")]),
+///     (langid!("ar"), vec![langid!("en")]),
+/// ]);
+/// let solver = LocaleFallbackSolver { rulebook: Borrowing(map) };
+/// assert_eq!(solver.solve_locale(langid!("arb")), langid!["ar", "en"]);
+/// assert!(solver.solve_locale(langid!("ja")).is_empty());
+/// ```
+pub trait BorrowingRulebook {
+    type Iter<'s>: Iterator<Item = &'s LanguageIdentifier>
+    where
+        Self: 's;
+
+    fn find_fallback_locale_ref<'s>(&'s self, locale: &LanguageIdentifier) -> Self::Iter<'s>;
+}
+
+impl<V, S> BorrowingRulebook for std::collections::HashMap<LanguageIdentifier, V, S>
+where
+    V: AsRef<[LanguageIdentifier]>,
+    S: std::hash::BuildHasher,
+{
+    type Iter<'s>
+        = std::slice::Iter<'s, LanguageIdentifier>
+    where
+        Self: 's;
+
+    fn find_fallback_locale_ref<'s>(&'s self, locale: &LanguageIdentifier) -> Self::Iter<'s> {
+        self.get(locale).map_or(&[][..], AsRef::as_ref).iter()
+    }
+}
+
+impl<V> BorrowingRulebook for std::collections::BTreeMap<LanguageIdentifier, V>
+where
+    V: AsRef<[LanguageIdentifier]>,
+{
+    type Iter<'s>
+        = std::slice::Iter<'s, LanguageIdentifier>
+    where
+        Self: 's;
+
+    fn find_fallback_locale_ref<'s>(&'s self, locale: &LanguageIdentifier) -> Self::Iter<'s> {
+        self.get(locale).map_or(&[][..], AsRef::as_ref).iter()
+    }
+}
+
+/// Use a [`BorrowingRulebook`] as a [`PolyL10nRulebook`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Borrowing<R>(pub R);
+
+impl<'s, C, R: BorrowingRulebook> PolyL10nRulebook<'s, C> for Borrowing<R> {
+    fn find_fallback_locale_ref(
+        &'s self,
+        locale: &LanguageIdentifier,
+    ) -> impl Iterator<Item = &'s LanguageIdentifier> {
+        self.0.find_fallback_locale_ref(locale)
+    }
+}
+
+/// How to order duplicated fallbacks when combining multiple rulebooks, e.g. with
+/// [`Rulebook::from_rulebooks_with`].
+///
+/// The rulebooks are given in the order of priority.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, MergePolicy};
+/// let outputs = || [langid!["en", "fr"].to_vec(), langid!["de", "en", "ja"].to_vec()].into_iter();
+/// assert_eq!(MergePolicy::FirstWins.merge(outputs()), langid!["en", "fr", "de", "ja"]);
+/// assert_eq!(MergePolicy::LastWins.merge(outputs()), langid!["fr", "de", "en", "ja"]);
+/// assert_eq!(MergePolicy::Interleave.merge(outputs()), langid!["en", "de", "fr", "ja"]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MergePolicy {
+    /// Concatenate the fallbacks; duplicates stay at their first position.
+    #[default]
+    FirstWins,
+    /// Concatenate the fallbacks; duplicates are moved to their last position, so that later
+    /// rulebooks override where a fallback is placed.
+    LastWins,
+    /// Take the first fallback from each rulebook, then the second from each, and so on;
+    /// duplicates stay at their first position.
+    Interleave,
+}
+
+impl MergePolicy {
+    /// Merge the fallbacks given by each rulebook for the same locale.
+    pub fn merge<I: Iterator<Item = Vec<LanguageIdentifier>>>(
+        self,
+        outputs: I,
+    ) -> Vec<LanguageIdentifier> {
+        match self {
+            Self::FirstWins => outputs.flatten().unique().collect(),
+            Self::LastWins => {
+                let mut merged = outputs.flatten().collect_vec();
+                merged.reverse();
+                let mut merged = merged.into_iter().unique().collect_vec();
+                merged.reverse();
+                merged
+            }
+            Self::Interleave => {
+                let mut outputs = outputs.map(Vec::into_iter).collect_vec();
+                let mut merged = vec![];
+                while !outputs.is_empty() {
+                    outputs.retain_mut(|output| output.next().map(|l| merged.push(l)).is_some());
+                }
+                merged.into_iter().unique().collect()
+            }
+        }
+    }
+}
+
+/// A rule of a [`Rulebook`].
+pub type FnRule = Rc<dyn Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier>>;
+pub type FnRules = Vec<FnRule>;
 
 /// A set of rules that govern how [`LocaleFallbackSolver`] should handle fallbacks.
 ///
 /// For the thread-safe version, see [`ARulebook<A>`].
 ///
-/// [`Rulebook<A>`], regardless of type `A`, stores the rules as [`FnRules`], a vector of boxed
-/// `dyn Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier>`. Therefore, the actual correct name of
+/// [`Rulebook<A>`], regardless of type `A`, stores the rules as [`FnRules`], a vector of
+/// reference-counted `dyn Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier>`, so that cloning a
+/// rulebook is cheap. Therefore, the actual correct name of
 /// this struct should be something along the lines of `FnsRulebook`.
 ///
 /// Obviously this rulebook can be used with the solver because it implements [`PolyL10nRulebook`].
 ///
 /// In addition, the default rulebook [`Rulebook::default()`] can and probably should be used for
 /// most situations you ever need to deal with.
+#[derive(Clone)]
 pub struct Rulebook<A = ()> {
     pub rules: FnRules,
     pub owned_values: A,
@@ -209,7 +1078,145 @@ impl std::fmt::Debug for PseudoFnRules {
     }
 }
 
-impl<A> PolyL10nRulebook<'_> for Rulebook<A> {
+impl<A> Rulebook<A> {
+    /// Number of rule functions in the rulebook.
+    ///
+    /// Rulebooks combined with e.g. [`Rulebook::from_rulebooks`] count as a single rule.
+    ///
+    /// # Examples
+    /// ```
+    /// use poly_l10n::{langid, Rulebook};
+    /// let rulebook = Rulebook::from_fn(|_| vec![langid!("en")]);
+    /// assert_eq!(rulebook.len(), 1);
+    /// assert!(Rulebook::from_fns(vec![]).is_empty());
+    /// ```
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Whether the rulebook has no rules, i.e. never gives any fallbacks.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Append a rule, which gives fallbacks after those of the existing rules.
+    ///
+    /// # Examples
+    /// ```
+    /// use poly_l10n::{langid, LocaleFallbackSolver, Rulebook};
+    /// let mut rulebook = Rulebook::strip_region();
+    /// rulebook.push_fn(|l| match l.language.as_str() {
+    ///     "gsw" => vec![langid!("de")],
+    ///     _ => vec![],
+    /// });
+    /// let other = Rulebook::from_fn(|_| vec![langid!("en")]);
+    /// rulebook.extend_from_rulebook(other);
+    /// assert_eq!(rulebook.len(), 3);
+    /// let solver = LocaleFallbackSolver { rulebook };
+    /// assert_eq!(solver.solve_locale(langid!("gsw-CH")), langid!["gsw", "de", "en"]);
+    /// ```
+    pub fn push_fn<F: Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> + 'static>(
+        &mut self,
+        f: F,
+    ) {
+        self.rules.push(Rc::new(f));
+    }
+
+    /// Append a rule, e.g. one taken from [`Self::rules`] of another rulebook. See
+    /// [`Self::push_fn`].
+    pub fn push_rule(&mut self, rule: FnRule) {
+        self.rules.push(rule);
+    }
+
+    /// Append the rules of `other`, which give fallbacks after those of the existing rules.
+    ///
+    /// Unlike [`Rulebook::from_rulebooks`], fallbacks are not merged with a [`MergePolicy`]; the
+    /// solver ignores duplicates anyway. The owned values of `other` are appended to those of
+    /// this rulebook, see [`RuleState`].
+    pub fn extend_from_rulebook<B: Into<A>>(&mut self, other: Rulebook<B>)
+    where
+        A: RuleState,
+    {
+        (self.owned_values).append(self.rules.len(), other.owned_values.into());
+        self.rules.extend(other.rules);
+    }
+
+    /// Remove the rule at `index`, if any, along with its [`RuleState`].
+    pub fn remove_rule(&mut self, index: usize) -> Option<FnRule>
+    where
+        A: RuleState,
+    {
+        (index < self.rules.len()).then(|| {
+            self.owned_values.remove(index);
+            self.rules.remove(index)
+        })
+    }
+
+    /// Remove all rules, along with their [`RuleState`].
+    pub fn clear(&mut self)
+    where
+        A: RuleState,
+    {
+        self.owned_values.clear();
+        self.rules.clear();
+    }
+
+    /// Check the rules against `samples` for common mistakes, see [`RuleDiagnostic`].
+    ///
+    /// For each sample locale, every rule is checked for fallbacks to the sample itself, for
+    /// fallbacks with unknown languages, and for giving only fallbacks that earlier rules already
+    /// give. The whole chain of each sample is then solved with an [`ExpansionGuard`] with the
+    /// default limits. This is meant for tests of custom rulebooks, and is as expensive as
+    /// solving every sample.
+    ///
+    /// # Examples
+    /// ```
+    /// use poly_l10n::{langid, LanguageIdentifier, RuleDiagnostic, Rulebook};
+    /// let rulebook = Rulebook::from_fns(vec![
+    ///     std::rc::Rc::new(|l: &LanguageIdentifier| match l.to_string().as_str() {
+    ///         "de-AT" => vec![langid!("de"), langid!("de-AT")],
+    ///         _ => vec![],
+    ///     }),
+    ///     std::rc::Rc::new(|l: &LanguageIdentifier| match l.language.as_str() {
+    ///         "de" => vec![langid!("de")],
+    ///         _ => vec![langid!("qqq")],
+    ///     }),
+    /// ]);
+    /// assert_eq!(
+    ///     rulebook.verify(langid!["de-AT", "fr"]),
+    ///     [
+    ///         RuleDiagnostic::SelfLoop { rule: 0, locale: langid!("de-AT") },
+    ///         RuleDiagnostic::Shadowed { rule: 1, locale: langid!("de-AT") },
+    ///         RuleDiagnostic::InvalidTag { rule: 1, locale: langid!("fr"), fallback: langid!("qqq") },
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn verify<I: IntoIterator<Item = LanguageIdentifier>>(
+        &self,
+        samples: I,
+    ) -> Vec<RuleDiagnostic> {
+        lint::verify(&self.rules, samples)
+    }
+
+    /// Record the invocation count and time of each rule with `profiler`.
+    ///
+    /// Only available with the feature `profile`.
+    #[cfg(feature = "profile")]
+    #[must_use]
+    pub fn profiled(self, profiler: &RuleProfiler) -> Self {
+        Self {
+            rules: (self.rules.into_iter().enumerate())
+                .map(|(i, f)| Rc::new(profiler.wrap(i, f)) as _)
+                .collect(),
+            owned_values: self.owned_values,
+        }
+    }
+}
+
+impl<A, C> PolyL10nRulebook<'_, C> for Rulebook<A> {
     fn find_fallback_locale(
         &self,
         locale: &LanguageIdentifier,
@@ -221,6 +1228,9 @@ impl<A> PolyL10nRulebook<'_> for Rulebook<A> {
 impl Rulebook<Rc<Vec<Rulebook>>> {
     /// Combine multiple rulebooks into one.
     ///
+    /// Fallbacks given by multiple rulebooks are placed where they first appear, see
+    /// [`MergePolicy::FirstWins`] and [`Self::from_rulebooks_with`].
+    ///
     /// See also: [`Self::from_ref_rulebooks`].
     ///
     /// # Examples
@@ -244,16 +1254,24 @@ impl Rulebook<Rc<Vec<Rulebook>>> {
     /// );
     /// ```
     pub fn from_rulebooks<I: Iterator<Item = Rulebook>>(rulebooks: I) -> Self {
+        Self::from_rulebooks_with(rulebooks, MergePolicy::FirstWins)
+    }
+
+    /// Combine multiple rulebooks into one, resolving duplicated fallbacks from different
+    /// rulebooks with the given [`MergePolicy`].
+    pub fn from_rulebooks_with<I: Iterator<Item = Rulebook>>(
+        rulebooks: I,
+        policy: MergePolicy,
+    ) -> Self {
         let mut new = Self {
             owned_values: Rc::new(rulebooks.collect_vec()),
             rules: vec![],
         };
         let owned_values = Rc::clone(&new.owned_values);
-        new.rules = vec![Box::new(move |l: &LanguageIdentifier| {
-            owned_values
-                .iter()
-                .flat_map(|rulebook| rulebook.find_fallback_locale(l).collect_vec())
-                .collect()
+        new.rules = vec![Rc::new(move |l: &LanguageIdentifier| {
+            policy.merge((owned_values.iter()).map(|rulebook| {
+                PolyL10nRulebook::<()>::find_fallback_locale(rulebook, l).collect_vec()
+            }))
         })];
         new
     }
@@ -291,25 +1309,128 @@ where
     /// );
     /// ```
     pub fn from_ref_rulebooks<I: Iterator<Item = RR>>(rulebooks: I) -> Self {
+        Self::from_ref_rulebooks_with(rulebooks, MergePolicy::FirstWins)
+    }
+
+    /// Combine multiple rulebooks into one, resolving duplicated fallbacks from different
+    /// rulebooks with the given [`MergePolicy`]. Each given rulebook `r` must implement
+    /// [`AsRef::as_ref`].
+    pub fn from_ref_rulebooks_with<I: Iterator<Item = RR>>(
+        rulebooks: I,
+        policy: MergePolicy,
+    ) -> Self {
         let mut new = Self {
             owned_values: (Rc::new(rulebooks.collect_vec()), std::marker::PhantomData),
             rules: vec![],
         };
         let owned_values = Rc::clone(&new.owned_values.0);
-        new.rules = vec![Box::new(move |l: &LanguageIdentifier| {
-            (owned_values.iter())
-                .flat_map(|rulebook| rulebook.as_ref().find_fallback_locale(l).collect_vec())
-                .collect()
+        new.rules = vec![Rc::new(move |l: &LanguageIdentifier| {
+            policy.merge((owned_values.iter()).map(|rulebook| {
+                PolyL10nRulebook::<()>::find_fallback_locale(rulebook.as_ref(), l).collect_vec()
+            }))
         })];
         new
     }
 }
 
 impl Rulebook {
+    /// Compose a rulebook with a [`RulebookBuilder`].
+    pub fn builder() -> RulebookBuilder {
+        RulebookBuilder::default()
+    }
+    /// An empty rulebook for named rules, see [`RuleSwitches`].
+    #[must_use]
+    pub const fn named() -> Rulebook<RuleSwitches> {
+        Rulebook {
+            rules: vec![],
+            owned_values: RuleSwitches::new(),
+        }
+    }
+    /// An empty rulebook for weighted rules, see [`RuleWeights`].
+    #[must_use]
+    pub const fn weighted() -> Rulebook<RuleWeights> {
+        Rulebook {
+            rules: vec![],
+            owned_values: RuleWeights::new(),
+        }
+    }
+    /// Parse a rulebook from a TOML configuration file.
+    ///
+    /// Each entry maps a locale to its fallbacks, which become a [`Rule::MapExact`]. A key ending
+    /// with `-*` matches all locales of the language ([`Rule::PerLanguage`]), and the key
+    /// `directives` lists generic rules: `default`, `strip-script`, `strip-region`,
+    /// `strip-variants` and `to-639-1`. The rules are in the order of the file.
+    ///
+    /// Gated behind the feature `config`.
+    ///
+    /// # Format
+    /// Only a subset of TOML is supported: one `key = value` entry per line, without tables.
+    /// - Keys are bare (letters, digits, `-` and `_`) or quoted with `"` or `'`. Quote keys ending
+    ///   with `-*`, which are not bare keys in TOML.
+    /// - Values are a string or an array of strings. Arrays may span multiple lines and have a
+    ///   trailing comma.
+    /// - Strings in `"` support the escapes `\"`, `\\`, `\n` and `\t`; strings in `'` are literal.
+    /// - Comments start with `#` and run to the end of the line.
+    ///
+    /// Locales are parsed leniently, so `es_MX` is `es-MX`, see [`langid!`].
+    ///
+    /// # Errors
+    /// Returns [`ConfigError`] if the input is not in the expected format.
+    ///
+    /// # Examples
+    /// ```
+    /// use poly_l10n::{langid, LocaleFallbackSolver, Rule, Rulebook};
+    /// let rulebook = Rulebook::from_toml_str(r#"
+    /// "arb" = ["ar-AE", "ar"]
+    /// "es-*" = "pt"
+    /// directives = ["strip-region"]
+    /// "#).unwrap();
+    /// assert_eq!(rulebook.owned_values[2], Rule::StripRegion);
+    /// let solver = LocaleFallbackSolver { rulebook };
+    /// assert_eq!(solver.solve_locale(langid!("arb")), langid!["ar-AE", "ar"]);
+    /// assert_eq!(solver.solve_locale(langid!("es-MX")), langid!["pt", "es"]);
+    /// assert!(Rulebook::from_toml_str("directives = [\"nonexistent\"]").is_err());
+    /// ```
+    #[cfg(feature = "config")]
+    pub fn from_toml_str(input: &str) -> Result<Rulebook<Vec<Rule>>, ConfigError> {
+        config::parse_toml(input).map(Self::from_rules)
+    }
+
+    /// Parse a rulebook from a JSON configuration file, an object with the same entries as in
+    /// [`Self::from_toml_str`].
+    ///
+    /// # Errors
+    /// Returns [`ConfigError`] if the input is not in the expected format.
+    ///
+    /// # Examples
+    /// ```
+    /// use poly_l10n::{langid, LocaleFallbackSolver, Rulebook};
+    /// let rulebook = Rulebook::from_json_str(r#"{
+    ///     "arb": ["ar-AE", "ar"],
+    ///     "directives": ["strip-script"]
+    /// }"#).unwrap();
+    /// let solver = LocaleFallbackSolver { rulebook };
+    /// assert_eq!(solver.solve_locale(langid!("sr-Latn")), [langid!("sr")]);
+    /// ```
+    #[cfg(feature = "config")]
+    pub fn from_json_str(input: &str) -> Result<Rulebook<Vec<Rule>>, ConfigError> {
+        config::parse_json(input).map(Self::from_rules)
+    }
+
+    /// A rulebook of declarative rules, which are kept as its owned values. See [`Rule`].
+    pub fn from_rules<I: IntoIterator<Item = Rule>>(rules: I) -> Rulebook<Vec<Rule>> {
+        let owned_values = rules.into_iter().collect_vec();
+        Rulebook {
+            rules: (owned_values.iter().cloned())
+                .map(|rule| Rc::new(move |l: &LanguageIdentifier| rule.apply(l)) as FnRule)
+                .collect(),
+            owned_values,
+        }
+    }
     #[must_use]
     pub fn from_fn<F: Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> + 'static>(f: F) -> Self {
         Self {
-            rules: vec![Box::new(f)],
+            rules: vec![Rc::new(f)],
             owned_values: (),
         }
     }
@@ -328,6 +1449,9 @@ impl Rulebook {
     /// While any valid arguments to this constructor are guaranteed to satisfy the trait
     /// [`PolyL10nRulebook`], it could be useful to convert them to rulebooks, e.g. to combine
     /// multiple rulebooks using [`Self::from_rulebooks`].
+    ///
+    /// Maps usually panic when indexed with a missing key. Use [`Self::from_lookup`] for maps
+    /// that do not cover every locale.
     pub fn from_map<M, LS>(map: M) -> Self
     where
         M: for<'a> std::ops::Index<&'a LanguageIdentifier, Output = LS> + 'static,
@@ -335,29 +1459,172 @@ impl Rulebook {
     {
         Self::from_fn(move |l| map[l].into_iter().cloned().collect())
     }
+
+    /// Convert a map (or any other [`BorrowingRulebook`]) into a rulebook. Unlike
+    /// [`Self::from_map`], which panics on locales missing from the map, missing locales have no
+    /// fallbacks.
+    ///
+    /// # Examples
+    /// ```
+    /// use poly_l10n::{langid, LocaleFallbackSolver, Rulebook};
+    /// let map = std::collections::HashMap::from([(langid!("arb"), vec![langid!("ar")])]);
+    /// let solver = LocaleFallbackSolver { rulebook: Rulebook::from_lookup(map) };
+    /// assert_eq!(solver.solve_locale(langid!("arb")), [langid!("ar")]);
+    /// assert!(solver.solve_locale(langid!("ja")).is_empty());
+    /// ```
+    pub fn from_lookup<M: BorrowingRulebook + 'static>(map: M) -> Self {
+        Self::from_fn(move |l| {
+            BorrowingRulebook::find_fallback_locale_ref(&map, l)
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// A rulebook falling back from a locale with a script to the same locale without it, e.g.
+    /// `sr-Latn-RS` → `sr-RS`.
+    ///
+    /// This and the other primitive rulebooks ([`Self::strip_region`], [`Self::strip_variants`]
+    /// and [`Self::to_639_1`]) are building blocks of the default rules, to be combined with
+    /// e.g. [`Self::from_rulebooks`]. Since the solver is recursive, a rulebook combining them
+    /// yields all combinations.
+    ///
+    /// # Examples
+    /// ```
+    /// use poly_l10n::{langid, LocaleFallbackSolver, Rulebook};
+    /// let rulebook = Rulebook::from_rulebooks(
+    ///     [Rulebook::to_639_1(), Rulebook::strip_region(), Rulebook::strip_script()].into_iter(),
+    /// );
+    /// let solver = LocaleFallbackSolver { rulebook };
+    /// assert_eq!(
+    ///     solver.solve_locale(langid!("srp-Latn-RS")),
+    ///     langid!["sr-Latn-RS", "srp-Latn", "srp-RS", "sr-Latn", "sr-RS", "srp", "sr"]
+    /// );
+    /// let rulebook = Rulebook::strip_script();
+    /// assert!(
+    ///     PolyL10nRulebook::<()>::find_fallback_locale(&rulebook, &langid!("sr")).next().is_none()
+    /// );
+    /// # use poly_l10n::PolyL10nRulebook;
+    /// ```
+    #[must_use]
+    pub fn strip_script() -> Self {
+        Self::from_fn(default_rulebook::strip_script)
+    }
+
+    /// A rulebook falling back from a locale with a region to the same locale without it, e.g.
+    /// `de-AT` → `de`. See [`Self::strip_script`].
+    #[must_use]
+    pub fn strip_region() -> Self {
+        Self::from_fn(default_rulebook::strip_region)
+    }
+
+    /// A rulebook falling back from a locale with variants to the same locale without them, e.g.
+    /// `de-CH-1901` → `de-CH`. See [`Self::strip_script`].
+    #[must_use]
+    pub fn strip_variants() -> Self {
+        Self::from_fn(default_rulebook::strip_variants)
+    }
+
+    /// A rulebook falling back from an ISO 639-3 language subtag to its ISO 639-1 equivalent, if
+    /// any, e.g. `deu-AT` → `de-AT`. See [`Self::strip_script`].
+    #[must_use]
+    pub fn to_639_1() -> Self {
+        Self::from_fn(default_rulebook::to_639_1)
+    }
+
+    /// The entries of the rule files loaded by [`Rulebook::default`], sorted by locale. Empty if
+    /// there are no rule files.
+    ///
+    /// This reads the rule files again, so it reflects their current content.
+    ///
+    /// Only available with the feature `user_rules`.
+    #[cfg(feature = "user_rules")]
+    #[must_use]
+    pub fn user_rules() -> Vec<(LanguageIdentifier, Vec<LanguageIdentifier>)> {
+        let mut rules = rules_file::load_overlay()
+            .unwrap_or_default()
+            .into_iter()
+            .collect_vec();
+        rules.sort_by_cached_key(|(locale, _)| locale.to_string());
+        rules
+    }
 }
 
 // TODO: rules?
+/// The default recommended rules.
+///
+/// With the feature `user_rules`, the rule files `/etc/poly_l10n/rules.toml` and
+/// `~/.config/poly_l10n/rules.toml` (in increasing priority) are loaded on top of the default
+/// rules. For locales specified in these files, the listed fallbacks replace the default ones:
+///
+/// ```toml
+/// "arb" = ["ar-AE", "ar"]
+/// es-MX = ["es-419", "es"]
+/// ```
 impl Default for Rulebook {
     fn default() -> Self {
+        #[cfg(feature = "user_rules")]
+        if let Some(overlay) = rules_file::load_overlay() {
+            return Self::from_fn(rules_file::with_overlay(
+                overlay,
+                default_rulebook::default_rulebook,
+            ));
+        }
         Self::from_fn(default_rulebook::default_rulebook)
     }
 }
 
-pub type AFnRules = Vec<Box<dyn Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> + Send + Sync>>;
+/// Append the rules of the right-hand side, like [`Rulebook::extend_from_rulebook`].
+///
+/// The owned values of the right-hand side are appended to those of the left-hand side, see
+/// [`RuleState`].
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, LocaleFallbackSolver, Rule, Rulebook};
+/// let custom = Rulebook::from_fn(|l| match l.language.as_str() {
+///     "gsw" => vec![langid!("de")],
+///     _ => vec![],
+/// });
+/// let solver = LocaleFallbackSolver { rulebook: Rulebook::strip_region() + custom };
+/// assert_eq!(solver.solve_locale(langid!("gsw-CH")), langid!["gsw", "de"]);
+///
+/// let mut rulebook = Rulebook::from_rules([Rule::StripRegion]);
+/// rulebook += Rulebook::from_rules([Rule::To639_1]);
+/// assert_eq!(rulebook.owned_values, [Rule::StripRegion, Rule::To639_1]);
+/// ```
+impl<A: RuleState, B: Into<A>> std::ops::Add<Rulebook<B>> for Rulebook<A> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Rulebook<B>) -> Self {
+        self.extend_from_rulebook(rhs);
+        self
+    }
+}
+
+impl<A: RuleState, B: Into<A>> std::ops::AddAssign<Rulebook<B>> for Rulebook<A> {
+    fn add_assign(&mut self, rhs: Rulebook<B>) {
+        self.extend_from_rulebook(rhs);
+    }
+}
+
+/// A rule of an [`ARulebook`].
+pub type AFnRule = Arc<dyn Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> + Send + Sync>;
+pub type AFnRules = Vec<AFnRule>;
 
 /// A set of rules that govern how [`LocaleFallbackSolver`] should handle fallbacks.
 ///
 /// This is the thread-safe version of [`Rulebook`].
 ///
-/// [`ARulebook<A>`], regardless of type `A`, stores the rules as [`AFnRules`], a vector of boxed
-/// `dyn Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> + Send + Sync`. Therefore, the actual
+/// [`ARulebook<A>`], regardless of type `A`, stores the rules as [`AFnRules`], a vector of
+/// reference-counted `dyn Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> + Send + Sync`, so
+/// that cloning a rulebook is cheap. Therefore, the actual
 /// correct name of this struct should be something along the lines of `AFnsRulebook`.
 ///
 /// Obviously this rulebook can be used with the solver because it implements [`PolyL10nRulebook`].
 ///
 /// In addition, the default rulebook [`ARulebook::default()`] can and probably should be used for
 /// most situations you ever need to deal with.
+#[derive(Clone)]
 pub struct ARulebook<A = ()> {
     pub rules: AFnRules,
     pub owned_values: A,
@@ -388,7 +1655,86 @@ impl std::fmt::Debug for APseudoFnRules {
     }
 }
 
-impl<A> PolyL10nRulebook<'_> for ARulebook<A> {
+impl<A> ARulebook<A> {
+    /// Number of rule functions in the rulebook, see [`Rulebook::len`].
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Whether the rulebook has no rules, i.e. never gives any fallbacks.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Append a rule, see [`Rulebook::push_fn`].
+    pub fn push_fn<F>(&mut self, f: F)
+    where
+        F: Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> + 'static + Send + Sync,
+    {
+        self.rules.push(Arc::new(f));
+    }
+
+    /// Append a rule, see [`Rulebook::push_rule`].
+    pub fn push_rule(&mut self, rule: AFnRule) {
+        self.rules.push(rule);
+    }
+
+    /// Append the rules of `other`, see [`Rulebook::extend_from_rulebook`].
+    pub fn extend_from_rulebook<B: Into<A>>(&mut self, other: ARulebook<B>)
+    where
+        A: RuleState,
+    {
+        (self.owned_values).append(self.rules.len(), other.owned_values.into());
+        self.rules.extend(other.rules);
+    }
+
+    /// Remove the rule at `index`, see [`Rulebook::remove_rule`].
+    pub fn remove_rule(&mut self, index: usize) -> Option<AFnRule>
+    where
+        A: RuleState,
+    {
+        (index < self.rules.len()).then(|| {
+            self.owned_values.remove(index);
+            self.rules.remove(index)
+        })
+    }
+
+    /// Remove all rules, see [`Rulebook::clear`].
+    pub fn clear(&mut self)
+    where
+        A: RuleState,
+    {
+        self.owned_values.clear();
+        self.rules.clear();
+    }
+
+    /// Check the rules against `samples` for common mistakes, see [`Rulebook::verify`].
+    #[must_use]
+    pub fn verify<I: IntoIterator<Item = LanguageIdentifier>>(
+        &self,
+        samples: I,
+    ) -> Vec<RuleDiagnostic> {
+        lint::verify(&self.rules, samples)
+    }
+
+    /// Record the invocation count and time of each rule with `profiler`.
+    ///
+    /// Only available with the feature `profile`.
+    #[cfg(feature = "profile")]
+    #[must_use]
+    pub fn profiled(self, profiler: &RuleProfiler) -> Self {
+        Self {
+            rules: (self.rules.into_iter().enumerate())
+                .map(|(i, f)| Arc::new(profiler.wrap(i, f)) as _)
+                .collect(),
+            owned_values: self.owned_values,
+        }
+    }
+}
+
+impl<A, C> PolyL10nRulebook<'_, C> for ARulebook<A> {
     fn find_fallback_locale(
         &self,
         locale: &LanguageIdentifier,
@@ -400,6 +1746,9 @@ impl<A> PolyL10nRulebook<'_> for ARulebook<A> {
 impl ARulebook<Arc<Vec<ARulebook>>> {
     /// Combine multiple rulebooks into one.
     ///
+    /// Fallbacks given by multiple rulebooks are placed where they first appear, see
+    /// [`MergePolicy::FirstWins`] and [`Self::from_rulebooks_with`].
+    ///
     /// See also: [`Self::from_ref_rulebooks`].
     ///
     /// # Examples
@@ -423,16 +1772,24 @@ impl ARulebook<Arc<Vec<ARulebook>>> {
     /// );
     /// ```
     pub fn from_rulebooks<I: Iterator<Item = ARulebook>>(rulebooks: I) -> Self {
+        Self::from_rulebooks_with(rulebooks, MergePolicy::FirstWins)
+    }
+
+    /// Combine multiple rulebooks into one, resolving duplicated fallbacks from different
+    /// rulebooks with the given [`MergePolicy`].
+    pub fn from_rulebooks_with<I: Iterator<Item = ARulebook>>(
+        rulebooks: I,
+        policy: MergePolicy,
+    ) -> Self {
         let mut new = Self {
             owned_values: Arc::new(rulebooks.collect_vec()),
             rules: vec![],
         };
         let owned_values = Arc::clone(&new.owned_values);
-        new.rules = vec![Box::new(move |l: &LanguageIdentifier| {
-            owned_values
-                .iter()
-                .flat_map(|rulebook| rulebook.find_fallback_locale(l).collect_vec())
-                .collect()
+        new.rules = vec![Arc::new(move |l: &LanguageIdentifier| {
+            policy.merge((owned_values.iter()).map(|rulebook| {
+                PolyL10nRulebook::<()>::find_fallback_locale(rulebook, l).collect_vec()
+            }))
         })];
         new
     }
@@ -470,21 +1827,79 @@ where
     /// );
     /// ```
     pub fn from_ref_rulebooks<I: Iterator<Item = RR>>(rulebooks: I) -> Self {
+        Self::from_ref_rulebooks_with(rulebooks, MergePolicy::FirstWins)
+    }
+
+    /// Combine multiple rulebooks into one, resolving duplicated fallbacks from different
+    /// rulebooks with the given [`MergePolicy`]. Each given rulebook `r` must implement
+    /// [`AsRef::as_ref`].
+    pub fn from_ref_rulebooks_with<I: Iterator<Item = RR>>(
+        rulebooks: I,
+        policy: MergePolicy,
+    ) -> Self {
         let mut new = Self {
             owned_values: (Arc::new(rulebooks.collect_vec()), std::marker::PhantomData),
             rules: vec![],
         };
         let owned_values = Arc::clone(&new.owned_values.0);
-        new.rules = vec![Box::new(move |l: &LanguageIdentifier| {
-            (owned_values.iter())
-                .flat_map(|rulebook| rulebook.as_ref().find_fallback_locale(l).collect_vec())
-                .collect()
+        new.rules = vec![Arc::new(move |l: &LanguageIdentifier| {
+            policy.merge((owned_values.iter()).map(|rulebook| {
+                PolyL10nRulebook::<()>::find_fallback_locale(rulebook.as_ref(), l).collect_vec()
+            }))
         })];
         new
     }
 }
 
 impl ARulebook {
+    /// Compose a rulebook with an [`ARulebookBuilder`].
+    pub fn builder() -> ARulebookBuilder {
+        ARulebookBuilder::default()
+    }
+    /// See [`Rulebook::named`].
+    #[must_use]
+    pub const fn named() -> ARulebook<RuleSwitches> {
+        ARulebook {
+            rules: vec![],
+            owned_values: RuleSwitches::new(),
+        }
+    }
+    /// See [`Rulebook::weighted`].
+    #[must_use]
+    pub const fn weighted() -> ARulebook<RuleWeights> {
+        ARulebook {
+            rules: vec![],
+            owned_values: RuleWeights::new(),
+        }
+    }
+    /// See [`Rulebook::from_toml_str`].
+    ///
+    /// # Errors
+    /// Returns [`ConfigError`] if the input is not in the expected format.
+    #[cfg(feature = "config")]
+    pub fn from_toml_str(input: &str) -> Result<ARulebook<Vec<Rule>>, ConfigError> {
+        config::parse_toml(input).map(Self::from_rules)
+    }
+
+    /// See [`Rulebook::from_json_str`].
+    ///
+    /// # Errors
+    /// Returns [`ConfigError`] if the input is not in the expected format.
+    #[cfg(feature = "config")]
+    pub fn from_json_str(input: &str) -> Result<ARulebook<Vec<Rule>>, ConfigError> {
+        config::parse_json(input).map(Self::from_rules)
+    }
+
+    /// See [`Rulebook::from_rules`].
+    pub fn from_rules<I: IntoIterator<Item = Rule>>(rules: I) -> ARulebook<Vec<Rule>> {
+        let owned_values = rules.into_iter().collect_vec();
+        ARulebook {
+            rules: (owned_values.iter().cloned())
+                .map(|rule| Arc::new(move |l: &LanguageIdentifier| rule.apply(l)) as AFnRule)
+                .collect(),
+            owned_values,
+        }
+    }
     #[must_use]
     pub fn from_fn<
         F: Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> + 'static + Send + Sync,
@@ -492,7 +1907,7 @@ impl ARulebook {
         f: F,
     ) -> Self {
         Self {
-            rules: vec![Box::new(f)],
+            rules: vec![Arc::new(f)],
             owned_values: (),
         }
     }
@@ -511,6 +1926,9 @@ impl ARulebook {
     /// While any valid arguments to this constructor are guaranteed to satisfy the trait
     /// [`PolyL10nRulebook`], it could be useful to convert them to rulebooks, e.g. to combine
     /// multiple rulebooks using [`Self::from_rulebooks`].
+    ///
+    /// Maps usually panic when indexed with a missing key. Use [`Self::from_lookup`] for maps
+    /// that do not cover every locale.
     pub fn from_map<M, LS>(map: M) -> Self
     where
         M: for<'a> std::ops::Index<&'a LanguageIdentifier, Output = LS> + 'static + Send + Sync,
@@ -518,11 +1936,182 @@ impl ARulebook {
     {
         Self::from_fn(move |l| map[l].into_iter().cloned().collect())
     }
+
+    /// See [`Rulebook::from_lookup`].
+    pub fn from_lookup<M: BorrowingRulebook + 'static + Send + Sync>(map: M) -> Self {
+        Self::from_fn(move |l| {
+            BorrowingRulebook::find_fallback_locale_ref(&map, l)
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// See [`Rulebook::strip_script`].
+    #[must_use]
+    pub fn strip_script() -> Self {
+        Self::from_fn(default_rulebook::strip_script)
+    }
+
+    /// See [`Rulebook::strip_region`].
+    #[must_use]
+    pub fn strip_region() -> Self {
+        Self::from_fn(default_rulebook::strip_region)
+    }
+
+    /// See [`Rulebook::strip_variants`].
+    #[must_use]
+    pub fn strip_variants() -> Self {
+        Self::from_fn(default_rulebook::strip_variants)
+    }
+
+    /// See [`Rulebook::to_639_1`].
+    #[must_use]
+    pub fn to_639_1() -> Self {
+        Self::from_fn(default_rulebook::to_639_1)
+    }
 }
 
 // TODO: rules?
+/// The default recommended rules.
+///
+/// With the feature `user_rules`, the rule files `/etc/poly_l10n/rules.toml` and
+/// `~/.config/poly_l10n/rules.toml` (in increasing priority) are loaded on top of the default
+/// rules. For locales specified in these files, the listed fallbacks replace the default ones:
+///
+/// ```toml
+/// "arb" = ["ar-AE", "ar"]
+/// es-MX = ["es-419", "es"]
+/// ```
 impl Default for ARulebook {
     fn default() -> Self {
+        #[cfg(feature = "user_rules")]
+        if let Some(overlay) = rules_file::load_overlay() {
+            return Self::from_fn(rules_file::with_overlay(
+                overlay,
+                default_rulebook::default_rulebook,
+            ));
+        }
         Self::from_fn(default_rulebook::default_rulebook)
     }
 }
+
+/// See [`Rulebook`]'s implementation.
+impl<A: RuleState, B: Into<A>> std::ops::Add<ARulebook<B>> for ARulebook<A> {
+    type Output = Self;
+
+    fn add(mut self, rhs: ARulebook<B>) -> Self {
+        self.extend_from_rulebook(rhs);
+        self
+    }
+}
+
+impl<A: RuleState, B: Into<A>> std::ops::AddAssign<ARulebook<B>> for ARulebook<A> {
+    fn add_assign(&mut self, rhs: ARulebook<B>) {
+        self.extend_from_rulebook(rhs);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hard_limits_endless_chain() {
+        // every locale falls back to a new one
+        let rulebook = FnRulebook(|l: &LanguageIdentifier| {
+            let n: u32 = l
+                .variants()
+                .next()
+                .map_or(0, |v| v.as_str()[1..].parse().unwrap());
+            let mut l = l.clone();
+            l.set_variants(&[format!("x{:07}", n + 1).parse().unwrap()]);
+            [l]
+        });
+        let solver = LocaleFallbackSolver { rulebook };
+        assert_eq!(solver.solve_locale(langid!("en")).len(), SOLVE_MAX_LOCALES);
+    }
+
+    #[test]
+    fn hard_limits_infinite_iterators() {
+        let rulebook = FnRulebook(|_: &LanguageIdentifier| std::iter::repeat(langid!("en")));
+        let solver = LocaleFallbackSolver { rulebook };
+        assert_eq!(solver.solve_locale(langid!("fr")), [langid!("en")]);
+
+        let rulebook = FnRulebook(|l: &LanguageIdentifier| {
+            let l = l.clone();
+            (1u32..).map(move |n| {
+                let mut l = l.clone();
+                l.set_variants(&[format!("x{n:07}").parse().unwrap()]);
+                l
+            })
+        });
+        let solver = LocaleFallbackSolver { rulebook };
+        assert_eq!(solver.solve_locale(langid!("fr")).len(), SOLVE_MAX_LOCALES);
+    }
+
+    #[test]
+    fn hard_limits_wide_tree() {
+        // each locale has a few fallbacks, all of which are distinct and expanded
+        let rulebook = FnRulebook(|l: &LanguageIdentifier| {
+            let variant = l
+                .variants()
+                .next()
+                .map_or("x", |v| v.as_str().trim_end_matches('z'));
+            ["a", "b", "c"].map(|c| {
+                let mut l = l.clone();
+                let v = format!("{variant}{c}");
+                l.set_variants(&[format!("{v:z<8}").parse().unwrap()]);
+                l
+            })
+        });
+        let solver = LocaleFallbackSolver { rulebook };
+        assert_eq!(solver.solve_locale(langid!("fr")).len(), SOLVE_MAX_LOCALES);
+    }
+
+    #[test]
+    fn rulebook_clone() {
+        let rulebook = Rulebook::from_fn(|l| match l.to_string().as_str() {
+            "de-AT" => vec![langid!("de")],
+            _ => vec![],
+        });
+        let cloned = rulebook.clone();
+        let mut shared = rulebook.rules.iter().zip(&cloned.rules);
+        assert!(shared.all(|(a, b)| Rc::ptr_eq(a, b)));
+        let solver = LocaleFallbackSolver { rulebook: cloned };
+        assert_eq!(solver.solve_locale(langid!("de-AT")), [langid!("de")]);
+    }
+
+    #[test]
+    fn dense_graph() {
+        // every locale falls back to all of the same 500 locales
+        let rulebook = FnRulebook(|l: &LanguageIdentifier| {
+            let l = l.clone();
+            (0u32..500).map(move |n| {
+                let mut l = l.clone();
+                l.set_variants(&[format!("x{n:07}").parse().unwrap()]);
+                l
+            })
+        });
+        let solver = LocaleFallbackSolver { rulebook };
+        let chain = solver.solve_locale(langid!("fr"));
+        assert_eq!(chain.len(), 500);
+        assert!(chain.iter().all_unique());
+    }
+
+    #[test]
+    fn keeps_all_distinct_fallbacks() {
+        // candidates are deduplicated by equality, so distinct locales are never dropped
+        let expected = (0u32..1000)
+            .map(|n| langid!(format!("en-x{n:07}")))
+            .collect_vec();
+        let rulebook = FnRulebook(|l: &LanguageIdentifier| {
+            if l.variants().len() == 0 {
+                expected.clone()
+            } else {
+                vec![]
+            }
+        });
+        let solver = LocaleFallbackSolver { rulebook };
+        assert_eq!(solver.solve_locale(langid!("en")), expected);
+    }
+}
@@ -0,0 +1,105 @@
+//! Detection of common mistakes in custom rules, see [`crate::Rulebook::verify`].
+use crate::{ExpansionGuard, FnRulebook, LanguageIdentifier, RunawayExpansion};
+use itertools::Itertools;
+
+/// A mistake in a rulebook found by [`crate::Rulebook::verify`].
+///
+/// Rules are referred to by their index in [`crate::Rulebook::rules`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RuleDiagnostic {
+    /// The rule gives `locale` as a fallback of itself.
+    SelfLoop {
+        rule: usize,
+        locale: LanguageIdentifier,
+    },
+    /// The rule gives a fallback of `locale` whose language subtag is not a valid ISO 639 code.
+    InvalidTag {
+        rule: usize,
+        locale: LanguageIdentifier,
+        fallback: LanguageIdentifier,
+    },
+    /// The fallback chain of a sample grows suspiciously, see [`ExpansionGuard`].
+    Runaway(RunawayExpansion),
+    /// All fallbacks the rule gives for `locale` are already given by earlier rules, so the rule
+    /// has no effect on it.
+    Shadowed {
+        rule: usize,
+        locale: LanguageIdentifier,
+    },
+}
+
+impl std::fmt::Display for RuleDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SelfLoop { rule, locale } => {
+                write!(f, "rule #{rule} falls back from `{locale}` to itself")
+            }
+            Self::InvalidTag {
+                rule,
+                locale,
+                fallback,
+            } => write!(
+                f,
+                "rule #{rule} gives `{fallback}` for `{locale}`, which has an unknown language"
+            ),
+            Self::Runaway(runaway) => runaway.fmt(f),
+            Self::Shadowed { rule, locale } => write!(
+                f,
+                "rule #{rule} is shadowed by earlier rules for `{locale}`"
+            ),
+        }
+    }
+}
+
+/// Whether the language subtag of `locale` is a known ISO 639 code.
+fn known_language(locale: &LanguageIdentifier) -> bool {
+    let lang = locale.language.as_str();
+    match lang.len() {
+        2 => isolang::Language::from_639_1(lang).is_some(),
+        3 => lang != "und" && isolang::Language::from_639_3(lang).is_some(),
+        _ => false,
+    }
+}
+
+/// See [`crate::Rulebook::verify`].
+pub fn verify<F, I>(rules: &[F], samples: I) -> Vec<RuleDiagnostic>
+where
+    F: std::ops::Deref<Target: Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier>>,
+    I: IntoIterator<Item = LanguageIdentifier>,
+{
+    let rulebook =
+        FnRulebook(|l: &LanguageIdentifier| rules.iter().flat_map(|f| f(l)).collect_vec());
+    let guard = ExpansionGuard::default().with_hard_error(true);
+    let mut diagnostics = vec![];
+    for locale in samples {
+        let mut earlier: Vec<LanguageIdentifier> = vec![];
+        for (rule, f) in rules.iter().enumerate() {
+            let fallbacks = f(&locale);
+            if fallbacks.contains(&locale) {
+                diagnostics.push(RuleDiagnostic::SelfLoop {
+                    rule,
+                    locale: locale.clone(),
+                });
+            }
+            let invalid = fallbacks.iter().filter(|l| !known_language(l));
+            diagnostics.extend(invalid.unique().map(|fallback| RuleDiagnostic::InvalidTag {
+                rule,
+                locale: locale.clone(),
+                fallback: fallback.clone(),
+            }));
+            if !fallbacks.is_empty() && fallbacks.iter().all(|l| earlier.contains(l)) {
+                diagnostics.push(RuleDiagnostic::Shadowed {
+                    rule,
+                    locale: locale.clone(),
+                });
+            }
+            earlier.extend(fallbacks);
+        }
+        let (_, runaway) = crate::solve_with(&rulebook, &locale, &(), guard.checker(&locale));
+        diagnostics.extend(runaway.map(RuleDiagnostic::Runaway));
+    }
+    #[cfg(feature = "tracing")]
+    tracing::debug!(?diagnostics, "verified rulebook");
+    diagnostics
+}
@@ -0,0 +1,388 @@
+use crate::macros::IntoLangIdAble;
+use unic_langid::{LanguageIdentifier, subtags::Region};
+
+/// A [`LanguageIdentifier`] parsed with the leniency of [`crate::langid!`].
+///
+/// Unlike [`LanguageIdentifier`]'s own [`std::str::FromStr`] implementation, `_` separators, any
+/// casing, POSIX codesets and legacy tags are accepted. This makes it suitable for parsing user
+/// inputs with [`str::parse`], e.g. in command line arguments or configuration files.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, Locale};
+/// let locale: Locale = "zh_TW.UTF-8".parse().unwrap();
+/// assert_eq!(*locale, langid!["zh-TW"]);
+/// assert_eq!(locale.to_string(), "zh-TW");
+/// assert!("en-US".parse::<poly_l10n::LanguageIdentifier>().is_ok());
+/// assert!("en_US.UTF-8".parse::<poly_l10n::LanguageIdentifier>().is_err());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Locale(pub LanguageIdentifier);
+
+impl std::str::FromStr for Locale {
+    type Err = unic_langid::LanguageIdentifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.to_langid().map(Self)
+    }
+}
+
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::ops::Deref for Locale {
+    type Target = LanguageIdentifier;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl std::ops::DerefMut for Locale {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl AsRef<LanguageIdentifier> for Locale {
+    fn as_ref(&self) -> &LanguageIdentifier {
+        &self.0
+    }
+}
+
+impl From<LanguageIdentifier> for Locale {
+    fn from(value: LanguageIdentifier) -> Self {
+        Self(value)
+    }
+}
+impl From<Locale> for LanguageIdentifier {
+    fn from(value: Locale) -> Self {
+        value.0
+    }
+}
+
+/// Parse `input` as leniently as possible, never failing.
+///
+/// The input is first parsed like [`crate::langid!`]. If that fails, any run of characters other
+/// than ASCII letters and digits is treated as a separator, and subtags are dropped from the end
+/// until the rest parses. [`LanguageIdentifier::default`] (`und`) is only returned if not even
+/// the first subtag is a valid language. This suits pipelines ingesting dirty data, such as
+/// analytics logs.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, parse_lossy};
+/// assert_eq!(parse_lossy("zh_TW.UTF-8"), langid!("zh-TW"));
+/// assert_eq!(parse_lossy(" en US "), langid!("en-US"));
+/// assert_eq!(parse_lossy("pt--BR;q=0.8"), langid!("pt-BR"));
+/// assert_eq!(parse_lossy("de-AT-!!-ÄÖÜ"), langid!("de-AT"));
+/// assert_eq!(parse_lossy("fr-CA-x"), langid!("fr-CA"));
+/// assert_eq!(parse_lossy("???"), langid!("und"));
+/// ```
+#[must_use]
+pub fn parse_lossy(input: &str) -> LanguageIdentifier {
+    /// Subtags considered at most, bounding the work for hostile inputs.
+    const MAX_SUBTAGS: usize = 16;
+    if let Ok(langid) = input.to_langid() {
+        return langid;
+    }
+    let subtags: Vec<&str> = (input.split(|c: char| !c.is_ascii_alphanumeric()))
+        .filter(|s| !s.is_empty())
+        .take(MAX_SUBTAGS)
+        .collect();
+    (1..=subtags.len())
+        .rev()
+        .find_map(|n| subtags.get(..n)?.join("-").to_langid().ok())
+        .unwrap_or_default()
+}
+
+/// The extensions of a locale, e.g. `u-ca-buddhist-nu-thai` of `th-TH-u-ca-buddhist-nu-thai`.
+///
+/// This covers every subtag starting from the first singleton (a subtag of length 1, such as
+/// `u`, `t` or `x`). The subtags are validated and lowercased but otherwise kept as-is.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Extensions(String);
+
+impl Extensions {
+    /// The extensions without the leading separator, e.g. `u-nu-thai`. Empty if there are none.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The value of the Unicode locale extension (`-u-`) keyword `key`, e.g. `buddhist` for `ca`
+    /// in `u-ca-buddhist-nu-thai`. Multi-subtag values are returned whole (`islamic-civil`), and a
+    /// keyword without a value has the value `true`.
+    ///
+    /// # Examples
+    /// ```
+    /// use poly_l10n::Extensions;
+    /// let ext: Extensions = "u-ca-islamic-civil-kn-nu-thai-x-foo".parse().unwrap();
+    /// assert_eq!(ext.unicode_keyword("ca"), Some("islamic-civil"));
+    /// assert_eq!(ext.unicode_keyword("kn"), Some("true"));
+    /// assert_eq!(ext.unicode_keyword("nu"), Some("thai"));
+    /// assert_eq!(ext.unicode_keyword("rg"), None);
+    /// ```
+    #[must_use]
+    pub fn unicode_keyword(&self, key: &str) -> Option<&str> {
+        let mut offset = 0_usize;
+        let mut in_unicode = false;
+        // start offset of the value of `key` once found
+        let mut value: Option<usize> = None;
+        for subtag in self.0.split('-') {
+            let start = offset;
+            offset = offset.saturating_add(subtag.len()).saturating_add(1);
+            let end_of_value = subtag.len() <= 2;
+            if let Some(value) = value.filter(|_| end_of_value) {
+                let end = start.saturating_sub(1);
+                return Some(
+                    self.0
+                        .get(value..end)
+                        .filter(|v| !v.is_empty())
+                        .unwrap_or("true"),
+                );
+            }
+            if subtag == "x" {
+                // private use subtags are not keywords
+                break;
+            } else if subtag.len() == 1 {
+                in_unicode = subtag == "u";
+            } else if in_unicode && subtag == key {
+                value = Some(offset);
+            }
+        }
+        let value = value?;
+        Some(
+            self.0
+                .get(value..)
+                .filter(|v| !v.is_empty())
+                .unwrap_or("true"),
+        )
+    }
+
+    /// The region override of the `-u-rg-` keyword, e.g. `US` for `u-rg-uszzzz`.
+    ///
+    /// The override says that region-dependent preferences (units, currency, regional
+    /// fallbacks, …) should follow that region instead of the region of the language tag.
+    ///
+    /// # Examples
+    /// ```
+    /// use poly_l10n::Extensions;
+    /// let ext: Extensions = "u-rg-uszzzz".parse().unwrap();
+    /// assert_eq!(ext.region_override().map(|r| r.to_string()).as_deref(), Some("US"));
+    /// let ext: Extensions = "u-rg-419zzzz".parse().unwrap();
+    /// assert_eq!(ext.region_override().map(|r| r.to_string()).as_deref(), Some("419"));
+    /// ```
+    #[must_use]
+    pub fn region_override(&self) -> Option<Region> {
+        subdivision_region(self.unicode_keyword("rg")?)
+    }
+
+    /// The subdivision of the `-u-sd-` keyword and its parent region, e.g. `escn` and `ES` for
+    /// `u-sd-escn` (the Canary Islands).
+    ///
+    /// # Examples
+    /// ```
+    /// use poly_l10n::Extensions;
+    /// let ext: Extensions = "u-sd-escn".parse().unwrap();
+    /// let (sd, region) = ext.subdivision().unwrap();
+    /// assert_eq!((sd, region.as_str()), ("escn", "ES"));
+    /// ```
+    #[must_use]
+    pub fn subdivision(&self) -> Option<(&str, Region)> {
+        let sd = self.unicode_keyword("sd")?;
+        Some((sd, subdivision_region(sd)?))
+    }
+
+    /// These extensions without the Unicode locale extension keyword `key` (and its value).
+    ///
+    /// # Examples
+    /// ```
+    /// use poly_l10n::Extensions;
+    /// let ext: Extensions = "u-ca-islamic-civil-sd-escn-x-foo".parse().unwrap();
+    /// assert_eq!(ext.without_unicode_keyword("ca").as_str(), "u-sd-escn-x-foo");
+    /// assert_eq!(ext.without_unicode_keyword("sd").as_str(), "u-ca-islamic-civil-x-foo");
+    /// let ext: Extensions = "u-sd-escn".parse().unwrap();
+    /// assert!(ext.without_unicode_keyword("sd").is_empty());
+    /// let ext: Extensions = "x-sd-escn".parse().unwrap();
+    /// assert_eq!(ext.without_unicode_keyword("sd"), ext);
+    /// assert_eq!(ext.subdivision(), None);
+    /// ```
+    #[must_use]
+    pub fn without_unicode_keyword(&self, key: &str) -> Self {
+        let mut subtags: Vec<&str> = vec![];
+        let mut in_unicode = false;
+        let mut skipping = false;
+        let mut private_use = false;
+        for subtag in self.0.split('-').filter(|s| !s.is_empty()) {
+            private_use |= subtag == "x";
+            if private_use {
+                subtags.push(subtag);
+            } else if subtag.len() == 1 {
+                in_unicode = subtag == "u";
+                skipping = false;
+            } else if in_unicode && subtag.len() == 2 {
+                skipping = subtag == key;
+            }
+            if !skipping && !private_use {
+                subtags.push(subtag);
+            }
+        }
+        // drop a `u` left without keywords or attributes
+        let mut out: Vec<&str> = vec![];
+        for (i, subtag) in subtags.iter().enumerate() {
+            let next = subtags.get(i.saturating_add(1));
+            if *subtag == "x" {
+                out.extend(subtags.get(i..).unwrap_or_default());
+                break;
+            }
+            if *subtag == "u" && next.is_none_or(|n| n.len() == 1) {
+                continue;
+            }
+            out.push(subtag);
+        }
+        Self(out.join("-"))
+    }
+}
+
+/// The region of a Unicode subdivision identifier like `uszzzz` or `gbsct`.
+fn subdivision_region(subdivision: &str) -> Option<Region> {
+    let len = if subdivision.starts_with(|c: char| c.is_ascii_digit()) {
+        3
+    } else {
+        2
+    };
+    let (region, suffix) = (subdivision.get(..len)?, subdivision.get(len..)?);
+    if suffix.is_empty() || suffix.len() > 4 {
+        return None;
+    }
+    region.parse().ok()
+}
+
+/// The context given to rulebooks by [`crate::LocaleFallbackSolver::solve_locale_with_extensions`]
+/// when the locale has a region override (`-u-rg-`), see [`Extensions::region_override`].
+///
+/// Rulebooks can read it with [`crate::ContextRulebook`] or
+/// [`crate::PolyL10nRulebook::find_fallback_locale_in`] to make region-dependent decisions
+/// follow the override.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RegionOverride(pub Region);
+
+impl std::str::FromStr for Extensions {
+    type Err = unic_langid::LanguageIdentifierError;
+
+    /// Parse extensions such as `u-nu-thai`. Both `-` and `_` are accepted as separators.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || unic_langid::parser::ParserError::InvalidSubtag.into();
+        let mut out = String::with_capacity(s.len());
+        // whether the previous subtag is a singleton, which must be followed by another subtag
+        let mut after_singleton = false;
+        for (i, subtag) in s.split(['-', '_']).filter(|_| !s.is_empty()).enumerate() {
+            if subtag.is_empty()
+                || subtag.len() > 8
+                || !subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+                || (i == 0 && subtag.len() != 1)
+                || (after_singleton && subtag.len() == 1 && !subtag.eq_ignore_ascii_case("x"))
+            {
+                return Err(invalid());
+            }
+            after_singleton = subtag.len() == 1;
+            if i != 0 {
+                out.push('-');
+            }
+            out.push_str(&subtag.to_ascii_lowercase());
+        }
+        if after_singleton {
+            return Err(invalid());
+        }
+        Ok(Self(out))
+    }
+}
+
+impl std::fmt::Display for Extensions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A [`LanguageIdentifier`] together with its [`Extensions`], such as `-u-ca-` calendar or `-u-nu-`
+/// numbering system preferences.
+///
+/// [`crate::LocaleFallbackSolver`] only works on [`LanguageIdentifier`]s. With
+/// [`crate::LocaleFallbackSolver::solve_locale_with_extensions`], the extensions of the input are
+/// carried through untouched to every fallback instead.
+///
+/// Parsing is lenient in the same way as [`Locale`].
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, ExtendedLocale};
+/// let locale: ExtendedLocale = "th_TH-u-nu-thai".parse().unwrap();
+/// assert_eq!(locale.id, langid!("th-TH"));
+/// assert_eq!(locale.extensions.as_str(), "u-nu-thai");
+/// assert_eq!(locale.to_string(), "th-TH-u-nu-thai");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ExtendedLocale {
+    pub id: LanguageIdentifier,
+    pub extensions: Extensions,
+}
+
+impl std::str::FromStr for ExtendedLocale {
+    type Err = unic_langid::LanguageIdentifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        // the first singleton starts the extensions
+        let singleton = |i: &usize| {
+            let subtag = s.split_at(*i).1.get(1..).unwrap_or_default();
+            subtag
+                .split(['-', '_'])
+                .next()
+                .is_some_and(|t| t.len() == 1)
+        };
+        let Some(split) = s.match_indices(['-', '_']).map(|(i, _)| i).find(singleton) else {
+            return Ok(Self {
+                id: s.to_langid()?,
+                extensions: Extensions::default(),
+            });
+        };
+        let (id, extensions) = s.split_at(split);
+        Ok(Self {
+            id: id.to_langid()?,
+            extensions: extensions.get(1..).unwrap_or_default().parse()?,
+        })
+    }
+}
+
+impl std::fmt::Display for ExtendedLocale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.id.fmt(f)?;
+        if !self.extensions.is_empty() {
+            write!(f, "-{}", self.extensions)?;
+        }
+        Ok(())
+    }
+}
+
+impl AsRef<LanguageIdentifier> for ExtendedLocale {
+    fn as_ref(&self) -> &LanguageIdentifier {
+        &self.id
+    }
+}
+
+impl From<LanguageIdentifier> for ExtendedLocale {
+    fn from(id: LanguageIdentifier) -> Self {
+        Self {
+            id,
+            extensions: Extensions::default(),
+        }
+    }
+}
@@ -7,6 +7,10 @@ use unic_langid::LanguageIdentifier;
 /// As far as concerned, both ISO 639-1 and 639-3 are accepted, but [`unic_langid`] considers them
 /// as **DIFFERENT** language codes.
 ///
+/// Parsing is lenient: subtags are case-insensitive and may be separated by `_`, and the codeset
+/// and modifier of POSIX locales (`en_US.UTF-8`, `de_DE@euro`) are ignored. Legacy forms are
+/// canonicalized with [`crate::canonicalize::canonicalize_tag`] (`zh-yue` becomes `yue`).
+///
 /// # Examples
 ///
 /// ```
@@ -16,6 +20,11 @@ use unic_langid::LanguageIdentifier;
 ///   unic_langid::LanguageIdentifier::from_bytes(b"zho").unwrap()
 /// );
 /// assert_eq!(langid!["en_US"], langid!["en-US"]);
+/// assert_eq!(langid!["EN_us"], langid!["en-US"]);
+/// assert_eq!(langid!["zh_hant_tw"].to_string(), "zh-Hant-TW");
+/// assert_eq!(langid!["en_US.UTF-8"], langid!["en-US"]);
+/// // paths are parsed by their file stems
+/// assert_eq!(langid!(std::path::Path::new("locales/zh-Hant.ftl")), langid!["zh-Hant"]);
 /// // IMPORTANT: 639-1/2/3 all can be parsed, but they are treated as *different* IDs.
 /// assert_ne!(langid!["fr"], langid!["fra"]);
 /// ```
@@ -29,17 +38,43 @@ macro_rules! langid {
         ))
     };
     ($lang:expr) => {{
+        use $crate::macros::IntoLangIdAble as _;
         let lang = $lang;
-        match $crate::macros::IntoLangIdAble::to_langid(lang) {
+        match lang.to_langid() {
             Ok(id) => id,
-            Err(e) => Err(e).expect(format!(
-                "cannot parse language identifier langid!(\"{lang}\")"
-            )),
+            Err(e) => panic!("cannot parse language identifier langid!({lang:?}): {e}"),
         }
     }};
     ($($lang:tt),+$(,)?) => {[$($crate::langid!($lang)),+]}
 }
 
+/// Create a [`Vec`] of [`unic_langid::LanguageIdentifier`]s from the given strings.
+///
+/// Each argument is parsed the same way as [`langid!`]. Unlike `langid!["en", "fr"]`, which gives
+/// a fixed-size array, this gives a [`Vec`] that can be extended or passed as a slice.
+///
+/// Prefix the arguments with `static` to get a [`std::sync::LazyLock`] instead, which can be used
+/// to initialize a `static` item; the strings are then only parsed once on first access.
+///
+/// # Examples
+///
+/// ```
+/// # use poly_l10n::{langid, langids, LanguageIdentifier};
+/// let mut locales = langids!["en", "zh_TW"];
+/// locales.push(langid!("fr"));
+/// assert_eq!(locales, langid!["en", "zh-TW", "fr"]);
+///
+/// static AVAILABLE: std::sync::LazyLock<Vec<LanguageIdentifier>> = langids![static "en", "ja"];
+/// assert_eq!(*AVAILABLE, langid!["en", "ja"]);
+/// ```
+#[macro_export]
+macro_rules! langids {
+    (static $($lang:tt),*$(,)?) => {
+        ::std::sync::LazyLock::new(|| ::std::vec![$($crate::langid!($lang)),*])
+    };
+    ($($lang:tt),*$(,)?) => { ::std::vec![$($crate::langid!($lang)),*] };
+}
+
 /// See [`IntoLangIdAble::to_langid()`].
 pub trait IntoLangIdAble {
     /// Turn `self` into `LanguageIdentifier`.
@@ -53,34 +88,21 @@ pub trait IntoLangIdAble {
 
 impl IntoLangIdAble for str {
     fn to_langid(&self) -> Result<LanguageIdentifier, unic_langid::LanguageIdentifierError> {
-        self.find('.')
-            .and_then(|i| locale_str_to_langid(self, i))
-            .unwrap_or_else(|| LanguageIdentifier::from_bytes(self.as_bytes()))
+        let tag = crate::canonicalize::canonicalize_tag(strip_posix_locale(self));
+        LanguageIdentifier::from_bytes(tag.as_bytes())
     }
 }
 
-#[allow(clippy::arithmetic_side_effects, clippy::indexing_slicing)]
-fn locale_str_to_langid(
-    locale: &str,
-    i: usize,
-) -> Option<Result<LanguageIdentifier, unic_langid::LanguageIdentifierError>> {
-    let bs = isolang::Language::from_locale(locale)?;
-    let mut count = 0;
-    while (locale.as_bytes().get(count))
-        .is_some_and(|b| ![b'_', b'-'].contains(b) && locale.len() > count)
-    {
-        count += 1;
-    }
-    // count is the number of characters until and excluding the `-` or the `_`
-    let mut bs = if count == 2 {
-        bs.to_639_1().unwrap()
-    } else {
-        bs.to_639_3()
-    }
-    .as_bytes()
-    .to_owned();
-    bs.extend_from_slice(&locale.as_bytes()[count + 2..i]);
-    Some(LanguageIdentifier::from_bytes(&bs))
+/// Remove the `.codeset` and `@modifier` parts of a POSIX locale string (e.g. `en_US.UTF-8`),
+/// along with surrounding whitespaces.
+///
+/// The casing of the remaining subtags does not matter: [`LanguageIdentifier::from_bytes`] folds
+/// them into the BCP-47 conventions (`zh_hant_tw` becomes `zh-Hant-TW`).
+fn strip_posix_locale(locale: &str) -> &str {
+    let locale = locale.trim();
+    locale
+        .find(['.', '@'])
+        .map_or(locale, |i| locale.split_at(i).0)
 }
 
 impl IntoLangIdAble for String {
@@ -90,8 +112,38 @@ impl IntoLangIdAble for String {
 }
 impl IntoLangIdAble for [u8] {
     fn to_langid(&self) -> Result<LanguageIdentifier, unic_langid::LanguageIdentifierError> {
-        (self.iter().position(|&b| b == b'.'))
-            .and_then(|i| locale_str_to_langid(core::str::from_utf8(self).ok()?, i))
-            .unwrap_or_else(|| LanguageIdentifier::from_bytes(self))
+        core::str::from_utf8(self).map_or_else(
+            |_| LanguageIdentifier::from_bytes(self),
+            IntoLangIdAble::to_langid,
+        )
+    }
+}
+impl IntoLangIdAble for std::ffi::OsStr {
+    fn to_langid(&self) -> Result<LanguageIdentifier, unic_langid::LanguageIdentifierError> {
+        self.as_encoded_bytes().to_langid()
+    }
+}
+impl IntoLangIdAble for std::ffi::OsString {
+    fn to_langid(&self) -> Result<LanguageIdentifier, unic_langid::LanguageIdentifierError> {
+        self.as_os_str().to_langid()
+    }
+}
+/// Parse the file stem of the path, e.g. `locales/zh-Hant.ftl` gives `zh-Hant`.
+impl IntoLangIdAble for std::path::Path {
+    fn to_langid(&self) -> Result<LanguageIdentifier, unic_langid::LanguageIdentifierError> {
+        self.file_stem().map_or_else(
+            || Err(unic_langid::parser::ParserError::InvalidLanguage.into()),
+            IntoLangIdAble::to_langid,
+        )
+    }
+}
+impl IntoLangIdAble for std::path::PathBuf {
+    fn to_langid(&self) -> Result<LanguageIdentifier, unic_langid::LanguageIdentifierError> {
+        self.as_path().to_langid()
+    }
+}
+impl<T: IntoLangIdAble + ToOwned + ?Sized> IntoLangIdAble for std::borrow::Cow<'_, T> {
+    fn to_langid(&self) -> Result<LanguageIdentifier, unic_langid::LanguageIdentifierError> {
+        self.as_ref().to_langid()
     }
 }
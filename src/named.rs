@@ -0,0 +1,252 @@
+//! Rules that can be switched off at runtime, see [`RuleSwitches`].
+use crate::{ARulebook, LanguageIdentifier, RuleState, Rulebook};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A rule with a name and a switch, see [`RuleSwitches`].
+///
+/// A disabled rule gives no fallbacks.
+#[derive(Clone)]
+pub struct NamedRule<F> {
+    name: String,
+    enabled: Arc<AtomicBool>,
+    rule: F,
+}
+
+impl<F> std::fmt::Debug for NamedRule<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NamedRule")
+            .field("name", &self.name)
+            .field("enabled", &self.is_enabled())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier>> NamedRule<F> {
+    /// An enabled rule.
+    pub fn new<S: Into<String>>(name: S, rule: F) -> Self {
+        Self {
+            name: name.into(),
+            enabled: Arc::new(AtomicBool::new(true)),
+            rule,
+        }
+    }
+
+    /// The fallbacks of `locale` given by the rule, or none if it is disabled.
+    pub fn call(&self, locale: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+        if self.is_enabled() {
+            (self.rule)(locale)
+        } else {
+            vec![]
+        }
+    }
+}
+
+impl<F> NamedRule<F> {
+    /// The name of the rule.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the rule is enabled.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Enable or disable the rule, including in rulebooks it was added to.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+}
+
+/// The switches of the named rules of a rulebook, kept as its [`Rulebook::owned_values`].
+///
+/// Rulebooks with named rules are built with [`Rulebook::with_named`] (or
+/// [`ARulebook::with_named`]), starting from [`Rulebook::named`]. Rules are switched by name with
+/// [`Rulebook::set_rule_enabled`], e.g. from a settings screen. Clones of a rulebook share the
+/// switches. The switches follow the rules when they are removed or appended, see [`RuleState`].
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, LocaleFallbackSolver, Rulebook};
+/// let rulebook = Rulebook::named()
+///     .with_named("es-pt", |l| match l.language.as_str() {
+///         "es" => vec![langid!("pt")],
+///         _ => vec![],
+///     })
+///     .with_named("en", |_| vec![langid!("en")]);
+/// let solver = LocaleFallbackSolver { rulebook };
+/// assert_eq!(solver.solve_locale(langid!("es")), langid!["pt", "en"]);
+/// assert!(solver.rulebook.set_rule_enabled("es-pt", false));
+/// assert_eq!(solver.solve_locale(langid!("es")), [langid!("en")]);
+/// assert_eq!(solver.rulebook.is_rule_enabled("es-pt"), Some(false));
+/// assert!(!solver.rulebook.set_rule_enabled("nonexistent", false));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RuleSwitches(Vec<Option<Switch>>);
+
+/// The name and switch of a rule, at the index of the rule.
+type Switch = (String, Arc<AtomicBool>);
+
+impl RuleSwitches {
+    pub(crate) const fn new() -> Self {
+        Self(vec![])
+    }
+
+    /// Enable or disable all rules named `name`. Returns whether there is any.
+    #[allow(clippy::must_use_candidate)]
+    pub fn set(&self, name: &str, enabled: bool) -> bool {
+        let mut found = false;
+        for (_, switch) in self.switches().filter(|(n, _)| n == name) {
+            switch.store(enabled, Ordering::Relaxed);
+            found = true;
+        }
+        found
+    }
+
+    /// Whether the rule named `name` is enabled, or [`None`] if there is no such rule.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<bool> {
+        self.switches()
+            .find(|(n, _)| n == name)
+            .map(|(_, switch)| switch.load(Ordering::Relaxed))
+    }
+
+    /// The names of the rules, in the order of the rules.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.switches().map(|(name, _)| name.as_str())
+    }
+
+    fn switches(&self) -> impl Iterator<Item = &Switch> {
+        self.0.iter().flatten()
+    }
+
+    /// Register `rule` as the rule at `index`.
+    fn register<F>(&mut self, index: usize, rule: &NamedRule<F>) {
+        self.0.resize(index, None);
+        (self.0).push(Some((rule.name.clone(), Arc::clone(&rule.enabled))));
+    }
+}
+
+impl From<()> for RuleSwitches {
+    fn from((): ()) -> Self {
+        Self::new()
+    }
+}
+
+impl RuleState for RuleSwitches {
+    fn append(&mut self, len: usize, other: Self) {
+        self.0.resize(len, None);
+        self.0.extend(other.0);
+    }
+
+    fn remove(&mut self, index: usize) {
+        if index < self.0.len() {
+            self.0.remove(index);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+impl Rulebook<RuleSwitches> {
+    /// Append the rule `f` named `name`. See [`RuleSwitches`].
+    #[must_use]
+    pub fn with_named<S, F>(self, name: S, f: F) -> Self
+    where
+        S: Into<String>,
+        F: Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> + 'static,
+    {
+        self.with_named_rule(NamedRule::new(name, f))
+    }
+
+    /// Append `rule`, which can still be switched with [`NamedRule::set_enabled`].
+    #[must_use]
+    pub fn with_named_rule<F>(mut self, rule: NamedRule<F>) -> Self
+    where
+        F: Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> + 'static,
+    {
+        self.owned_values.register(self.rules.len(), &rule);
+        self.rules.push(std::rc::Rc::new(move |l| rule.call(l)));
+        self
+    }
+
+    /// Enable or disable the rules named `name`. Returns whether there is any.
+    #[allow(clippy::must_use_candidate)]
+    pub fn set_rule_enabled(&self, name: &str, enabled: bool) -> bool {
+        self.owned_values.set(name, enabled)
+    }
+
+    /// Whether the rule named `name` is enabled, or [`None`] if there is no such rule.
+    #[must_use]
+    pub fn is_rule_enabled(&self, name: &str) -> Option<bool> {
+        self.owned_values.get(name)
+    }
+}
+
+impl ARulebook<RuleSwitches> {
+    /// See [`Rulebook::with_named`].
+    #[must_use]
+    pub fn with_named<S, F>(self, name: S, f: F) -> Self
+    where
+        S: Into<String>,
+        F: Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> + Send + Sync + 'static,
+    {
+        self.with_named_rule(NamedRule::new(name, f))
+    }
+
+    /// See [`Rulebook::with_named_rule`].
+    #[must_use]
+    pub fn with_named_rule<F>(mut self, rule: NamedRule<F>) -> Self
+    where
+        F: Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> + Send + Sync + 'static,
+    {
+        self.owned_values.register(self.rules.len(), &rule);
+        self.rules.push(Arc::new(move |l| rule.call(l)));
+        self
+    }
+
+    /// See [`Rulebook::set_rule_enabled`].
+    #[allow(clippy::must_use_candidate)]
+    pub fn set_rule_enabled(&self, name: &str, enabled: bool) -> bool {
+        self.owned_values.set(name, enabled)
+    }
+
+    /// See [`Rulebook::is_rule_enabled`].
+    #[must_use]
+    pub fn is_rule_enabled(&self, name: &str) -> Option<bool> {
+        self.owned_values.get(name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{LocaleFallbackSolver, langid};
+
+    #[test]
+    fn switches_follow_rules() {
+        let mut rulebook = Rulebook::named()
+            .with_named("a", |_| vec![langid!("aa")])
+            .with_named("b", |_| vec![langid!("bb")]);
+        rulebook.push_fn(|_| vec![langid!("cc")]);
+        let rulebook = rulebook.with_named("d", |_| vec![langid!("dd")]);
+        let mut rulebook = rulebook + Rulebook::named().with_named("e", |_| vec![langid!("ee")]);
+        assert!(rulebook.remove_rule(0).is_some());
+        assert!(rulebook.remove_rule(1).is_some());
+        assert!(rulebook.owned_values.names().eq(["b", "d", "e"]));
+        assert!(!rulebook.set_rule_enabled("a", false));
+        assert!(rulebook.set_rule_enabled("d", false));
+        let solver = LocaleFallbackSolver { rulebook };
+        assert_eq!(solver.solve_locale(langid!("en")), langid!["bb", "ee"]);
+        let mut rulebook = solver.rulebook;
+        rulebook.clear();
+        assert_eq!(rulebook.owned_values.names().count(), 0);
+        assert!(!rulebook.set_rule_enabled("b", false));
+        assert_eq!(rulebook.is_rule_enabled("e"), None);
+    }
+}
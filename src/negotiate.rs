@@ -0,0 +1,575 @@
+//! Negotiation of the best available locale for the locales a user wants.
+use crate::{LanguageIdentifier, LocaleSolver};
+
+/// The `Vary` header value for responses negotiated on the `Accept-Language` request header.
+pub const VARY: &str = "Accept-Language";
+
+/// Parse a quality value (`q=0.8`) into thousandths.
+fn parse_q(q: &str) -> Option<u16> {
+    let (int, frac) = q.split_once('.').unwrap_or((q, ""));
+    if frac.len() > 3 || !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let frac = format!("{frac:0<3}").parse::<u16>().ok()?;
+    match int {
+        "0" => Some(frac),
+        "1" if frac == 0 => Some(1000),
+        _ => None,
+    }
+}
+
+/// Parse an HTTP `Accept-Language` header into the wanted locales, most preferred first.
+///
+/// Tags are normalized with [`crate::interop::from_browser_tag`]. Entries with a quality of 0,
+/// invalid qualities or unparsable tags, and the wildcard `*` are skipped. Entries with equal
+/// quality keep their order in the header.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, negotiate::parse_accept_language};
+/// assert_eq!(
+///     parse_accept_language("fr-CH, fr;q=0.9, en;q=0.8, de;q=0.7, *;q=0.5, ja;q=0"),
+///     langid!["fr-CH", "fr", "en", "de"]
+/// );
+/// assert_eq!(parse_accept_language("en;q=0.5, zh-TW"), langid!["zh-TW", "en"]);
+/// ```
+#[must_use]
+pub fn parse_accept_language(header: &str) -> Vec<LanguageIdentifier> {
+    let mut entries: Vec<(u16, LanguageIdentifier)> = (header.split(','))
+        .filter_map(|entry| {
+            let mut params = entry.split(';').map(str::trim);
+            let tag = params.next()?;
+            let q = params
+                .find_map(|p| p.strip_prefix("q=").or_else(|| p.strip_prefix("Q=")))
+                .map_or(Some(1000), parse_q)?;
+            Some((q, crate::interop::from_browser_tag(tag)?)).filter(|(q, _)| *q > 0)
+        })
+        .collect();
+    entries.sort_by(|(a, _), (b, _)| b.cmp(a));
+    entries.into_iter().map(|(_, locale)| locale).collect()
+}
+
+/// Parse an HTTP `Content-Language` header into its locales.
+///
+/// Tags are normalized with [`crate::interop::from_browser_tag`]; unparsable ones are skipped.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, negotiate::parse_content_language};
+/// assert_eq!(parse_content_language("mi, en-nz"), langid!["mi", "en-NZ"]);
+/// assert!(parse_content_language("").is_empty());
+/// ```
+#[must_use]
+pub fn parse_content_language(header: &str) -> Vec<LanguageIdentifier> {
+    (header.split(','))
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .filter_map(crate::interop::from_browser_tag)
+        .collect()
+}
+
+/// The `Content-Language` header value for content in the `locales`, e.g. `mi, en-NZ`.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, negotiate::content_language};
+/// assert_eq!(content_language(&langid!["mi", "en-NZ"]), "mi, en-NZ");
+/// ```
+#[must_use]
+pub fn content_language<L: AsRef<LanguageIdentifier>>(locales: &[L]) -> String {
+    (locales.iter())
+        .map(|l| l.as_ref().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The response headers for content negotiated on `Accept-Language`: `Content-Language` for the
+/// locale actually `served` (after fallback, not the requested one), if any, and `Vary`.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::negotiate::{negotiate, parse_accept_language, response_headers};
+/// use poly_l10n::{langid, LocaleFallbackSolver, Rulebook};
+/// let solver = LocaleFallbackSolver {
+///     rulebook: Rulebook::from_fn(|l| match l.to_string().as_str() {
+///         "de-AT" => vec![langid!("de")],
+///         _ => vec![],
+///     }),
+/// };
+/// let available = langid!["de", "en"];
+/// let served = negotiate(&solver, &parse_accept_language("de-AT"), &available);
+/// assert_eq!(
+///     response_headers(served),
+///     [("Content-Language", "de".to_owned()), ("Vary", "Accept-Language".to_owned())]
+/// );
+/// ```
+#[must_use]
+pub fn response_headers<L: AsRef<LanguageIdentifier>>(
+    served: Option<&L>,
+) -> Vec<(&'static str, String)> {
+    let content_language = served.map(|l| ("Content-Language", content_language(&[l])));
+    (content_language.into_iter())
+        .chain([("Vary", VARY.to_owned())])
+        .collect()
+}
+
+/// Find the best locale in `available` for the `wanted` locales, most preferred first.
+///
+/// Each wanted locale is tried in order, followed by its fallbacks from `solver`; the first one
+/// that is available wins. Returns [`None`] if nothing matches.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, negotiate::negotiate, LocaleFallbackSolver, Rulebook};
+/// let solver = LocaleFallbackSolver {
+///     rulebook: Rulebook::from_fn(|l| match l.to_string().as_str() {
+///         "zh-TW" => vec![langid!("zh-Hant")],
+///         _ => vec![],
+///     }),
+/// };
+/// let available = langid!["en", "zh-Hant", "zh-Hans"];
+/// assert_eq!(negotiate(&solver, &langid!["zh-TW", "en"], &available), Some(&langid!("zh-Hant")));
+/// assert_eq!(negotiate(&solver, &[langid!("ko")], &available), None);
+/// ```
+pub fn negotiate<'a, S: LocaleSolver, L: AsRef<LanguageIdentifier>>(
+    solver: &S,
+    wanted: &[LanguageIdentifier],
+    available: &'a [L],
+) -> Option<&'a L> {
+    negotiate_with_distance(solver, wanted, available).map(|(found, _)| found)
+}
+
+/// Like [`negotiate`], but also returns the distance of the match.
+///
+/// The distance is the position of the match in the fallback chain of the wanted locale it was
+/// found for, `0` being the wanted locale itself and `n` its `n`th fallback from `solver`.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, negotiate::negotiate_with_distance, LocaleFallbackSolver, Rulebook};
+/// let solver = LocaleFallbackSolver {
+///     rulebook: Rulebook::from_fn(|l| match l.to_string().as_str() {
+///         "de-AT" => vec![langid!("de-DE"), langid!("de")],
+///         _ => vec![],
+///     }),
+/// };
+/// let available = langid!["de", "en"];
+/// assert_eq!(
+///     negotiate_with_distance(&solver, &[langid!("de-AT")], &available),
+///     Some((&langid!("de"), 2))
+/// );
+/// ```
+pub fn negotiate_with_distance<'a, S: LocaleSolver, L: AsRef<LanguageIdentifier>>(
+    solver: &S,
+    wanted: &[LanguageIdentifier],
+    available: &'a [L],
+) -> Option<(&'a L, usize)> {
+    negotiate_within(solver, wanted, available, usize::MAX)
+}
+
+/// Like [`negotiate_with_distance`], but only accept matches at a distance of at most
+/// `max_distance`.
+///
+/// A wanted locale whose nearest available fallback is too far away is skipped in favour of the
+/// next wanted locale. If none is close enough, [`None`] is returned so that the caller can use
+/// its ultimate fallback instead of a loosely related match, e.g. a rule falling back from `gsw`
+/// (Swiss German) to `de` may be acceptable for UI texts but not for a legal document.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, negotiate::negotiate_within, LocaleFallbackSolver, Rulebook};
+/// let solver = LocaleFallbackSolver {
+///     rulebook: Rulebook::from_fn(|l| match l.to_string().as_str() {
+///         "gsw-CH" => vec![langid!("gsw"), langid!("de-CH"), langid!("de")],
+///         _ => vec![],
+///     }),
+/// };
+/// let available = langid!["de", "fr"];
+/// let wanted = langid!["gsw-CH", "fr"];
+/// assert_eq!(negotiate_within(&solver, &wanted, &available, 1), Some((&langid!("fr"), 0)));
+/// assert_eq!(negotiate_within(&solver, &wanted[..1], &available, 1), None);
+/// assert_eq!(negotiate_within(&solver, &wanted, &available, 3), Some((&langid!("de"), 3)));
+/// ```
+pub fn negotiate_within<'a, S: LocaleSolver, L: AsRef<LanguageIdentifier>>(
+    solver: &S,
+    wanted: &[LanguageIdentifier],
+    available: &'a [L],
+    max_distance: usize,
+) -> Option<(&'a L, usize)> {
+    let find = |locale: &LanguageIdentifier| available.iter().find(|a| a.as_ref() == locale);
+    let found = wanted.iter().find_map(|locale| {
+        find(locale).map(|l| (l, 0)).or_else(|| {
+            let fallbacks = solver.solve_locale(locale);
+            (fallbacks.iter().take(max_distance).enumerate())
+                .find_map(|(i, fallback)| find(fallback).map(|l| (l, i.saturating_add(1))))
+        })
+    });
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        ?wanted,
+        max_distance,
+        found = ?found.map(|(l, distance)| (l.as_ref(), distance)),
+        "negotiated locale"
+    );
+    found
+}
+
+/// Pick up to `k` locales in distinct languages from `available` for the `wanted` locales, most
+/// preferred first, with their distances (see [`negotiate_with_distance`]).
+///
+/// Each wanted locale is matched like in [`negotiate`], except that available locales in a
+/// language already picked are skipped. This suits media players choosing e.g. an audio track and
+/// a subtitle track in a different language.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, negotiate::pick_top_k, LocaleFallbackSolver, Rulebook};
+/// let solver = LocaleFallbackSolver {
+///     rulebook: Rulebook::from_fn(|l| match l.to_string().as_str() {
+///         "de-AT" => vec![langid!("de")],
+///         "en-GB" => vec![langid!("en")],
+///         _ => vec![],
+///     }),
+/// };
+/// let tracks = langid!["en", "de", "de-CH", "fr"];
+/// let wanted = langid!["de-AT", "de-CH", "en-GB"];
+/// assert_eq!(
+///     pick_top_k(&solver, &wanted, &tracks, 2),
+///     [(&langid!("de"), 1), (&langid!("en"), 1)]
+/// );
+/// assert_eq!(pick_top_k(&solver, &wanted, &tracks, 1), [(&langid!("de"), 1)]);
+/// assert!(pick_top_k(&solver, &[langid!("ko")], &tracks, 2).is_empty());
+/// ```
+pub fn pick_top_k<'a, S: LocaleSolver, L: AsRef<LanguageIdentifier>>(
+    solver: &S,
+    wanted: &[LanguageIdentifier],
+    available: &'a [L],
+    k: usize,
+) -> Vec<(&'a L, usize)> {
+    let mut picked: Vec<(&'a L, usize)> = vec![];
+    for locale in wanted {
+        if picked.len() >= k {
+            break;
+        }
+        let is_new = |l: &LanguageIdentifier| {
+            !(picked.iter()).any(|(p, _)| p.as_ref().language == l.language)
+        };
+        if !is_new(locale) {
+            continue;
+        }
+        let find = |l: &LanguageIdentifier| {
+            (available.iter()).find(|a| a.as_ref() == l && is_new(a.as_ref()))
+        };
+        let found = find(locale).map(|l| (l, 0)).or_else(|| {
+            (solver.solve_locale(locale).iter().enumerate())
+                .find_map(|(i, fallback)| find(fallback).map(|l| (l, i.saturating_add(1))))
+        });
+        picked.extend(found);
+    }
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        ?wanted,
+        k,
+        picked = ?picked.iter().map(|(l, distance)| (l.as_ref(), distance)).collect::<Vec<_>>(),
+        "picked locales"
+    );
+    picked
+}
+
+/// Locale of a file variant named like `index.zh-hant.html`, i.e. the second last extension.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, negotiate::file_variant_locale};
+/// assert_eq!(file_variant_locale("docs/index.zh-hant.html"), Some(langid!("zh-Hant")));
+/// assert_eq!(file_variant_locale("index.html"), None);
+/// ```
+#[must_use]
+pub fn file_variant_locale(path: &str) -> Option<LanguageIdentifier> {
+    let name = path.rsplit(['/', '\\']).next()?;
+    let mut parts = name.rsplit('.');
+    let (_ext, tag, _stem) = (parts.next()?, parts.next()?, parts.next()?);
+    tag.parse().ok()
+}
+
+/// A file variant selected by [`negotiate_file`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileVariant<'a> {
+    /// The selected file.
+    pub path: &'a str,
+    /// The locale of the file, or [`None`] for a language-neutral file such as `index.html`.
+    pub locale: Option<LanguageIdentifier>,
+}
+
+impl FileVariant<'_> {
+    /// The `Content-Language` header value, if the file has a locale.
+    #[must_use]
+    pub fn content_language(&self) -> Option<String> {
+        self.locale.as_ref().map(ToString::to_string)
+    }
+
+    /// The response headers to send with the file: `Content-Language` (if the file has a locale)
+    /// and `Vary`.
+    #[must_use]
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        response_headers(self.locale.as_ref())
+    }
+}
+
+/// Select the variant of a pre-rendered file, among `files` named like `index.en.html` and
+/// `index.zh-hant.html`, for a request with the `Accept-Language` header `accept_language`.
+///
+/// If no localized variant matches, a language-neutral variant (`index.html`) is selected if
+/// there is one, else the first file. Returns [`None`] only if `files` is empty.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, negotiate::negotiate_file, LocaleFallbackSolver, Rulebook};
+/// let solver = LocaleFallbackSolver {
+///     rulebook: Rulebook::from_fn(|l| match l.to_string().as_str() {
+///         "zh-TW" => vec![langid!("zh-Hant")],
+///         _ => vec![],
+///     }),
+/// };
+/// let files = ["index.html", "index.en.html", "index.zh-hant.html"];
+/// let variant = negotiate_file(&solver, "zh-TW,en;q=0.5", &files).unwrap();
+/// assert_eq!(variant.path, "index.zh-hant.html");
+/// assert_eq!(
+///     variant.headers(),
+///     [("Content-Language", "zh-Hant".to_owned()), ("Vary", "Accept-Language".to_owned())]
+/// );
+/// let variant = negotiate_file(&solver, "ko", &files).unwrap();
+/// assert_eq!(variant.path, "index.html");
+/// assert_eq!(variant.content_language(), None);
+/// ```
+pub fn negotiate_file<'a, S: LocaleSolver, P: AsRef<str>>(
+    solver: &S,
+    accept_language: &str,
+    files: &'a [P],
+) -> Option<FileVariant<'a>> {
+    let variants: Vec<_> = (files.iter())
+        .map(|path| FileVariant {
+            path: path.as_ref(),
+            locale: file_variant_locale(path.as_ref()),
+        })
+        .collect();
+    let localized: Vec<_> = (variants.iter())
+        .filter_map(|v| Some(LocalizedVariant(v.locale.as_ref()?, v)))
+        .collect();
+    let wanted = parse_accept_language(accept_language);
+    if let Some(LocalizedVariant(_, variant)) = negotiate(solver, &wanted, &localized) {
+        return Some((*variant).clone());
+    }
+    (variants.iter())
+        .find(|v| v.locale.is_none())
+        .or_else(|| variants.first())
+        .cloned()
+}
+
+struct LocalizedVariant<'a, 'b>(&'b LanguageIdentifier, &'b FileVariant<'a>);
+
+impl AsRef<LanguageIdentifier> for LocalizedVariant<'_, '_> {
+    fn as_ref(&self) -> &LanguageIdentifier {
+        self.0
+    }
+}
+
+/// The canonical URL path segment of `locale`: its lowercase BCP-47 tag, like `zh-hant`.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, negotiate::url_segment};
+/// assert_eq!(url_segment(&langid!("zh-Hant-TW")), "zh-hant-tw");
+/// ```
+#[must_use]
+pub fn url_segment(locale: &LanguageIdentifier) -> String {
+    locale.to_string().to_ascii_lowercase()
+}
+
+/// Split the leading locale segment off a URL path like `/zh-hant/docs/x`, returning the locale
+/// among `available` and the rest of the path (`/docs/x`).
+///
+/// The segment is matched case-insensitively, with `_` accepted for `-`. Only `available`
+/// locales are recognized, since ordinary segments like `docs` are valid language tags too. The
+/// rest is `/` for a bare locale path like `/en`, and starts with `?` or `#` for `/en?q=x`.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, negotiate::split_locale_path};
+/// let available = langid!["en", "zh-Hant"];
+/// assert_eq!(
+///     split_locale_path("/zh-hant/docs/x", &available),
+///     Some((&langid!("zh-Hant"), "/docs/x"))
+/// );
+/// assert_eq!(split_locale_path("/en", &available), Some((&langid!("en"), "/")));
+/// assert_eq!(split_locale_path("/EN?q=x", &available), Some((&langid!("en"), "?q=x")));
+/// assert_eq!(split_locale_path("/docs/x", &available), None);
+/// ```
+pub fn split_locale_path<'a, 'p, L: AsRef<LanguageIdentifier>>(
+    path: &'p str,
+    available: &'a [L],
+) -> Option<(&'a L, &'p str)> {
+    let trimmed = path.strip_prefix('/').unwrap_or(path);
+    let (segment, rest) = trimmed
+        .find(['/', '?', '#'])
+        .map_or((trimmed, ""), |i| trimmed.split_at(i));
+    let segment = segment.replace('_', "-");
+    let locale =
+        (available.iter()).find(|l| url_segment(l.as_ref()).eq_ignore_ascii_case(&segment))?;
+    Some((locale, if rest.is_empty() { "/" } else { rest }))
+}
+
+/// The path to redirect a request for `path` to, for a website routed by a leading locale
+/// segment (see [`split_locale_path`]), or [`None`] if `path` already has a locale.
+///
+/// The locale is negotiated with [`negotiate`] for the `Accept-Language` header
+/// `accept_language`, falling back to `default`, and prefixed to `path` as its [`url_segment`].
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, negotiate::redirect_path, LocaleFallbackSolver, Rulebook};
+/// let solver = LocaleFallbackSolver {
+///     rulebook: Rulebook::from_fn(|l| match l.to_string().as_str() {
+///         "zh-TW" => vec![langid!("zh-Hant")],
+///         _ => vec![],
+///     }),
+/// };
+/// let available = langid!["en", "zh-Hant"];
+/// let default = langid!("en");
+/// let redirect = |path, header| redirect_path(&solver, path, header, &available, &default);
+/// assert_eq!(redirect("/docs/x", "zh-TW,en;q=0.5").as_deref(), Some("/zh-hant/docs/x"));
+/// assert_eq!(redirect("/", "ko").as_deref(), Some("/en/"));
+/// assert_eq!(redirect("/en/docs", "zh-TW"), None);
+/// ```
+pub fn redirect_path<S: LocaleSolver, L: AsRef<LanguageIdentifier>>(
+    solver: &S,
+    path: &str,
+    accept_language: &str,
+    available: &[L],
+    default: &LanguageIdentifier,
+) -> Option<String> {
+    if split_locale_path(path, available).is_some() {
+        return None;
+    }
+    let wanted = parse_accept_language(accept_language);
+    let locale = negotiate(solver, &wanted, available).map_or(default, AsRef::as_ref);
+    let path = path.strip_prefix('/').unwrap_or(path);
+    Some(format!("/{}/{path}", url_segment(locale)))
+}
+
+/// Mapping of the host names of a website to locales, e.g. `example.de` to `de-DE`.
+///
+/// Configured aliases match the domain and its subdomains, the longest matching alias winning.
+/// With the feature `likely_subtags`, country-code top-level domains can also be mapped to the
+/// most likely language of the country ([`Self::cctld`]).
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, negotiate::DomainLocales};
+/// let domains = DomainLocales::new()
+///     .alias("example.com.br", langid!("pt-BR"))
+///     .alias("example.de", langid!("de-DE"));
+/// assert_eq!(domains.locale_of("www.example.de:8080"), Some(langid!("de-DE")));
+/// assert_eq!(domains.locale_of("Example.com.BR"), Some(langid!("pt-BR")));
+/// assert_eq!(domains.locale_of("example.com"), None);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[must_use]
+pub struct DomainLocales {
+    aliases: Vec<(String, LanguageIdentifier)>,
+    #[cfg(feature = "likely_subtags")]
+    cctld: bool,
+}
+
+impl DomainLocales {
+    /// A mapping without aliases, not using country-code top-level domains.
+    pub const fn new() -> Self {
+        Self {
+            aliases: vec![],
+            #[cfg(feature = "likely_subtags")]
+            cctld: false,
+        }
+    }
+
+    /// Map `domain` and its subdomains to `locale`.
+    pub fn alias<S: Into<String>>(mut self, domain: S, locale: LanguageIdentifier) -> Self {
+        let domain: String = domain.into();
+        self.aliases.push((domain.to_ascii_lowercase(), locale));
+        self
+    }
+
+    /// Whether to map country-code top-level domains without an alias to the most likely
+    /// language of the country, e.g. `example.de` to `de-DE` and `example.tw` to `zh-TW`.
+    ///
+    /// Only available with the feature `likely_subtags`.
+    ///
+    /// # Examples
+    /// ```
+    /// use poly_l10n::{langid, negotiate::DomainLocales};
+    /// let domains = DomainLocales::new().cctld(true);
+    /// assert_eq!(domains.locale_of("example.de"), Some(langid!("de-DE")));
+    /// assert_eq!(domains.locale_of("example.co.uk"), Some(langid!("en-GB")));
+    /// assert_eq!(domains.locale_of("example.eu"), None);
+    /// ```
+    #[cfg(feature = "likely_subtags")]
+    pub const fn cctld(mut self, cctld: bool) -> Self {
+        self.cctld = cctld;
+        self
+    }
+
+    /// The locale of the host `host` (a `Host` header value, with an optional port).
+    #[must_use]
+    pub fn locale_of(&self, host: &str) -> Option<LanguageIdentifier> {
+        let host = host.rsplit_once(':').map_or(host, |(host, _)| host);
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        let alias = (self.aliases.iter())
+            .filter(|(domain, _)| {
+                (host.strip_suffix(domain.as_str()))
+                    .is_some_and(|sub| sub.is_empty() || sub.ends_with('.'))
+            })
+            .max_by_key(|(domain, _)| domain.len());
+        if let Some((_, locale)) = alias {
+            return Some(locale.clone());
+        }
+        #[cfg(feature = "likely_subtags")]
+        if self.cctld {
+            return cctld_locale(host.rsplit('.').next()?);
+        }
+        None
+    }
+}
+
+/// The most likely locale of the country with the country-code top-level domain `tld`.
+#[cfg(feature = "likely_subtags")]
+fn cctld_locale(tld: &str) -> Option<LanguageIdentifier> {
+    let region = match tld {
+        "uk" => "GB",
+        // not country codes
+        "eu" | "su" => return None,
+        _ if tld.len() == 2 => tld,
+        _ => return None,
+    };
+    let mut locale: LanguageIdentifier = format!("und-{region}").parse().ok()?;
+    if !locale.maximize() {
+        return None;
+    }
+    locale.script = None;
+    Some(locale)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn accept_language_q() {
+        assert_eq!(parse_q("1"), Some(1000));
+        assert_eq!(parse_q("1.000"), Some(1000));
+        assert_eq!(parse_q("0.5"), Some(500));
+        assert_eq!(parse_q("0.125"), Some(125));
+        for bad in ["1.5", "2", "0.1234", "-0.5", "", "0.x"] {
+            assert_eq!(parse_q(bad), None, "{bad}");
+        }
+        assert!(parse_accept_language("en;q=2, , ;q=1").is_empty());
+    }
+}
@@ -0,0 +1,50 @@
+//! Solving many locales on multiple threads, see [`LocaleFallbackSolver::solve_locales_par`].
+//!
+//! This module is gated behind the feature `parallel`.
+use crate::{FallbackChain, LanguageIdentifier, LocaleFallbackSolver, PolyL10nRulebook};
+use itertools::Itertools;
+
+impl<R: for<'a> PolyL10nRulebook<'a> + Sync> LocaleFallbackSolver<R> {
+    /// Solve each of `locales` independently with [`Self::solve_locale`], on as many threads as
+    /// there are available cores, e.g. to precompute a routing table at startup. The chains are
+    /// in the order of `locales`.
+    ///
+    /// Unlike [`Self::solve_locales`], the chains are not merged. The rulebook must be [`Sync`],
+    /// e.g. an [`crate::ARulebook`] rather than a [`crate::Rulebook`].
+    ///
+    /// ```
+    /// use poly_l10n::{langid, ARulebook, LocaleFallbackSolver};
+    /// let solver = LocaleFallbackSolver { rulebook: ARulebook::default() };
+    /// let locales = langid!["de-AT", "zh-Hant-HK", "arb"];
+    /// let chains = solver.solve_locales_par(&locales);
+    /// assert_eq!(chains.len(), 3);
+    /// for (locale, chain) in locales.iter().zip(chains) {
+    ///     assert_eq!(chain, solver.solve_locale(locale));
+    /// }
+    /// ```
+    pub fn solve_locales_par<I>(&self, locales: I) -> Vec<FallbackChain>
+    where
+        I: IntoIterator<Item: AsRef<LanguageIdentifier>>,
+    {
+        let locales = (locales.into_iter())
+            .map(|l| l.as_ref().clone())
+            .collect_vec();
+        let threads = std::thread::available_parallelism()
+            .map_or(1, std::num::NonZero::get)
+            .min(locales.len());
+        if threads <= 1 {
+            return locales.iter().map(|l| self.solve_locale(l)).collect();
+        }
+        let chunk_len = locales.len().div_ceil(threads);
+        std::thread::scope(|s| {
+            let handles = (locales.chunks(chunk_len))
+                .map(|chunk| {
+                    s.spawn(move || chunk.iter().map(|l| self.solve_locale(l)).collect_vec())
+                })
+                .collect_vec();
+            (handles.into_iter())
+                .flat_map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+                .collect()
+        })
+    }
+}
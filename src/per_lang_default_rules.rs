@@ -5,14 +5,70 @@
 //!
 //! This module is gated behind the feature `per_lang_default_rules`.
 use isolang::Language;
+use std::collections::HashMap;
 use unic_langid::LanguageIdentifier;
 
+/// Number of languages known to [`isolang`].
 pub const ISOLANG_OVERVIEW_LEN: usize = 7916;
 
-type OptArcFnLangFallbacks = Option<
-    std::sync::Arc<dyn Fn(&LanguageIdentifier, &Language) -> Vec<LanguageIdentifier> + Sync + Send>,
->;
-type InnerLangRules = [OptArcFnLangFallbacks; ISOLANG_OVERVIEW_LEN];
+/// The scripts of Kazakh (`kk`), default first.
+///
+/// Kazakhstan is transitioning from Cyrillic to Latin; locales without a script prefer the first
+/// script and fall back to the second, and locales with either script fall back to the other one.
+/// Swap them once Latin becomes the default.
+pub const KAZAKH_SCRIPTS: [&str; 2] = ["Cyrl", "Latn"];
+
+/// Rules for Berber languages: `tzm`, `zgh`, `shi`, `rif`, `kab`, and the collective code `ber`.
+///
+/// They are written in Tifinagh, Latin and Arabic scripts. Tifinagh is preferred in Morocco
+/// (`MA`), the default region except for Kabyle, and Latin in Algeria (`DZ`); the other scripts
+/// are fallbacks. The Moroccan varieties also fall back to Standard Moroccan Tamazight (`zgh`),
+/// and all of them to the collective `ber` of their region, which falls back to its members.
+pub(crate) fn berber_rules(l: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+    let lang = l.language.as_str();
+    let default_region = if lang == "kab" { "DZ" } else { "MA" };
+    let region = l
+        .region
+        .as_ref()
+        .map_or(default_region, unic_langid::subtags::Region::as_str);
+    let members = match (lang, l.region) {
+        ("ber", None) => vec![("zgh", "MA"), ("kab", "DZ")],
+        ("ber", _) if region == "DZ" => vec![("kab", region)],
+        ("ber", _) if region == "MA" => ["zgh", "tzm", "shi", "rif"].map(|m| (m, region)).to_vec(),
+        ("ber", _) => vec![],
+        ("tzm" | "shi" | "rif", _) if region == "MA" => vec![(lang, region), ("zgh", region)],
+        _ => vec![(lang, region)],
+    };
+    let mut rules = vec![];
+    for (member, region) in members {
+        let mut scripts = match region {
+            "DZ" => vec!["Latn", "Tfng", "Arab"],
+            _ => vec!["Tfng", "Latn", "Arab"],
+        };
+        if let Some(script) = &l.script {
+            scripts.retain(|s| *s != script.as_str());
+            scripts.insert(0, script.as_str());
+        }
+        rules.extend(
+            scripts
+                .iter()
+                .map(|script| format!("{member}-{script}-{region}")),
+        );
+    }
+    if lang != "ber" {
+        rules.push(format!("ber-{region}"));
+    }
+    (rules.iter())
+        .map(|rule| rule.parse().expect("cannot parse berber rule"))
+        .filter(|rule| rule != l)
+        .collect()
+}
+
+type ArcFnLangFallbacks =
+    std::sync::Arc<dyn Fn(&LanguageIdentifier, &Language) -> Vec<LanguageIdentifier> + Sync + Send>;
+/// Only a handful of languages have special rules, so a map is used instead of an array indexed
+/// by [`Language`] (which would be [`ISOLANG_OVERVIEW_LEN`] entries long and nearly all empty).
+type InnerLangRules = HashMap<Language, ArcFnLangFallbacks>;
 
 macro_rules! gen_langrules {
     ($l:ident $lang:ident: $($($Lang:ident)|+ $(if $guard:expr)? => $rule:expr),+$(,)?) => {
@@ -20,9 +76,15 @@ macro_rules! gen_langrules {
     };
     ([$dollar:tt] $l:ident $lang:ident: $($($Lang:ident)|+ $(if $guard:expr)? => $rule:expr),+$(,)?) => {{
         // why pub(crate) aaaaaaaaaaaaa
-        let mut arr: InnerLangRules = [const { None }; ISOLANG_OVERVIEW_LEN];
+        let mut map = InnerLangRules::new();
 
         macro_rules! rules {
+            // literals are parsed only once, subsequent calls just clone the cached identifiers
+            ($dollar($r:literal),*$dollar(,)?) => {{
+                static RULES: std::sync::LazyLock<Vec<LanguageIdentifier>> =
+                    std::sync::LazyLock::new(|| vec![$dollar($r.parse().expect(rules!(@$r))),*]);
+                RULES.clone()
+            }};
             ($dollar($r:expr),*$dollar(,)?) => {vec![$dollar({
                 let rule = $r;
                 rule.parse().expect(rules!(@rule))
@@ -37,10 +99,11 @@ macro_rules! gen_langrules {
                 [!ignore! $guard]
                 else { vec![] }
             )?]
-            $(arr[Language::$Lang as usize] = Some(std::sync::Arc::new(|$l: &LanguageIdentifier, $lang: &Language| #ifguard { $rule } #else));)+
+            $(map.insert(Language::$Lang, std::sync::Arc::new(|$l: &LanguageIdentifier, $lang: &Language| #ifguard { $rule } #else));)+
         )+ }
 
-        return arr;
+        map.shrink_to_fit();
+        return map;
     } };
 }
 
@@ -87,8 +150,78 @@ pub static LANG_RULES: std::sync::LazyLock<InnerLangRules> = std::sync::LazyLock
                 ],
             },
         },
-        Spa if l.variants().len() == 0 => rules!["es-ES", "spa-ES", "pt-PT", "por-PT"],
+        Spa if l.variants().len() == 0 => match l.region.as_ref().map(unic_langid::subtags::Region::as_str) {
+            // Latin America and the Caribbean
+            Some(
+                "AR" | "BO" | "BR" | "BZ" | "CL" | "CO" | "CR" | "CU" | "DO" | "EC" | "GT" | "HN"
+                | "MX" | "NI" | "PA" | "PE" | "PR" | "PY" | "SV" | "US" | "UY" | "VE",
+            ) => rules!["es-419", "spa-419", "es-ES", "spa-ES", "pt-PT", "por-PT"],
+            _ => rules!["es-ES", "spa-ES", "pt-PT", "por-PT"],
+        },
         Por if l.variants().len() == 0 => rules!["pt-PT", "por-PT", "es-ES", "spa-ES"],
+        Eng if l.variants().len() == 0 => match l.region.as_ref().map(unic_langid::subtags::Region::as_str) {
+            None | Some("001" | "US" | "AS" | "GU" | "MH" | "MP" | "PR" | "UM" | "VI") => vec![],
+            // Europe
+            Some("AT" | "BE" | "CH" | "DE" | "DK" | "FI" | "NL" | "SE" | "SI") => {
+                rules!["en-150", "eng-150", "en-001", "eng-001"]
+            }
+            // international English
+            Some(_) => rules!["en-001", "eng-001"],
+        },
+        // see `KAZAKH_SCRIPTS`; Kazakh in China is written in the Arabic script
+        Kaz if l.variants().len() == 0 => {
+            let [primary, secondary] = KAZAKH_SCRIPTS;
+            let region = l.region.as_ref().map_or("KZ", unic_langid::subtags::Region::as_str);
+            match l.script.as_ref().map(unic_langid::subtags::Script::as_str) {
+                Some(s) if s.eq_ignore_ascii_case(primary) || s.eq_ignore_ascii_case(secondary) => {
+                    let other = if s.eq_ignore_ascii_case(primary) { secondary } else { primary };
+                    rules![format!("kk-{s}-{region}"), format!("kk-{other}-{region}"), format!("kk-{other}")]
+                }
+                Some(_) => vec![],
+                None if region == "CN" => rules!["kk-Arab-CN", format!("kk-{primary}-KZ")],
+                None => rules![format!("kk-{primary}-{region}"), format!("kk-{secondary}-{region}")],
+            }
+        },
+        // Inuktitut is written in syllabics (Cans) and Latin; Eastern Canadian Inuktitut (`ike`)
+        // is mostly written in syllabics and Inuinnaqtun (`ikt`) in Latin
+        Iku | Ike | Ikt if l.variants().len() == 0 => {
+            let region = l.region.as_ref().map_or("CA", unic_langid::subtags::Region::as_str);
+            let default = if *lang == Language::Ikt { "Latn" } else { "Cans" };
+            let script = l.script.as_ref().map_or(default, unic_langid::subtags::Script::as_str);
+            let other = if script == "Cans" { "Latn" } else { "Cans" };
+            let code = lang.to_639_3();
+            match *lang {
+                Language::Iku => rules![
+                    format!("iu-{script}-{region}"),
+                    format!("iu-{other}-{region}"),
+                    format!("iu-{other}"),
+                ],
+                Language::Ikt => rules![format!("ikt-{script}-{region}"), format!("iu-{script}-{region}")],
+                _ => rules![
+                    format!("{code}-{script}-{region}"),
+                    format!("iu-{script}-{region}"),
+                    format!("{code}-{other}-{region}"),
+                    format!("iu-{other}-{region}"),
+                ],
+            }
+        },
+        Kab | Rif | Shi | Tzm | Zgh if l.variants().len() == 0 => berber_rules(l),
+        // national sign languages default to their region, and never fall back to spoken languages
+        Ase | Bfi | Bzs | Csn | Dse | Dsl | Fsl | Gsg | Gss | Isg | Ise | Jsl | Mfs | Ncs | Nsl | Psr
+            | Sfb | Sfs | Sgg | Ssp | Swl | Vgt if l.region.is_none() =>
+        {
+            match crate::canonicalize::sign_language_region(l.language.as_str()) {
+                Some(region) => rules![format!("{}-{region}", l.language)],
+                None => vec![],
+            }
+        },
+        // constructed languages have no regional norms: strip the other subtags instead of
+        // inventing regions like `eo-XX`, see also `crate::ConstructedFallback`
+        Afh | Avk | Bzt | Dws | Epo | Ido | Igs | Ile | Ina | Jbo | Ldn | Lfn | Neu | Nov | Qya
+            | Sjn | Tlh | Tzl | Vol => (lang.to_639_1().into_iter())
+            .chain([lang.to_639_3()])
+            .map(|code| code.parse().expect("cannot parse isolang code"))
+            .collect(),
         Yue => match l.script {
             Some(s) if s.as_str().eq_ignore_ascii_case("Hans") => {
                 rules!["yue-Hans-CN", "yue-Hant-HK", "yue-Hant-MO", "zho"]
@@ -124,6 +257,21 @@ pub static LANG_RULES: std::sync::LazyLock<InnerLangRules> = std::sync::LazyLock
     )
 });
 
+/// The languages with special rules in [`LANG_RULES`], sorted by their ISO 639-3 code.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{isolang::Language, per_lang_default_rules::special_languages};
+/// assert!(special_languages().contains(&Language::Yue));
+/// assert!(!special_languages().contains(&Language::Deu));
+/// ```
+#[must_use]
+pub fn special_languages() -> Vec<Language> {
+    let mut langs: Vec<_> = LANG_RULES.keys().copied().collect();
+    langs.sort_by_key(Language::to_639_3);
+    langs
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -132,4 +280,108 @@ mod test {
         assert!(Language::from_usize(ISOLANG_OVERVIEW_LEN).is_none());
         assert!(Language::from_usize(ISOLANG_OVERVIEW_LEN - 1).is_some());
     }
+
+    #[test]
+    fn sign_languages() {
+        let solver = crate::LocaleFallbackSolver::<crate::Rulebook>::default();
+        assert_eq!(
+            solver.solve_locale(crate::langid!("ase")),
+            crate::langid!["ase", "ase-US"]
+        );
+        assert_eq!(crate::langid!("sgn-GB"), crate::langid!("bfi"));
+        assert!(
+            (solver.solve_locale(crate::langid!("sgn-GB"))).contains(&crate::langid!("bfi-GB"))
+        );
+        let chain = solver.solve_locale(crate::langid!("ase-CA"));
+        assert!(chain.iter().all(|l| l.language == "ase"), "{chain:?}");
+    }
+
+    #[test]
+    fn kazakh() {
+        let solver = crate::LocaleFallbackSolver::<crate::Rulebook>::default();
+        // the fallbacks with a script, in order
+        let scripted = |locale: LanguageIdentifier| {
+            let chain = solver.solve_locale(locale);
+            chain
+                .into_iter()
+                .filter(|l| l.script.is_some())
+                .collect::<Vec<_>>()
+        };
+        let scripts = crate::langid!["kk-Cyrl-KZ", "kk-Latn-KZ"];
+        assert!(scripted(crate::langid!("kk")).starts_with(&scripts));
+        assert!(scripted(crate::langid!("kk-KZ")).starts_with(&scripts));
+        assert!(
+            scripted(crate::langid!("kk-Latn"))
+                .starts_with(&crate::langid!["kk-Latn-KZ", "kk-Cyrl-KZ"])
+        );
+        assert!(scripted(crate::langid!("kk-Cyrl-KZ")).contains(&crate::langid!("kk-Latn-KZ")));
+        assert_eq!(
+            scripted(crate::langid!("kk-CN")).first(),
+            Some(&crate::langid!("kk-Arab-CN"))
+        );
+    }
+
+    #[test]
+    fn berber() {
+        let solver = crate::LocaleFallbackSolver::<crate::Rulebook>::default();
+        let chain = solver.solve_locale(crate::langid!("tzm"));
+        assert!(
+            chain.starts_with(&crate::langid!["tzm", "tzm-Tfng-MA", "tzm-Latn-MA"]),
+            "{chain:?}"
+        );
+        assert!(chain.contains(&crate::langid!("zgh-Tfng-MA")), "{chain:?}");
+        let chain = solver.solve_locale(crate::langid!("kab"));
+        assert!(
+            chain.starts_with(&crate::langid!["kab", "kab-Latn-DZ", "kab-Tfng-DZ"]),
+            "{chain:?}"
+        );
+        let chain = solver.solve_locale(crate::langid!("shi-Latn"));
+        assert!(chain.contains(&crate::langid!("shi-Tfng-MA")), "{chain:?}");
+        let chain = solver.solve_locale(crate::langid!("ber-DZ"));
+        assert_eq!(chain.first(), Some(&crate::langid!("kab-Latn-DZ")));
+        let chain = solver.solve_locale(crate::langid!("ber"));
+        assert_eq!(chain.first(), Some(&crate::langid!("zgh-Tfng-MA")));
+    }
+
+    #[test]
+    fn inuktitut() {
+        let solver = crate::LocaleFallbackSolver::<crate::Rulebook>::default();
+        let chain = solver.solve_locale(crate::langid!("iu"));
+        assert!(
+            chain.starts_with(&crate::langid!["iu", "iu-Cans-CA", "iu-Latn-CA"]),
+            "{chain:?}"
+        );
+        let chain = solver.solve_locale(crate::langid!("iu-Latn"));
+        assert!(
+            chain.starts_with(&crate::langid!["iu", "iu-Latn-CA", "iu-Cans-CA"]),
+            "{chain:?}"
+        );
+        let chain = solver.solve_locale(crate::langid!("ike"));
+        assert!(
+            chain.starts_with(&crate::langid!["ike", "ike-Cans-CA", "iu-Cans-CA"]),
+            "{chain:?}"
+        );
+        let chain = solver.solve_locale(crate::langid!("ikt"));
+        assert!(
+            chain.starts_with(&crate::langid!["ikt", "ikt-Latn-CA", "iu-Latn-CA"]),
+            "{chain:?}"
+        );
+    }
+
+    #[test]
+    fn constructed_languages() {
+        let solver = crate::LocaleFallbackSolver::<crate::Rulebook>::default();
+        for (locale, language) in [("eo-FR", "eo"), ("ia-Latn-IT", "ia"), ("jbo-US", "jbo")] {
+            let chain = solver.solve_locale(locale.parse::<LanguageIdentifier>().unwrap());
+            assert!(chain.iter().any(|l| l.language == language), "{chain:?}");
+            assert!(
+                (chain.iter()).all(|l| l.region.is_none() && l.script.is_none()),
+                "{chain:?}"
+            );
+        }
+        assert_eq!(
+            solver.solve_locale(crate::langid!("eo-FR")),
+            crate::langid!["eo", "epo"]
+        );
+    }
 }
@@ -0,0 +1,125 @@
+//! Per-rule timing of rulebooks, see [`RuleProfiler`].
+use crate::LanguageIdentifier;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Invocation count and cumulative time of a rule, see [`RuleProfiler`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RuleStats {
+    /// Number of times the rule was called.
+    pub calls: u64,
+    /// Total time spent in the rule.
+    pub total: Duration,
+}
+
+type Hook = Arc<dyn Fn(usize, Duration) + Send + Sync>;
+
+/// Recorder of per-rule invocation counts and time, for finding slow closures in large custom
+/// rulebooks.
+///
+/// Use [`crate::Rulebook::profiled`] (or [`crate::ARulebook::profiled`]) to record the rules of a
+/// rulebook. Rules are referred to by their index in [`crate::Rulebook::rules`]. Clones of a
+/// profiler share the same records.
+///
+/// This type is gated behind the feature `profile`.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, LocaleFallbackSolver, RuleProfiler, Rulebook};
+/// let profiler = RuleProfiler::new();
+/// let rulebook = Rulebook::from_fns(vec![
+///     std::rc::Rc::new(|l: &poly_l10n::LanguageIdentifier| match l.to_string().as_str() {
+///         "de-AT" => vec![langid!("de")],
+///         _ => vec![],
+///     }),
+///     std::rc::Rc::new(|_: &_| vec![]),
+/// ]);
+/// let solver = LocaleFallbackSolver { rulebook: rulebook.profiled(&profiler) };
+/// assert_eq!(solver.solve_locale(langid!("de-AT")), [langid!("de")]);
+/// let stats = profiler.stats();
+/// assert_eq!(stats.len(), 2);
+/// // once for `de-AT`, once for `de`
+/// assert_eq!(stats[1].calls, 2);
+/// ```
+#[derive(Clone, Default)]
+#[must_use]
+pub struct RuleProfiler {
+    stats: Arc<Mutex<Vec<RuleStats>>>,
+    hook: Option<Hook>,
+}
+
+impl std::fmt::Debug for RuleProfiler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RuleProfiler")
+            .field("stats", &self.stats())
+            .finish_non_exhaustive()
+    }
+}
+
+impl RuleProfiler {
+    /// A profiler without records.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call `hook` with the index of the rule and the time spent after each call of a rule, e.g.
+    /// to feed an application's metrics.
+    ///
+    /// The hook only applies to rulebooks profiled after it is set.
+    pub fn on_rule<F: Fn(usize, Duration) + Send + Sync + 'static>(mut self, hook: F) -> Self {
+        self.hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// The records so far, indexed by rule. Rules that were never called may be missing at the
+    /// end.
+    #[must_use]
+    pub fn stats(&self) -> Vec<RuleStats> {
+        self.stats
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Clear the records.
+    pub fn reset(&self) {
+        self.stats
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    fn record(&self, rule: usize, elapsed: Duration) {
+        {
+            let mut stats = self.stats.lock().unwrap_or_else(PoisonError::into_inner);
+            if stats.len() <= rule {
+                stats.resize(rule.saturating_add(1), RuleStats::default());
+            }
+            if let Some(stats) = stats.get_mut(rule) {
+                stats.calls = stats.calls.saturating_add(1);
+                stats.total = stats.total.saturating_add(elapsed);
+            }
+        }
+        if let Some(hook) = &self.hook {
+            hook(rule, elapsed);
+        }
+    }
+
+    /// Wrap the rule `f` at index `rule` so that its calls are recorded.
+    pub(crate) fn wrap<F>(
+        &self,
+        rule: usize,
+        f: F,
+    ) -> impl Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> + use<F>
+    where
+        F: std::ops::Deref<Target: Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier>>,
+    {
+        let profiler = self.clone();
+        move |l| {
+            let start = Instant::now();
+            let fallbacks = f(l);
+            profiler.record(rule, start.elapsed());
+            fallbacks
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Diagnostics of solving, see [`SolveReport`].
+use crate::{
+    ExpansionGuard, FallbackChain, LanguageIdentifier, LocaleFallbackSolver, PolyL10nRulebook,
+    RunawayExpansion, SolveIter,
+};
+use itertools::Itertools;
+
+/// The fallbacks of a locale along with the problems found while solving them, which usually
+/// indicate a buggy custom rulebook. See [`LocaleFallbackSolver::solve_locale_report`].
+///
+/// This is displayed as one problem per line.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, ExpansionGuard, LocaleFallbackSolver};
+/// let solver = LocaleFallbackSolver::from_fn(|l| match l.to_string().as_str() {
+///     "de-AT" => vec![langid!("de-DE")],
+///     "de-DE" => vec![langid!("de")],
+///     "de" => vec![langid!("de-DE")],
+///     _ => vec![],
+/// });
+/// let report = solver.solve_locale_report(langid!("de-AT"), &ExpansionGuard::default());
+/// assert_eq!(report.chain, langid!["de-DE", "de"]);
+/// assert_eq!(report.cycles, [langid!["de-DE", "de", "de-DE"]]);
+/// assert_eq!(report.to_string(), "fallback cycle de-DE → de → de-DE\n");
+/// assert!(!report.is_clean());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolveReport {
+    /// The fallbacks, like [`LocaleFallbackSolver::solve_locale`].
+    pub chain: FallbackChain,
+    /// Cycles in the fallbacks, each from a locale back to itself, e.g. `[A, B, A]` if `A` falls
+    /// back to `B` and `B` back to `A`. Locales falling back to themselves are not reported.
+    pub cycles: Vec<Vec<LanguageIdentifier>>,
+    /// Suspicious growth of the chain detected with the [`ExpansionGuard`].
+    pub runaway: Vec<RunawayExpansion>,
+    /// Whether solving was stopped by the hard limits ([`crate::SOLVE_MAX_LOCALES`] and related).
+    pub truncated: bool,
+}
+
+impl SolveReport {
+    /// Whether no problems were found.
+    #[must_use]
+    pub const fn is_clean(&self) -> bool {
+        self.cycles.is_empty() && self.runaway.is_empty() && !self.truncated
+    }
+}
+
+impl std::fmt::Display for SolveReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for cycle in &self.cycles {
+            writeln!(f, "fallback cycle {}", cycle.iter().format(" → "))?;
+        }
+        for runaway in &self.runaway {
+            writeln!(f, "{runaway}")?;
+        }
+        if self.truncated {
+            writeln!(f, "solving stopped by hard limits")?;
+        }
+        Ok(())
+    }
+}
+
+impl<R: for<'a> PolyL10nRulebook<'a>> LocaleFallbackSolver<R> {
+    /// Same as [`Self::solve_locale`], but also detects cycles and suspicious growth of the chain,
+    /// for debugging custom rulebooks. See [`SolveReport`].
+    ///
+    /// Runaway expansions are reported to the hook of `guard` and logged like with
+    /// [`Self::solve_locale_guarded`], but never stop solving. Cycles are logged as warnings if
+    /// the feature `tracing` is enabled.
+    pub fn solve_locale_report<L: AsRef<LanguageIdentifier>>(
+        &self,
+        locale: L,
+        guard: &ExpansionGuard,
+    ) -> SolveReport {
+        let locale = locale.as_ref();
+        let mut detect = guard.detector(locale);
+        let mut iter = SolveIter::new(&self.rulebook, locale, &()).with_diagnostics();
+        let mut report = SolveReport::default();
+        for l in iter.by_ref() {
+            report.chain.push(l);
+            for runaway in detect(&report.chain) {
+                guard.warn(&runaway);
+                report.runaway.push(runaway);
+            }
+        }
+        report.cycles = iter.take_cycles();
+        report.truncated = iter.is_truncated();
+        report
+    }
+}
@@ -0,0 +1,239 @@
+//! Resolution of the locales to use from several sources, see [`LocaleResolver`].
+use crate::macros::IntoLangIdAble;
+use crate::negotiate::DomainLocales;
+use crate::{LanguageIdentifier, LocaleSolver};
+
+/// The environment variable read by [`LocaleResolver::env`].
+pub const LOCALE_ENV_VAR: &str = "POLY_L10N_LOCALE";
+
+/// A source of wanted locales for [`LocaleResolver`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LocaleSource {
+    /// An explicit command-line flag.
+    Flag,
+    /// The environment variable with the given name.
+    Env(String),
+    /// The host name of the request, see [`crate::negotiate::DomainLocales`].
+    Domain,
+    /// The choice persisted with a [`crate::ChoiceStore`].
+    Choice,
+    /// The locales detected from the system, see [`crate::system_want_langids`].
+    System,
+    /// Locales given by the application, e.g. a hard-coded default.
+    Fixed,
+}
+
+#[derive(Clone, Debug)]
+enum Source {
+    Flag(Option<String>),
+    Env(String),
+    Domain(Option<String>, DomainLocales),
+    #[cfg(feature = "persist")]
+    Choice(crate::ChoiceStore),
+    #[cfg(feature = "getlang_lite")]
+    System,
+    Fixed(Vec<LanguageIdentifier>),
+}
+
+/// Parse a list of locales like `de_AT:de` or `fr-CA, fr`, skipping invalid entries.
+fn parse_list(list: &str) -> Vec<LanguageIdentifier> {
+    (list.split([':', ',']))
+        .filter(|s| !s.trim().is_empty())
+        .filter_map(|s| s.to_langid().ok())
+        .collect()
+}
+
+impl Source {
+    fn kind(&self) -> LocaleSource {
+        match self {
+            Self::Flag(_) => LocaleSource::Flag,
+            Self::Env(var) => LocaleSource::Env(var.clone()),
+            Self::Domain(..) => LocaleSource::Domain,
+            #[cfg(feature = "persist")]
+            Self::Choice(_) => LocaleSource::Choice,
+            #[cfg(feature = "getlang_lite")]
+            Self::System => LocaleSource::System,
+            Self::Fixed(_) => LocaleSource::Fixed,
+        }
+    }
+
+    fn want_langids(&self) -> Vec<LanguageIdentifier> {
+        match self {
+            Self::Flag(flag) => flag.as_deref().map(parse_list).unwrap_or_default(),
+            Self::Env(var) => (std::env::var(var).ok().as_deref())
+                .map(parse_list)
+                .unwrap_or_default(),
+            Self::Domain(host, domains) => (host
+                .as_deref()
+                .and_then(|host| domains.locale_of(host))
+                .into_iter())
+            .collect(),
+            #[cfg(feature = "persist")]
+            Self::Choice(store) => store.load_choice().into_iter().collect(),
+            #[cfg(feature = "getlang_lite")]
+            Self::System => crate::system_want_langids().collect(),
+            Self::Fixed(langids) => langids.clone(),
+        }
+    }
+}
+
+/// Resolution of the locales an application should use, from sources in a declared order of
+/// precedence.
+///
+/// Sources are consulted in the order they are added to the builder, and the first one providing
+/// at least one valid locale wins; the remaining sources are ignored. A typical precedence is an
+/// explicit command-line flag, then the [`LOCALE_ENV_VAR`] environment variable, then the choice
+/// saved in the application's settings, then the system locales.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, LocaleResolver, LocaleSource, LocaleFallbackSolver, Rulebook};
+/// let resolver = LocaleResolver::new()
+///     .flag(None::<String>) // e.g. `--lang` was not passed
+///     .env_var("POLY_L10N_DOCTEST_UNSET")
+///     .fixed(langid!["de-AT", "en"]);
+/// assert_eq!(
+///     resolver.resolve_with_source(),
+///     Some((LocaleSource::Fixed, langid!["de-AT", "en"].to_vec()))
+/// );
+///
+/// let resolver = LocaleResolver::new().flag(Some("fr_CA:fr")).fixed([langid!("en")]);
+/// assert_eq!(resolver.resolve(), langid!["fr-CA", "fr"]);
+///
+/// let solver = LocaleFallbackSolver {
+///     rulebook: Rulebook::from_fn(|l| match l.to_string().as_str() {
+///         "fr-CA" => vec![langid!("fr")],
+///         _ => vec![],
+///     }),
+/// };
+/// assert_eq!(resolver.resolve_chain(&solver), langid!["fr-CA", "fr"]);
+/// ```
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct LocaleResolver {
+    sources: Vec<Source>,
+}
+
+impl LocaleResolver {
+    /// A resolver without sources.
+    pub const fn new() -> Self {
+        Self { sources: vec![] }
+    }
+
+    /// Consult the value of a command-line flag, a list of locales like `de_AT:de` or `fr-CA,fr`.
+    /// [`None`] (the flag was not passed) provides no locale.
+    pub fn flag<S: Into<String>>(mut self, flag: Option<S>) -> Self {
+        self.sources.push(Source::Flag(flag.map(Into::into)));
+        self
+    }
+
+    /// Consult the environment variable [`LOCALE_ENV_VAR`].
+    pub fn env(self) -> Self {
+        self.env_var(LOCALE_ENV_VAR)
+    }
+
+    /// Consult the environment variable `var`, a list of locales like `de_AT:de` or `fr-CA,fr`.
+    pub fn env_var<S: Into<String>>(mut self, var: S) -> Self {
+        self.sources.push(Source::Env(var.into()));
+        self
+    }
+
+    /// Consult the locale of the host name `host` of a request, e.g. from its `Host` header,
+    /// mapped by `domains`. [`None`] (no host name) provides no locale.
+    ///
+    /// # Examples
+    /// ```
+    /// use poly_l10n::{langid, negotiate::DomainLocales, LocaleResolver, LocaleSource};
+    /// let domains = DomainLocales::new().alias("example.de", langid!("de-DE"));
+    /// let resolver = LocaleResolver::new()
+    ///     .flag(None::<String>) // e.g. no `?lang=` query parameter
+    ///     .domain(Some("example.de"), domains)
+    ///     .fixed([langid!("en")]);
+    /// assert_eq!(
+    ///     resolver.resolve_with_source(),
+    ///     Some((LocaleSource::Domain, vec![langid!("de-DE")]))
+    /// );
+    /// ```
+    pub fn domain<S: Into<String>>(mut self, host: Option<S>, domains: DomainLocales) -> Self {
+        self.sources
+            .push(Source::Domain(host.map(Into::into), domains));
+        self
+    }
+
+    /// Consult the choice persisted in `store`.
+    ///
+    /// Only available with the feature `persist`.
+    #[cfg(feature = "persist")]
+    pub fn choice(mut self, store: crate::ChoiceStore) -> Self {
+        self.sources.push(Source::Choice(store));
+        self
+    }
+
+    /// Consult the system locales, see [`crate::system_want_langids`].
+    ///
+    /// Only available with the feature `getlang`.
+    #[cfg(feature = "getlang_lite")]
+    pub fn system(mut self) -> Self {
+        self.sources.push(Source::System);
+        self
+    }
+
+    /// Use `langids`, e.g. as the last resort.
+    pub fn fixed<I: IntoIterator<Item = LanguageIdentifier>>(mut self, langids: I) -> Self {
+        self.sources
+            .push(Source::Fixed(langids.into_iter().collect()));
+        self
+    }
+
+    /// The wanted locales from the first source providing any, with that source.
+    #[must_use]
+    pub fn resolve_with_source(&self) -> Option<(LocaleSource, Vec<LanguageIdentifier>)> {
+        let found = self.sources.iter().find_map(|source| {
+            let langids = source.want_langids();
+            (!langids.is_empty()).then(|| (source.kind(), langids))
+        });
+        #[cfg(feature = "tracing")]
+        tracing::debug!(?found, "resolved wanted locales");
+        found
+    }
+
+    /// The wanted locales from the first source providing any, or an empty list.
+    #[must_use]
+    pub fn resolve(&self) -> Vec<LanguageIdentifier> {
+        self.resolve_with_source()
+            .map(|(_, langids)| langids)
+            .unwrap_or_default()
+    }
+
+    /// The final fallback chain: each wanted locale from [`Self::resolve`] followed by its
+    /// fallbacks from `solver`, without duplicates.
+    #[must_use]
+    pub fn resolve_chain<S: LocaleSolver>(&self, solver: &S) -> Vec<LanguageIdentifier> {
+        let mut chain = vec![];
+        for locale in self.resolve() {
+            let fallbacks = solver.solve_locale(&locale);
+            for locale in std::iter::once(locale).chain(fallbacks) {
+                if !chain.contains(&locale) {
+                    chain.push(locale);
+                }
+            }
+        }
+        chain
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::langid;
+
+    #[test]
+    fn parse_list_lenient() {
+        assert_eq!(
+            parse_list("de_AT.UTF-8:de, ,!!,en"),
+            langid!["de-AT", "de", "en"]
+        );
+        assert!(parse_list("").is_empty());
+    }
+}
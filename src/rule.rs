@@ -0,0 +1,107 @@
+//! Declarative rules, see [`Rule`].
+use crate::{LanguageIdentifier, default_rulebook};
+use unic_langid::subtags;
+
+/// A rule as data, which can be inspected, compared and sent across threads, unlike the closures
+/// of [`crate::FnRules`].
+///
+/// Rulebooks of these are created with [`crate::Rulebook::from_rules`], which keeps the rules as
+/// its [`crate::Rulebook::owned_values`]. [`Rule::Custom`] is the escape hatch for rules that
+/// cannot be expressed as data.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, LocaleFallbackSolver, Rule, Rulebook};
+/// let rulebook = Rulebook::from_rules([
+///     Rule::MapExact { from: langid!("arb"), to: langid!["ar-AE", "ar"].to_vec() },
+///     Rule::StripRegion,
+///     Rule::PerLanguage { language: "es".parse().unwrap(), to: vec![langid!("pt")] },
+/// ]);
+/// assert_eq!(rulebook.owned_values[1], Rule::StripRegion);
+/// let solver = LocaleFallbackSolver { rulebook };
+/// assert_eq!(solver.solve_locale(langid!("arb")), langid!["ar-AE", "ar"]);
+/// assert_eq!(solver.solve_locale(langid!("es-MX")), langid!["es", "pt"]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Rule {
+    /// The default recommended rules, see [`crate::Rulebook::default`].
+    Default,
+    /// See [`crate::Rulebook::strip_script`].
+    StripScript,
+    /// See [`crate::Rulebook::strip_region`].
+    StripRegion,
+    /// See [`crate::Rulebook::strip_variants`].
+    StripVariants,
+    /// See [`crate::Rulebook::to_639_1`].
+    To639_1,
+    /// Fall back from exactly `from` to `to`.
+    MapExact {
+        from: LanguageIdentifier,
+        to: Vec<LanguageIdentifier>,
+    },
+    /// Fall back from any locale of `language` to `to`.
+    PerLanguage {
+        language: subtags::Language,
+        to: Vec<LanguageIdentifier>,
+    },
+    /// Any other rule.
+    Custom(CustomRule),
+}
+
+/// A rule function with a name, see [`Rule::Custom`].
+///
+/// Custom rules are compared and hashed by name, as function pointers cannot be compared
+/// reliably.
+#[derive(Clone, Copy)]
+pub struct CustomRule {
+    /// Identifies the rule, e.g. in [`crate::Rulebook::owned_values`].
+    pub name: &'static str,
+    pub f: fn(&LanguageIdentifier) -> Vec<LanguageIdentifier>,
+}
+
+impl CustomRule {
+    pub const fn new(
+        name: &'static str,
+        f: fn(&LanguageIdentifier) -> Vec<LanguageIdentifier>,
+    ) -> Self {
+        Self { name, f }
+    }
+}
+
+impl std::fmt::Debug for CustomRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CustomRule").field(&self.name).finish()
+    }
+}
+
+impl PartialEq for CustomRule {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+impl Eq for CustomRule {}
+
+impl std::hash::Hash for CustomRule {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
+impl Rule {
+    /// The fallbacks of `locale` given by this rule.
+    #[must_use]
+    pub fn apply(&self, locale: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+        match self {
+            Self::Default => default_rulebook::default_rulebook(locale),
+            Self::StripScript => default_rulebook::strip_script(locale),
+            Self::StripRegion => default_rulebook::strip_region(locale),
+            Self::StripVariants => default_rulebook::strip_variants(locale),
+            Self::To639_1 => default_rulebook::to_639_1(locale),
+            Self::MapExact { from, to } if from == locale => to.clone(),
+            Self::PerLanguage { language, to } if *language == locale.language => to.clone(),
+            Self::MapExact { .. } | Self::PerLanguage { .. } => vec![],
+            Self::Custom(rule) => (rule.f)(locale),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! Owned values of rulebooks that follow their rules, see [`RuleState`].
+use crate::Rule;
+
+/// State of each rule of a rulebook, kept as its [`crate::Rulebook::owned_values`], e.g.
+/// [`crate::RuleWeights`].
+///
+/// The state follows the rules when they are removed with [`crate::Rulebook::remove_rule`] or
+/// [`crate::Rulebook::clear`], and when the rules of another rulebook are appended with
+/// [`crate::Rulebook::extend_from_rulebook`] or `+`. Rulebooks whose owned values are not per
+/// rule, e.g. those of [`crate::Rulebook::from_rulebooks`], can only be appended to with
+/// [`crate::Rulebook::push_fn`].
+///
+/// Types implementing [`From<()>`] can have the rules of rulebooks without any state, e.g.
+/// [`crate::Rulebook::strip_region`], appended to them.
+pub trait RuleState {
+    /// Append `other`, the state of the rules appended after the first `len` rules.
+    fn append(&mut self, len: usize, other: Self);
+
+    /// Remove the state of the rule at `index`.
+    fn remove(&mut self, index: usize);
+
+    /// Remove the state of all rules.
+    fn clear(&mut self);
+}
+
+impl RuleState for () {
+    fn append(&mut self, _: usize, (): Self) {}
+    fn remove(&mut self, _: usize) {}
+    fn clear(&mut self) {}
+}
+
+/// The rules of [`crate::Rulebook::from_rules`].
+impl RuleState for Vec<Rule> {
+    fn append(&mut self, _: usize, other: Self) {
+        self.extend(other);
+    }
+
+    fn remove(&mut self, index: usize) {
+        if index < self.len() {
+            Self::remove(self, index);
+        }
+    }
+
+    fn clear(&mut self) {
+        Self::clear(self);
+    }
+}
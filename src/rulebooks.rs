@@ -0,0 +1,790 @@
+//! Ready-made rulebooks other than [`crate::Rulebook`] and [`crate::ARulebook`].
+use crate::{LanguageIdentifier, PolyL10nRulebook};
+use isolang::Language;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+
+/// A rulebook consisting of a single closure.
+///
+/// Unlike [`crate::Rulebook::from_fn`], the closure is not boxed and may return anything that can
+/// be turned into an iterator of [`LanguageIdentifier`]s.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, FnRulebook, LocaleFallbackSolver};
+/// let rulebook = FnRulebook(|l: &poly_l10n::LanguageIdentifier| {
+///     l.region.map(|_| poly_l10n::LanguageIdentifier::from_parts(l.language, None, None, &[]))
+/// });
+/// let solver = LocaleFallbackSolver { rulebook };
+/// assert_eq!(solver.solve_locale(langid!("en-GB")), [langid!("en")]);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FnRulebook<F>(pub F);
+
+impl<C, F, I> PolyL10nRulebook<'_, C> for FnRulebook<F>
+where
+    F: Fn(&LanguageIdentifier) -> I,
+    I: IntoIterator<Item = LanguageIdentifier>,
+{
+    fn find_fallback_locale(
+        &self,
+        locale: &LanguageIdentifier,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        (self.0)(locale).into_iter()
+    }
+}
+
+/// A rulebook backed by a map from each locale to a single fallback locale.
+///
+/// Locales missing from the map have no fallbacks; looking them up does not panic (unlike the
+/// [`std::ops::Index`]-based implementation of [`PolyL10nRulebook`]).
+///
+/// For maps with multiple fallbacks per locale, see [`crate::Borrowing`].
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, LocaleFallbackSolver, MapRulebook};
+/// let map = std::collections::BTreeMap::from([
+///     (langid!("pt-BR"), langid!("pt-PT")),
+///     (langid!("pt-PT"), langid!("pt")),
+/// ]);
+/// let solver = LocaleFallbackSolver { rulebook: MapRulebook(map) };
+/// assert_eq!(solver.solve_locale(langid!("pt-BR")), langid!["pt-PT", "pt"]);
+/// assert!(solver.solve_locale(langid!("ja")).is_empty());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MapRulebook<M>(pub M);
+
+impl<S> MapRulebook<HashMap<LanguageIdentifier, LanguageIdentifier, S>> {
+    /// The mappings from each locale to its fallback, sorted by locale.
+    ///
+    /// # Examples
+    /// ```
+    /// use poly_l10n::{langid, MapRulebook};
+    /// let map = std::collections::HashMap::from([
+    ///     (langid!("pt-PT"), langid!("pt")),
+    ///     (langid!("pt-BR"), langid!("pt-PT")),
+    /// ]);
+    /// assert_eq!(
+    ///     MapRulebook(map).mappings(),
+    ///     [(&langid!("pt-BR"), &langid!("pt-PT")), (&langid!("pt-PT"), &langid!("pt"))]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn mappings(&self) -> Vec<(&LanguageIdentifier, &LanguageIdentifier)> {
+        let mut mappings: Vec<_> = self.0.iter().collect();
+        mappings.sort_by_cached_key(|(locale, _)| locale.to_string());
+        mappings
+    }
+}
+
+impl MapRulebook<BTreeMap<LanguageIdentifier, LanguageIdentifier>> {
+    /// The mappings from each locale to its fallback, ordered by locale.
+    #[must_use]
+    pub fn mappings(&self) -> Vec<(&LanguageIdentifier, &LanguageIdentifier)> {
+        self.0.iter().collect()
+    }
+}
+
+impl<'s, C, S: std::hash::BuildHasher> PolyL10nRulebook<'s, C>
+    for MapRulebook<HashMap<LanguageIdentifier, LanguageIdentifier, S>>
+{
+    fn find_fallback_locale_ref(
+        &'s self,
+        locale: &LanguageIdentifier,
+    ) -> impl Iterator<Item = &'s LanguageIdentifier> {
+        self.0.get(locale).into_iter()
+    }
+}
+
+impl<'s, C> PolyL10nRulebook<'s, C>
+    for MapRulebook<BTreeMap<LanguageIdentifier, LanguageIdentifier>>
+{
+    fn find_fallback_locale_ref(
+        &'s self,
+        locale: &LanguageIdentifier,
+    ) -> impl Iterator<Item = &'s LanguageIdentifier> {
+        self.0.get(locale).into_iter()
+    }
+}
+
+/// A rulebook with fallbacks per language, regardless of the script, region and variants of the
+/// given locale.
+///
+/// This saves enumerating every script/region combination of a language as separate keys, as
+/// required by e.g. [`MapRulebook`]. Both ISO 639-1 and 639-3 language subtags are looked up as the
+/// same [`Language`].
+///
+/// The language of a locale is looked up as a whole key: as the keys are languages rather than
+/// locales, every locale of a language already falls under the same key, which is what a prefix
+/// or range lookup over locale keys would achieve. Locales whose language is unknown to
+/// [`isolang`], e.g. `und`, have no fallbacks.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{isolang::Language, langid, LanguageRulebook, LocaleFallbackSolver};
+/// let rulebook = LanguageRulebook::from_iter([(Language::Nob, vec![langid!("nn"), langid!("da")])]);
+/// let solver = LocaleFallbackSolver { rulebook };
+/// assert_eq!(solver.solve_locale(langid!("nb-NO")), langid!["nn", "da"]);
+/// assert_eq!(solver.solve_locale(langid!("nob")), langid!["nn", "da"]);
+/// assert!(solver.solve_locale(langid!("sv")).is_empty());
+/// assert!(solver.solve_locale(langid!("und")).is_empty());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LanguageRulebook(pub BTreeMap<Language, Vec<LanguageIdentifier>>);
+
+impl LanguageRulebook {
+    /// Get the fallbacks of all locales of the given language.
+    #[must_use]
+    pub fn get(&self, lang: Language) -> &[LanguageIdentifier] {
+        self.0.get(&lang).map_or(&[], Vec::as_slice)
+    }
+
+    /// The languages with fallbacks and their fallbacks, ordered by language.
+    pub fn iter(&self) -> impl Iterator<Item = (Language, &[LanguageIdentifier])> {
+        self.0.iter().map(|(lang, rules)| (*lang, rules.as_slice()))
+    }
+
+    /// Number of languages with fallbacks.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether no language has fallbacks.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<'s, C> PolyL10nRulebook<'s, C> for LanguageRulebook {
+    fn find_fallback_locale_ref(
+        &'s self,
+        locale: &LanguageIdentifier,
+    ) -> impl Iterator<Item = &'s LanguageIdentifier> {
+        crate::default_rulebook::langid_to_isolang_silent(locale)
+            .map_or(&[][..], |lang| self.get(lang))
+            .iter()
+    }
+}
+
+impl FromIterator<(Language, Vec<LanguageIdentifier>)> for LanguageRulebook {
+    fn from_iter<T: IntoIterator<Item = (Language, Vec<LanguageIdentifier>)>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl From<BTreeMap<Language, Vec<LanguageIdentifier>>> for LanguageRulebook {
+    fn from(value: BTreeMap<Language, Vec<LanguageIdentifier>>) -> Self {
+        Self(value)
+    }
+}
+
+/// Mark all fallbacks of the wrapped rulebook as terminal.
+///
+/// The solver does not look for fallbacks of terminal fallbacks, e.g. once a locale has fallen
+/// back to `en`, the solver will not wander into the rules for `en` (which might hop to related
+/// languages). Use [`Chain`] to combine it with a rulebook with non-terminal fallbacks.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, Chain, FnRulebook, LocaleFallbackSolver, MapRulebook, Terminal};
+/// let map = std::collections::HashMap::from([(langid!("en"), langid!("en-GB"))]);
+/// let solver = LocaleFallbackSolver {
+///     rulebook: Chain(MapRulebook(map), Terminal(FnRulebook(|_: &_| [langid!("en")]))),
+/// };
+/// assert_eq!(solver.solve_locale(langid!("eo")), [langid!("en")]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Terminal<R>(pub R);
+
+impl<'s, C, R: PolyL10nRulebook<'s, C>> PolyL10nRulebook<'s, C> for Terminal<R> {
+    fn find_terminal_fallback_locale(
+        &'s self,
+        locale: &LanguageIdentifier,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        (self.0.find_fallback_locale(locale))
+            .chain(self.0.find_fallback_locale_ref(locale).cloned())
+            .chain(self.0.find_terminal_fallback_locale(locale))
+    }
+
+    fn find_terminal_fallback_locale_in(
+        &'s self,
+        locale: &LanguageIdentifier,
+        ctx: &C,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        (self.0.find_fallback_locale(locale))
+            .chain(self.0.find_fallback_locale_ref(locale).cloned())
+            .chain(self.0.find_fallback_locale_in(locale, ctx))
+            .chain(self.0.find_terminal_fallback_locale_in(locale, ctx))
+    }
+}
+
+/// Constructed languages (Esperanto, Interlingua, Ido, Lojban, …), see [`ConstructedFallback`].
+pub const CONSTRUCTED_LANGUAGES: [Language; 19] = [
+    Language::Afh,
+    Language::Avk,
+    Language::Bzt,
+    Language::Dws,
+    Language::Epo,
+    Language::Ido,
+    Language::Igs,
+    Language::Ile,
+    Language::Ina,
+    Language::Jbo,
+    Language::Ldn,
+    Language::Lfn,
+    Language::Neu,
+    Language::Nov,
+    Language::Qya,
+    Language::Sjn,
+    Language::Tlh,
+    Language::Tzl,
+    Language::Vol,
+];
+
+/// Fall back from [constructed languages](CONSTRUCTED_LANGUAGES) to natural languages chosen by
+/// the application, e.g. the language the content was originally written in.
+///
+/// Constructed languages are not related to any natural language, so the default rulebook never
+/// falls back from them to one. The fallbacks are [terminal](Terminal), so the solver does not
+/// continue into the rules of the natural languages. Use [`Chain`] to combine it with another
+/// rulebook.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, Chain, ConstructedFallback, LocaleFallbackSolver, Rulebook};
+/// let solver = LocaleFallbackSolver {
+///     rulebook: Chain(Rulebook::default(), ConstructedFallback(vec![langid!("en")])),
+/// };
+/// # #[cfg(feature = "per_lang_default_rules")]
+/// assert_eq!(solver.solve_locale(langid!("eo-FR")), langid!["eo", "epo", "en"]);
+/// assert!(!solver.solve_locale(langid!("fr-CA")).contains(&langid!("en")));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConstructedFallback(pub Vec<LanguageIdentifier>);
+
+impl<'s, C> PolyL10nRulebook<'s, C> for ConstructedFallback {
+    fn find_terminal_fallback_locale(
+        &'s self,
+        locale: &LanguageIdentifier,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        let constructed = crate::default_rulebook::langid_to_isolang_silent(locale)
+            .is_some_and(|lang| CONSTRUCTED_LANGUAGES.contains(&lang));
+        (constructed.then_some(&self.0))
+            .into_iter()
+            .flatten()
+            .cloned()
+    }
+}
+
+/// Combine two rulebooks of possibly different types. Fallbacks from the first rulebook come
+/// first.
+///
+/// Unlike [`crate::Rulebook::from_rulebooks`], terminal fallbacks (see [`Terminal`]) are preserved.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Chain<A, B>(pub A, pub B);
+
+impl<'s, C, A, B> PolyL10nRulebook<'s, C> for Chain<A, B>
+where
+    A: PolyL10nRulebook<'s, C>,
+    B: PolyL10nRulebook<'s, C>,
+{
+    fn find_fallback_locale(
+        &self,
+        locale: &LanguageIdentifier,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        (self.0.find_fallback_locale(locale)).chain(self.1.find_fallback_locale(locale))
+    }
+
+    fn find_fallback_locale_ref(
+        &'s self,
+        locale: &LanguageIdentifier,
+    ) -> impl Iterator<Item = &'s LanguageIdentifier> {
+        (self.0.find_fallback_locale_ref(locale)).chain(self.1.find_fallback_locale_ref(locale))
+    }
+
+    fn find_terminal_fallback_locale(
+        &'s self,
+        locale: &LanguageIdentifier,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        (self.0.find_terminal_fallback_locale(locale))
+            .chain(self.1.find_terminal_fallback_locale(locale))
+    }
+
+    fn find_fallback_locale_in(
+        &'s self,
+        locale: &LanguageIdentifier,
+        ctx: &C,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        (self.0.find_fallback_locale_in(locale, ctx))
+            .chain(self.1.find_fallback_locale_in(locale, ctx))
+    }
+
+    fn find_terminal_fallback_locale_in(
+        &'s self,
+        locale: &LanguageIdentifier,
+        ctx: &C,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        (self.0.find_terminal_fallback_locale_in(locale, ctx))
+            .chain(self.1.find_terminal_fallback_locale_in(locale, ctx))
+    }
+}
+
+/// A rulebook consisting of a single closure that depends on an application-supplied context of
+/// type `C`.
+///
+/// The closure is called with the context given to
+/// [`crate::LocaleFallbackSolver::solve_locale_with_context`], which must be of type `C`. This
+/// allows one rulebook to answer differently per use, e.g. falling back from `zh-Hant` to `zh-Hans`
+/// may be fine for UI texts but not for spell checking.
+///
+/// This can only add fallbacks; to filter or replace the fallbacks of another rulebook by context,
+/// see [`Contextual`].
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, ContextRulebook, LanguageIdentifier, LocaleFallbackSolver};
+/// #[derive(PartialEq)]
+/// enum Usage {
+///     Ui,
+///     SpellCheck,
+/// }
+/// let rulebook = ContextRulebook::new(|l: &LanguageIdentifier, usage: &Usage| {
+///     if *usage == Usage::Ui && *l == langid!("zh-Hant") {
+///         vec![langid!("zh-Hans")]
+///     } else {
+///         vec![]
+///     }
+/// });
+/// let solver = LocaleFallbackSolver { rulebook };
+/// assert_eq!(solver.solve_locale_with_context(langid!("zh-Hant"), &Usage::Ui), [langid!("zh-Hans")]);
+/// assert!(solver.solve_locale_with_context(langid!("zh-Hant"), &Usage::SpellCheck).is_empty());
+/// ```
+///
+/// Solving under a context of another type, including `()` with
+/// [`crate::LocaleFallbackSolver::solve_locale`], does not compile:
+/// ```compile_fail
+/// use poly_l10n::{langid, ContextRulebook, LanguageIdentifier, LocaleFallbackSolver};
+/// struct Usage;
+/// let rulebook = ContextRulebook::new(|_: &LanguageIdentifier, _: &Usage| vec![langid!("en")]);
+/// let solver = LocaleFallbackSolver { rulebook };
+/// solver.solve_locale_with_context(langid!("de"), &"spell checking");
+/// ```
+pub struct ContextRulebook<C, F> {
+    f: F,
+    _ctx: std::marker::PhantomData<fn(&C)>,
+}
+
+impl<C, F> ContextRulebook<C, F> {
+    #[must_use]
+    pub const fn new(f: F) -> Self {
+        Self {
+            f,
+            _ctx: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<C, F: std::fmt::Debug> std::fmt::Debug for ContextRulebook<C, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContextRulebook")
+            .field("f", &self.f)
+            .field("ctx", &std::any::type_name::<C>())
+            .finish()
+    }
+}
+
+impl<C, F, I> PolyL10nRulebook<'_, C> for ContextRulebook<C, F>
+where
+    F: Fn(&LanguageIdentifier, &C) -> I,
+    I: IntoIterator<Item = LanguageIdentifier>,
+{
+    fn find_fallback_locale_in(
+        &self,
+        locale: &LanguageIdentifier,
+        ctx: &C,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        (self.f)(locale, ctx).into_iter()
+    }
+}
+
+/// Filter or replace the fallbacks of a rulebook depending on an application-supplied context.
+///
+/// The closure is given each locale, the fallbacks the wrapped rulebook gives for it under the
+/// context, and the context itself, and returns the fallbacks to use instead. Terminal fallbacks
+/// (see [`Terminal`]), if any, go through the closure separately. Unlike [`ContextRulebook`], this
+/// can make the fallbacks of any rulebook depend on the context, including those of
+/// [`crate::Rulebook::default`].
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, Contextual, LanguageIdentifier, LocaleFallbackSolver, Rulebook};
+/// #[derive(PartialEq)]
+/// enum Usage {
+///     Ui,
+///     SpellCheck,
+/// }
+/// let rulebook = Contextual::new(
+///     Rulebook::from_fn(|l| match l.to_string().as_str() {
+///         "zh-Hant-HK" => vec![langid!("zh-Hant"), langid!("zh-Hans")],
+///         _ => vec![],
+///     }),
+///     |l: &LanguageIdentifier, mut fallbacks: Vec<LanguageIdentifier>, usage: &Usage| {
+///         if *usage == Usage::SpellCheck {
+///             fallbacks.retain(|f| f.script == l.script);
+///         }
+///         fallbacks
+///     },
+/// );
+/// let solver = LocaleFallbackSolver { rulebook };
+/// let locale = langid!("zh-Hant-HK");
+/// assert_eq!(solver.solve_locale_with_context(&locale, &Usage::Ui), langid!["zh-Hant", "zh-Hans"]);
+/// assert_eq!(solver.solve_locale_with_context(&locale, &Usage::SpellCheck), [langid!("zh-Hant")]);
+/// ```
+pub struct Contextual<R, F> {
+    rulebook: R,
+    f: F,
+}
+
+impl<R, F> Contextual<R, F> {
+    #[must_use]
+    pub const fn new(rulebook: R, f: F) -> Self {
+        Self { rulebook, f }
+    }
+
+    /// The wrapped rulebook.
+    #[must_use]
+    pub const fn rulebook(&self) -> &R {
+        &self.rulebook
+    }
+}
+
+impl<R: std::fmt::Debug, F> std::fmt::Debug for Contextual<R, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Contextual")
+            .field("rulebook", &self.rulebook)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'s, C, R, F, I> PolyL10nRulebook<'s, C> for Contextual<R, F>
+where
+    R: PolyL10nRulebook<'s, C>,
+    F: Fn(&LanguageIdentifier, Vec<LanguageIdentifier>, &C) -> I,
+    I: IntoIterator<Item = LanguageIdentifier>,
+{
+    fn find_fallback_locale_in(
+        &'s self,
+        locale: &LanguageIdentifier,
+        ctx: &C,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        let fallbacks = (self.rulebook.find_fallback_locale(locale))
+            .chain(self.rulebook.find_fallback_locale_ref(locale).cloned())
+            .chain(self.rulebook.find_fallback_locale_in(locale, ctx))
+            .take(crate::SOLVE_MAX_CANDIDATES)
+            .collect();
+        (self.f)(locale, fallbacks, ctx).into_iter()
+    }
+
+    fn find_terminal_fallback_locale_in(
+        &'s self,
+        locale: &LanguageIdentifier,
+        ctx: &C,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        let fallbacks: Vec<_> = (self.rulebook.find_terminal_fallback_locale_in(locale, ctx))
+            .take(crate::SOLVE_MAX_CANDIDATES)
+            .collect();
+        (!fallbacks.is_empty())
+            .then(|| (self.f)(locale, fallbacks, ctx))
+            .into_iter()
+            .flatten()
+    }
+}
+
+/// Multiple named rulebooks, e.g. one per subsystem (`"ui"`, `"content"`, `"dates"`).
+///
+/// Use [`crate::LocaleFallbackSolver::solve_locale_in_domain`] to select a rulebook per call.
+/// Unknown domains, as well as [`crate::LocaleFallbackSolver::solve_locale`], use the default
+/// rulebook.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, DomainRulebooks, LocaleFallbackSolver, Rulebook};
+/// let rulebook = DomainRulebooks::new(Rulebook::default())
+///     .with_domain("dates", Rulebook::from_fn(|_| vec![langid!("en-001")]));
+/// let solver = LocaleFallbackSolver { rulebook };
+/// assert_eq!(solver.solve_locale_in_domain("dates", langid!("en-GB")), [langid!("en-001")]);
+/// assert_eq!(solver.solve_locale_in_domain("ui", langid!("en-GB")), solver.solve_locale(langid!("en-GB")));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DomainRulebooks<R> {
+    pub default: R,
+    pub domains: HashMap<Cow<'static, str>, R>,
+}
+
+impl<R> DomainRulebooks<R> {
+    #[must_use]
+    pub fn new(default: R) -> Self {
+        Self {
+            default,
+            domains: HashMap::new(),
+        }
+    }
+
+    /// Add a rulebook for the given domain.
+    #[must_use]
+    pub fn with_domain<N: Into<Cow<'static, str>>>(mut self, domain: N, rulebook: R) -> Self {
+        self.domains.insert(domain.into(), rulebook);
+        self
+    }
+
+    /// Get the rulebook of the given domain, or the default rulebook if there are none.
+    #[must_use]
+    pub fn get(&self, domain: &str) -> &R {
+        self.domains.get(domain).unwrap_or(&self.default)
+    }
+}
+
+impl<'s, C, R: PolyL10nRulebook<'s, C>> PolyL10nRulebook<'s, C> for DomainRulebooks<R> {
+    fn find_fallback_locale(
+        &self,
+        locale: &LanguageIdentifier,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        self.default.find_fallback_locale(locale)
+    }
+
+    fn find_fallback_locale_ref(
+        &'s self,
+        locale: &LanguageIdentifier,
+    ) -> impl Iterator<Item = &'s LanguageIdentifier> {
+        self.default.find_fallback_locale_ref(locale)
+    }
+
+    fn find_terminal_fallback_locale(
+        &'s self,
+        locale: &LanguageIdentifier,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        self.default.find_terminal_fallback_locale(locale)
+    }
+
+    fn find_fallback_locale_in(
+        &'s self,
+        locale: &LanguageIdentifier,
+        ctx: &C,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        self.default.find_fallback_locale_in(locale, ctx)
+    }
+
+    fn find_terminal_fallback_locale_in(
+        &'s self,
+        locale: &LanguageIdentifier,
+        ctx: &C,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        self.default.find_terminal_fallback_locale_in(locale, ctx)
+    }
+}
+
+/// Boxed iterator of fallbacks returned by [`DynRulebook`].
+pub type DynFallbacks<'a> = Box<dyn Iterator<Item = LanguageIdentifier> + 'a>;
+
+/// Dyn-compatible version of [`PolyL10nRulebook`], for choosing or swapping rulebooks at runtime.
+///
+/// This is implemented for every [`PolyL10nRulebook`], and `Box<dyn DynRulebook>` (as well as
+/// `Box<dyn DynRulebook + Send + Sync>`) implements [`PolyL10nRulebook`] in turn, so it can be
+/// used with [`crate::LocaleFallbackSolver`]. Borrowed fallbacks are cloned, and the fallbacks of
+/// each locale are collected before the solver sees them.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, DynRulebook, FnRulebook, LocaleFallbackSolver, Rulebook};
+/// let rulebook: Box<dyn DynRulebook> = Box::new(FnRulebook(|_: &_| []));
+/// let mut solver = LocaleFallbackSolver { rulebook };
+/// assert!(solver.solve_locale(langid!("de-AT")).is_empty());
+/// solver.rulebook = Box::new(Rulebook::from_fn(|l| match l.to_string().as_str() {
+///     "de-AT" => vec![langid!("de")],
+///     _ => vec![],
+/// }));
+/// assert_eq!(solver.solve_locale(langid!("de-AT")), [langid!("de")]);
+/// ```
+pub trait DynRulebook<C = ()> {
+    /// See [`PolyL10nRulebook::find_fallback_locale`] and
+    /// [`PolyL10nRulebook::find_fallback_locale_ref`].
+    fn dyn_find_fallback_locale<'a>(&'a self, locale: &'a LanguageIdentifier) -> DynFallbacks<'a>
+    where
+        C: 'a;
+
+    /// See [`PolyL10nRulebook::find_terminal_fallback_locale`].
+    fn dyn_find_terminal_fallback_locale<'a>(
+        &'a self,
+        locale: &'a LanguageIdentifier,
+    ) -> DynFallbacks<'a>
+    where
+        C: 'a;
+
+    /// See [`PolyL10nRulebook::find_fallback_locale_in`].
+    fn dyn_find_fallback_locale_in<'a>(
+        &'a self,
+        locale: &'a LanguageIdentifier,
+        ctx: &'a C,
+    ) -> DynFallbacks<'a>;
+
+    /// See [`PolyL10nRulebook::find_terminal_fallback_locale_in`].
+    fn dyn_find_terminal_fallback_locale_in<'a>(
+        &'a self,
+        locale: &'a LanguageIdentifier,
+        ctx: &'a C,
+    ) -> DynFallbacks<'a>;
+}
+
+impl<C, R: for<'a> PolyL10nRulebook<'a, C>> DynRulebook<C> for R {
+    fn dyn_find_fallback_locale<'a>(&'a self, locale: &'a LanguageIdentifier) -> DynFallbacks<'a>
+    where
+        C: 'a,
+    {
+        Box::new(
+            (self.find_fallback_locale(locale))
+                .chain(self.find_fallback_locale_ref(locale).cloned()),
+        )
+    }
+
+    fn dyn_find_terminal_fallback_locale<'a>(
+        &'a self,
+        locale: &'a LanguageIdentifier,
+    ) -> DynFallbacks<'a>
+    where
+        C: 'a,
+    {
+        Box::new(self.find_terminal_fallback_locale(locale))
+    }
+
+    fn dyn_find_fallback_locale_in<'a>(
+        &'a self,
+        locale: &'a LanguageIdentifier,
+        ctx: &'a C,
+    ) -> DynFallbacks<'a> {
+        Box::new(self.find_fallback_locale_in(locale, ctx))
+    }
+
+    fn dyn_find_terminal_fallback_locale_in<'a>(
+        &'a self,
+        locale: &'a LanguageIdentifier,
+        ctx: &'a C,
+    ) -> DynFallbacks<'a> {
+        Box::new(self.find_terminal_fallback_locale_in(locale, ctx))
+    }
+}
+
+/// Collect the fallbacks given by a [`DynRulebook`], which borrow the locale, up to the hard limit
+/// on candidates of the solver so that infinite iterators are still fine.
+fn collect_capped(fallbacks: DynFallbacks<'_>) -> std::vec::IntoIter<LanguageIdentifier> {
+    let fallbacks: Vec<_> = fallbacks.take(crate::SOLVE_MAX_CANDIDATES).collect();
+    fallbacks.into_iter()
+}
+
+macro_rules! impl_dyn_rulebook {
+    ($($ty:ty),*) => {$(
+        impl<'s, C> PolyL10nRulebook<'s, C> for $ty {
+            fn find_fallback_locale(
+                &self,
+                locale: &LanguageIdentifier,
+            ) -> impl Iterator<Item = LanguageIdentifier> {
+                collect_capped((**self).dyn_find_fallback_locale(locale))
+            }
+
+            fn find_terminal_fallback_locale(
+                &'s self,
+                locale: &LanguageIdentifier,
+            ) -> impl Iterator<Item = LanguageIdentifier> {
+                collect_capped((**self).dyn_find_terminal_fallback_locale(locale))
+            }
+
+            fn find_fallback_locale_in(
+                &'s self,
+                locale: &LanguageIdentifier,
+                ctx: &C,
+            ) -> impl Iterator<Item = LanguageIdentifier> {
+                collect_capped((**self).dyn_find_fallback_locale_in(locale, ctx))
+            }
+
+            fn find_terminal_fallback_locale_in(
+                &'s self,
+                locale: &LanguageIdentifier,
+                ctx: &C,
+            ) -> impl Iterator<Item = LanguageIdentifier> {
+                collect_capped((**self).dyn_find_terminal_fallback_locale_in(locale, ctx))
+            }
+        }
+    )*};
+}
+
+impl_dyn_rulebook!(
+    Box<dyn DynRulebook<C>>,
+    Box<dyn DynRulebook<C> + Send + Sync>
+);
+
+/// Use a rulebook behind a reference or smart pointer, e.g. to share it between several solvers.
+///
+/// The pointer may be `&R`, [`Box`], [`std::rc::Rc`] or [`std::sync::Arc`]. These cannot implement [`PolyL10nRulebook`] directly since that would
+/// conflict with the [`std::ops::Index`]-based implementation.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, LocaleFallbackSolver, Rulebook, Shared};
+/// use std::rc::Rc;
+/// let rulebook = Rc::new(Rulebook::from_fn(|l| match l.to_string().as_str() {
+///     "de-AT" => vec![langid!("de")],
+///     _ => vec![],
+/// }));
+/// let a = LocaleFallbackSolver { rulebook: Shared(Rc::clone(&rulebook)) };
+/// let b = LocaleFallbackSolver { rulebook: Shared(&*rulebook) };
+/// assert_eq!(a.solve_locale(langid!("de-AT")), b.solve_locale(langid!("de-AT")));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Shared<P>(pub P);
+
+macro_rules! impl_shared {
+    ($($ty:ty),*) => {$(
+        impl<'s, C, R: PolyL10nRulebook<'s, C> + ?Sized> PolyL10nRulebook<'s, C> for Shared<$ty> {
+            fn find_fallback_locale(
+                &self,
+                locale: &LanguageIdentifier,
+            ) -> impl Iterator<Item = LanguageIdentifier> {
+                self.0.find_fallback_locale(locale)
+            }
+
+            fn find_fallback_locale_ref(
+                &'s self,
+                locale: &LanguageIdentifier,
+            ) -> impl Iterator<Item = &'s LanguageIdentifier> {
+                self.0.find_fallback_locale_ref(locale)
+            }
+
+            fn find_terminal_fallback_locale(
+                &'s self,
+                locale: &LanguageIdentifier,
+            ) -> impl Iterator<Item = LanguageIdentifier> {
+                self.0.find_terminal_fallback_locale(locale)
+            }
+
+            fn find_fallback_locale_in(
+                &'s self,
+                locale: &LanguageIdentifier,
+                ctx: &C,
+            ) -> impl Iterator<Item = LanguageIdentifier> {
+                self.0.find_fallback_locale_in(locale, ctx)
+            }
+
+            fn find_terminal_fallback_locale_in(
+                &'s self,
+                locale: &LanguageIdentifier,
+                ctx: &C,
+            ) -> impl Iterator<Item = LanguageIdentifier> {
+                self.0.find_terminal_fallback_locale_in(locale, ctx)
+            }
+        }
+    )*};
+}
+
+impl_shared!(&R, Box<R>, std::rc::Rc<R>, std::sync::Arc<R>);
@@ -0,0 +1,280 @@
+//! Data rule files, i.e. fallback rules stored as data instead of code.
+//!
+//! A rule file maps locales to their fallbacks in a subset of TOML:
+//!
+//! ```toml
+//! # comments are allowed
+//! "arb" = ["ar-AE", "ar"]
+//! es-MX = [
+//!     "es-419",
+//!     "es",
+//! ]
+//! ```
+use crate::macros::IntoLangIdAble;
+use std::collections::HashMap;
+use unic_langid::LanguageIdentifier;
+
+/// Fallbacks for each locale, as read from a rule file.
+#[cfg_attr(not(any(feature = "user_rules", feature = "watch")), allow(dead_code))]
+pub type RuleMap = HashMap<LanguageIdentifier, Vec<LanguageIdentifier>>;
+
+/// Error when parsing a rule file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number where the error occurred.
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+struct Cursor<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+}
+
+impl Cursor<'_> {
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        if c == Some('\n') {
+            self.line = self.line.saturating_add(1);
+        }
+        c
+    }
+
+    fn err<T, S: Into<String>>(&self, message: S) -> Result<T, ParseError> {
+        Err(ParseError {
+            line: self.line,
+            message: message.into(),
+        })
+    }
+
+    /// Skip spaces and comments, and also newlines if `newlines` is true.
+    fn skip_ws(&mut self, newlines: bool) {
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' | '\r' => {}
+                '\n' if newlines => {}
+                '#' => {
+                    while self.peek().is_some_and(|c| c != '\n') {
+                        self.bump();
+                    }
+                    continue;
+                }
+                _ => return,
+            }
+            self.bump();
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        let Some(quote @ ('"' | '\'')) = self.bump() else {
+            return self.err("expected a string");
+        };
+        let mut s = String::new();
+        loop {
+            match self.bump() {
+                None | Some('\n') => return self.err("unterminated string"),
+                Some(c) if c == quote => return Ok(s),
+                Some('\\') if quote == '"' => match self.bump() {
+                    Some('"') => s.push('"'),
+                    Some('\\') => s.push('\\'),
+                    Some('n') => s.push('\n'),
+                    Some('t') => s.push('\t'),
+                    _ => return self.err("unsupported escape sequence"),
+                },
+                Some(c) => s.push(c),
+            }
+        }
+    }
+
+    fn key(&mut self) -> Result<String, ParseError> {
+        if matches!(self.peek(), Some('"' | '\'')) {
+            return self.string();
+        }
+        let mut key = String::new();
+        while let Some(c) = self
+            .peek()
+            .filter(|c| c.is_ascii_alphanumeric() || "-_".contains(*c))
+        {
+            key.push(c);
+            self.bump();
+        }
+        if key.is_empty() {
+            return self.err("expected a key");
+        }
+        Ok(key)
+    }
+
+    fn value(&mut self) -> Result<Vec<String>, ParseError> {
+        if self.peek() != Some('[') {
+            return Ok(vec![self.string()?]);
+        }
+        self.bump();
+        let mut values = vec![];
+        loop {
+            self.skip_ws(true);
+            if self.peek() == Some(']') {
+                self.bump();
+                return Ok(values);
+            }
+            values.push(self.string()?);
+            self.skip_ws(true);
+            match self.bump() {
+                Some(',') => {}
+                Some(']') => return Ok(values),
+                _ => return self.err("expected `,` or `]`"),
+            }
+        }
+    }
+}
+
+/// Parse the TOML rule file format described in the [module documentation](self), without
+/// parsing the locales.
+///
+/// # Errors
+/// Returns [`ParseError`] if the input is not in the expected format.
+pub fn parse_toml_entries(input: &str) -> Result<Vec<(usize, String, Vec<String>)>, ParseError> {
+    let mut cursor = Cursor {
+        chars: input.chars().peekable(),
+        line: 1,
+    };
+    let mut entries = vec![];
+    loop {
+        cursor.skip_ws(true);
+        if cursor.peek().is_none() {
+            return Ok(entries);
+        }
+        if cursor.peek() == Some('[') {
+            return cursor.err("tables are not supported");
+        }
+        let line = cursor.line;
+        let key = cursor.key()?;
+        cursor.skip_ws(false);
+        if cursor.bump() != Some('=') {
+            return cursor.err("expected `=`");
+        }
+        cursor.skip_ws(false);
+        let value = cursor.value()?;
+        cursor.skip_ws(false);
+        if !matches!(cursor.bump(), None | Some('\n')) {
+            return cursor.err("expected a new line");
+        }
+        entries.push((line, key, value));
+    }
+}
+
+/// Parse a TOML rule file. Locales are parsed leniently, see [`crate::langid!`].
+///
+/// If a locale is specified multiple times, the last entry wins.
+///
+/// # Errors
+/// Returns [`ParseError`] if the input is not in the expected format or contains invalid locales.
+#[cfg_attr(not(any(feature = "user_rules", feature = "watch")), allow(dead_code))]
+pub fn parse_toml(input: &str) -> Result<RuleMap, ParseError> {
+    let parse = |line, s: &str| {
+        s.to_langid().map_err(|e| ParseError {
+            line,
+            message: format!("invalid locale `{s}`: {e}"),
+        })
+    };
+    let mut map = RuleMap::new();
+    for (line, key, values) in parse_toml_entries(input)? {
+        let values = values.iter().map(|v| parse(line, v));
+        map.insert(parse(line, &key)?, values.collect::<Result<_, _>>()?);
+    }
+    Ok(map)
+}
+
+/// Rule files that are overlaid on top of the default rulebook, in the order of increasing
+/// priority.
+#[cfg(feature = "user_rules")]
+fn overlay_paths() -> Vec<std::path::PathBuf> {
+    let mut paths = vec![];
+    if cfg!(unix) {
+        paths.push(std::path::PathBuf::from("/etc/poly_l10n/rules.toml"));
+    }
+    if let Some(dir) = crate::dirs::user_config_dir() {
+        paths.push(dir.join("poly_l10n").join("rules.toml"));
+    }
+    paths
+}
+
+/// Rule function that looks up the overlay first and uses `rules` (usually [`default_rulebook`])
+/// for locales not specified in the overlay.
+///
+/// [`default_rulebook`]: crate::default_rulebook::default_rulebook
+#[cfg(feature = "user_rules")]
+pub fn with_overlay(
+    overlay: RuleMap,
+    rules: fn(&LanguageIdentifier) -> Vec<LanguageIdentifier>,
+) -> impl Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> + Send + Sync + 'static {
+    move |l| overlay.get(l).map_or_else(|| rules(l), Clone::clone)
+}
+
+/// Load the system-wide and user rule files. Entries in later files override earlier ones.
+///
+/// Missing files are skipped. Invalid files are skipped as a whole (with an error logged if the
+/// feature `tracing` is enabled).
+#[cfg(feature = "user_rules")]
+pub fn load_overlay() -> Option<RuleMap> {
+    let mut overlay: Option<RuleMap> = None;
+    for path in overlay_paths() {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        match parse_toml(&content) {
+            Ok(map) => overlay.get_or_insert_default().extend(map),
+            #[allow(unused_variables)]
+            Err(err) => {
+                #[cfg(feature = "tracing")]
+                tracing::error!(?path, %err, "cannot parse poly_l10n rule file");
+            }
+        }
+    }
+    overlay
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let map = parse_toml(
+            r#"
+            # comment
+            "arb" = ["ar-AE", "ar"] # trailing comment
+            es_MX = [
+                'es-419',
+                "es", # trailing comma
+            ]
+            pt = "pt-PT"
+            "#,
+        )
+        .unwrap();
+        let get = |l: &str| map.get(&crate::langid!(l)).map(Vec::as_slice);
+        assert_eq!(get("arb"), Some(&crate::langid!["ar-AE", "ar"][..]));
+        assert_eq!(get("es-MX"), Some(&crate::langid!["es-419", "es"][..]));
+        assert_eq!(get("pt"), Some(&[crate::langid!("pt-PT")][..]));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = |s| parse_toml(s).unwrap_err().line;
+        assert_eq!(err("[table]"), 1);
+        assert_eq!(err("en = [\"fr\"\nde = []"), 2);
+        assert_eq!(err("en = \"fr"), 1);
+        assert_eq!(err("\n\nen = [\"!!\"]"), 3);
+    }
+}
@@ -0,0 +1,421 @@
+//! Lazy breadth-first solving of fallbacks, see [`SolveIter`].
+use crate::{
+    LanguageIdentifier, PolyL10nRulebook, SOLVE_MAX_CANDIDATES, SOLVE_MAX_EXPANSIONS,
+    SOLVE_MAX_LOCALES,
+};
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Capacity reserved for the locales of a solve, as most fallback chains are shorter.
+pub const CHAIN_CAPACITY: usize = 8;
+
+/// Options for solving with [`crate::LocaleFallbackSolver::solve_locale_with_options`].
+///
+/// The hard limits ([`SOLVE_MAX_LOCALES`] and related) always apply in addition to these.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, LocaleFallbackSolver, Rulebook, SolverOptions};
+/// let solver = LocaleFallbackSolver {
+///     rulebook: Rulebook::from_fn(|l| match l.to_string().as_str() {
+///         "de-AT" => vec![langid!("de-DE")],
+///         "de-DE" => vec![langid!("de")],
+///         _ => vec![],
+///     }),
+/// };
+/// let options = SolverOptions::default().with_max_depth(1);
+/// assert_eq!(solver.solve_locale_with_options(langid!("de-AT"), &options), [langid!("de-DE")]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct SolverOptions {
+    max_depth: usize,
+    max_results: usize,
+    time_budget: Option<Duration>,
+}
+
+impl Default for SolverOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: usize::MAX,
+            max_results: usize::MAX,
+            time_budget: None,
+        }
+    }
+}
+
+impl SolverOptions {
+    /// Only follow rules this many times from the input locale, e.g. with a depth of 1 the chain
+    /// only contains the fallbacks given by the rulebook for the input locale itself. Unbounded by
+    /// default.
+    pub const fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// See [`Self::with_max_depth`].
+    #[must_use]
+    pub const fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Stop solving after this many fallbacks, e.g. to bound the work spent on hostile or
+    /// misconfigured rulebooks. Unbounded by default, apart from [`SOLVE_MAX_LOCALES`].
+    pub const fn with_max_results(mut self, max_results: usize) -> Self {
+        self.max_results = max_results;
+        self
+    }
+
+    /// See [`Self::with_max_results`].
+    #[must_use]
+    pub const fn max_results(&self) -> usize {
+        self.max_results
+    }
+
+    /// Stop solving once this much time has passed since solving started. This is checked before
+    /// each query of the rulebook, so a single slow query may overrun it. Unbounded by default.
+    pub const fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// See [`Self::with_time_budget`].
+    #[must_use]
+    pub const fn time_budget(&self) -> Option<Duration> {
+        self.time_budget
+    }
+}
+
+/// Iterator over the fallbacks of a locale, expanding them breadth-first on demand.
+///
+/// See [`crate::LocaleFallbackSolver::solve_locale_iter`]. The fallbacks are the same, in the
+/// same order, as [`crate::LocaleFallbackSolver::solve_locale`], and the same limits apply.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct SolveIter<'s, R, C = ()> {
+    rulebook: &'s R,
+    ctx: &'s C,
+    /// locales found so far
+    seen: HashSet<LanguageIdentifier>,
+    /// locales found but not expanded yet, with their depth
+    frontier: VecDeque<(LanguageIdentifier, usize)>,
+    /// locales found but not yielded yet
+    pending: VecDeque<LanguageIdentifier>,
+    /// the candidates of the current expansion, kept to reuse the allocation
+    buf: Vec<(LanguageIdentifier, bool)>,
+    found: usize,
+    expansions: usize,
+    candidates: usize,
+    options: SolverOptions,
+    /// when the time budget of the options runs out
+    deadline: Option<Instant>,
+    /// whether the hard limits or the budget stopped solving
+    truncated: bool,
+    /// the locale each fallback was first given for, when diagnosing
+    parents: Option<HashMap<LanguageIdentifier, LanguageIdentifier>>,
+    cycles: Vec<Vec<LanguageIdentifier>>,
+}
+
+impl<R, C> std::fmt::Debug for SolveIter<'_, R, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SolveIter")
+            .field("frontier", &self.frontier)
+            .field("pending", &self.pending)
+            .field("found", &self.found)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'s, C, R: PolyL10nRulebook<'s, C>> SolveIter<'s, R, C> {
+    pub(crate) fn new(rulebook: &'s R, locale: &LanguageIdentifier, ctx: &'s C) -> Self {
+        Self {
+            rulebook,
+            ctx,
+            seen: HashSet::with_capacity(CHAIN_CAPACITY),
+            frontier: VecDeque::from([(locale.clone(), 0)]),
+            pending: VecDeque::with_capacity(CHAIN_CAPACITY),
+            buf: vec![],
+            found: 0,
+            expansions: 0,
+            candidates: 0,
+            options: SolverOptions::default(),
+            deadline: None,
+            truncated: false,
+            parents: None,
+            cycles: vec![],
+        }
+    }
+
+    pub(crate) fn with_options(mut self, options: SolverOptions) -> Self {
+        self.options = options;
+        self.deadline = (options.time_budget).and_then(|budget| Instant::now().checked_add(budget));
+        self
+    }
+
+    /// Also detect cycles, see [`Self::take_cycles`].
+    pub(crate) fn with_diagnostics(mut self) -> Self {
+        self.parents = Some(HashMap::new());
+        self
+    }
+
+    /// The cycles detected so far with [`Self::with_diagnostics`], each from a fallback back to
+    /// itself, e.g. `[A, B, A]` if `A` falls back to `B` and `B` to `A`.
+    pub(crate) fn take_cycles(&mut self) -> Vec<Vec<LanguageIdentifier>> {
+        std::mem::take(&mut self.cycles)
+    }
+
+    /// Whether solving was stopped by the hard limits ([`SOLVE_MAX_LOCALES`] and related) or by
+    /// the budget of the [`SolverOptions`], so that the fallbacks are incomplete.
+    #[must_use]
+    pub const fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    fn stop(&mut self) {
+        self.truncated = true;
+        self.frontier.clear();
+    }
+
+    /// The cycle closed by `locale` falling back to `fallback`, an earlier fallback, if
+    /// `fallback` is one of the locales `locale` was derived from.
+    fn find_cycle(
+        &self,
+        locale: &LanguageIdentifier,
+        fallback: &LanguageIdentifier,
+    ) -> Option<Vec<LanguageIdentifier>> {
+        let parents = self.parents.as_ref()?;
+        // falling back to itself is harmless, and common
+        if locale == fallback {
+            return None;
+        }
+        let mut path = vec![locale.clone()];
+        let mut current = locale;
+        for _ in 0..parents.len() {
+            let parent = parents.get(current)?;
+            path.push(parent.clone());
+            if parent == fallback {
+                path.reverse();
+                path.push(fallback.clone());
+                return Some(path);
+            }
+            current = parent;
+        }
+        None
+    }
+
+    /// Query the rulebook for the fallbacks of `locale`, queueing the new ones.
+    fn expand(&mut self, locale: &LanguageIdentifier, depth: usize) {
+        let (rulebook, ctx) = (self.rulebook, self.ctx);
+        if self.expansions >= SOLVE_MAX_EXPANSIONS || self.candidates >= SOLVE_MAX_CANDIDATES {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                expansions = self.expansions,
+                candidates = self.candidates,
+                "solving stopped by hard limits"
+            );
+            return self.stop();
+        }
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(len = self.found, "solving stopped by time budget");
+            return self.stop();
+        }
+        if depth >= self.options.max_depth {
+            return;
+        }
+        self.expansions = self.expansions.saturating_add(1);
+        let budget = SOLVE_MAX_CANDIDATES.saturating_sub(self.candidates);
+        let seen = &self.seen;
+        let mut fallbacks = std::mem::take(&mut self.buf);
+        fallbacks.extend(
+            (rulebook.find_fallback_locale(locale))
+                .chain(
+                    (rulebook.find_fallback_locale_ref(locale))
+                        .take(budget)
+                        .filter(|l| !seen.contains(*l))
+                        .map(Clone::clone),
+                )
+                .chain(rulebook.find_fallback_locale_in(locale, ctx))
+                .map(|l| (l, true))
+                .chain((rulebook.find_terminal_fallback_locale_in(locale, ctx)).map(|l| (l, false)))
+                .take(budget),
+        );
+        self.candidates = self.candidates.saturating_add(fallbacks.len());
+        #[expect(clippy::iter_with_drain, reason = "keeps the allocation of the buffer")]
+        for (l, expand) in fallbacks.drain(..) {
+            if self.seen.contains(&l) {
+                if let Some(cycle) = self.find_cycle(locale, &l) {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(cycle = %cycle.iter().format(" → "), "fallback cycle");
+                    self.cycles.push(cycle);
+                }
+                continue;
+            }
+            if self.found >= SOLVE_MAX_LOCALES.min(self.options.max_results) {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(len = self.found, "solving stopped by limits");
+                self.stop();
+                break;
+            }
+            self.seen.insert(l.clone());
+            if let Some(parents) = self.parents.as_mut().filter(|_| l != *locale) {
+                parents.insert(l.clone(), locale.clone());
+            }
+            self.found = self.found.saturating_add(1);
+            if expand {
+                self.frontier
+                    .push_back((l.clone(), depth.saturating_add(1)));
+            }
+            self.pending.push_back(l);
+        }
+        self.buf = fallbacks;
+    }
+}
+
+impl<'s, C, R: PolyL10nRulebook<'s, C>> Iterator for SolveIter<'s, R, C> {
+    type Item = LanguageIdentifier;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(l) = self.pending.pop_front() {
+                return Some(l);
+            }
+            let (locale, depth) = self.frontier.pop_front()?;
+            self.expand(&locale, depth);
+        }
+    }
+}
+
+impl<'s, C, R: PolyL10nRulebook<'s, C>> std::iter::FusedIterator for SolveIter<'s, R, C> {}
+
+/// The fallbacks of a locale found under a budget, see
+/// [`crate::LocaleFallbackSolver::solve_locale_bounded`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BoundedChain {
+    /// The fallbacks found before solving stopped.
+    pub chain: FallbackChain,
+    /// Whether solving was stopped by the budget of the [`SolverOptions`] or by the hard limits,
+    /// so that `chain` is incomplete. Reaching the maximum depth does not count.
+    pub truncated: bool,
+}
+
+/// The fallbacks of a locale found by [`crate::LocaleFallbackSolver::solve_locale`], in order.
+///
+/// This dereferences to the underlying [`Vec`], and is displayed as the locales separated by
+/// arrows.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, LocaleFallbackSolver, Rulebook};
+/// let solver = LocaleFallbackSolver {
+///     rulebook: Rulebook::from_fn(|l| match l.to_string().as_str() {
+///         "arb" => vec![langid!("ar-AE"), langid!("ar")],
+///         _ => vec![],
+///     }),
+/// };
+/// let chain = solver.solve_locale(langid!("arb"));
+/// assert_eq!(chain.to_string(), "ar-AE → ar");
+/// assert!(chain.contains(&langid!("ar")));
+/// assert_eq!(chain.best_match(&langid!["en", "ar"]), Some(&langid!("ar")));
+/// assert_eq!(chain, langid!["ar-AE", "ar"]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FallbackChain(pub Vec<LanguageIdentifier>);
+
+impl FallbackChain {
+    /// The first locale of the chain that is in `available`, if any.
+    pub fn best_match<'a, L: AsRef<LanguageIdentifier>>(
+        &self,
+        available: &'a [L],
+    ) -> Option<&'a L> {
+        (self.0.iter()).find_map(|l| available.iter().find(|a| a.as_ref() == l))
+    }
+}
+
+impl std::fmt::Display for FallbackChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.iter().format(" → "))
+    }
+}
+
+impl std::ops::Deref for FallbackChain {
+    type Target = Vec<LanguageIdentifier>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl std::ops::DerefMut for FallbackChain {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl AsRef<[LanguageIdentifier]> for FallbackChain {
+    fn as_ref(&self) -> &[LanguageIdentifier] {
+        &self.0
+    }
+}
+
+impl From<Vec<LanguageIdentifier>> for FallbackChain {
+    fn from(value: Vec<LanguageIdentifier>) -> Self {
+        Self(value)
+    }
+}
+impl From<FallbackChain> for Vec<LanguageIdentifier> {
+    fn from(value: FallbackChain) -> Self {
+        value.0
+    }
+}
+
+impl FromIterator<LanguageIdentifier> for FallbackChain {
+    fn from_iter<T: IntoIterator<Item = LanguageIdentifier>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for FallbackChain {
+    type Item = LanguageIdentifier;
+    type IntoIter = std::vec::IntoIter<LanguageIdentifier>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+impl<'a> IntoIterator for &'a FallbackChain {
+    type Item = &'a LanguageIdentifier;
+    type IntoIter = std::slice::Iter<'a, LanguageIdentifier>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<const N: usize> PartialEq<[LanguageIdentifier; N]> for FallbackChain {
+    fn eq(&self, other: &[LanguageIdentifier; N]) -> bool {
+        self.0 == other
+    }
+}
+impl PartialEq<[LanguageIdentifier]> for FallbackChain {
+    fn eq(&self, other: &[LanguageIdentifier]) -> bool {
+        self.0 == other
+    }
+}
+impl PartialEq<&[LanguageIdentifier]> for FallbackChain {
+    fn eq(&self, other: &&[LanguageIdentifier]) -> bool {
+        self.0 == *other
+    }
+}
+impl PartialEq<Vec<LanguageIdentifier>> for FallbackChain {
+    fn eq(&self, other: &Vec<LanguageIdentifier>) -> bool {
+        self.0 == *other
+    }
+}
+impl PartialEq<FallbackChain> for Vec<LanguageIdentifier> {
+    fn eq(&self, other: &FallbackChain) -> bool {
+        *self == other.0
+    }
+}
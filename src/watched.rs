@@ -0,0 +1,156 @@
+//! [`WatchedRulebook`], a rulebook backed by a rule file that is reloaded on change.
+use crate::rules_file::{self, RuleMap};
+use crate::{LanguageIdentifier, PolyL10nRulebook};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+
+/// A rulebook loaded from a rule file, which is reloaded when the file changes.
+///
+/// The rule file has the syntax of [`crate::Rulebook::from_toml_str`], but each key is a locale
+/// mapped to its fallbacks; `-*` keys and `directives` are not supported. Locales missing from the
+/// file have no fallbacks, so this is usually combined with other rulebooks using
+/// [`crate::Chain`].
+///
+/// The modification time of the file is checked at most once per interval (1 second by default)
+/// when the rulebook is queried. If the file has changed, it is parsed again. If it cannot be
+/// read or parsed, the previously loaded rules are kept (with an error logged if the feature
+/// `tracing` is enabled), so a half-written file never takes effect.
+///
+/// Reloaded rules are swapped in atomically: a query that is being answered keeps using the rules
+/// it started with, so long-running daemons pick up changes to the file without a restart and
+/// without ever seeing a mix of old and new rules. The file is polled rather than watched with
+/// OS notifications, so there is no background thread.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, LocaleFallbackSolver, WatchedRulebook};
+/// let path = std::env::temp_dir().join("poly_l10n_watched_doctest.toml");
+/// std::fs::write(&path, r#"es-MX = ["es-419", "es"]"#).unwrap();
+/// let rulebook = WatchedRulebook::open(&path)
+///     .unwrap()
+///     .with_interval(std::time::Duration::ZERO);
+/// let solver = LocaleFallbackSolver { rulebook };
+/// assert_eq!(solver.solve_locale(langid!("es-MX")), langid!["es-419", "es"]);
+///
+/// std::fs::write(&path, r#"es-MX = ["es"]"#).unwrap();
+/// solver.rulebook.reload().unwrap();
+/// assert_eq!(solver.solve_locale(langid!("es-MX")), [langid!("es")]);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct WatchedRulebook {
+    path: PathBuf,
+    interval: Duration,
+    state: RwLock<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    rules: Arc<RuleMap>,
+    modified: Option<SystemTime>,
+    checked: Instant,
+}
+
+fn load(path: &Path) -> std::io::Result<(RuleMap, Option<SystemTime>)> {
+    let modified = std::fs::metadata(path)?.modified().ok();
+    let content = std::fs::read_to_string(path)?;
+    let rules = rules_file::parse_toml(&content)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    Ok((rules, modified))
+}
+
+impl WatchedRulebook {
+    /// Load the rule file at `path`.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read, or an error of kind
+    /// [`std::io::ErrorKind::InvalidData`] if it cannot be parsed.
+    pub fn open<P: Into<PathBuf>>(path: P) -> std::io::Result<Self> {
+        let path = path.into();
+        let (rules, modified) = load(&path)?;
+        Ok(Self {
+            path,
+            interval: Duration::from_secs(1),
+            state: RwLock::new(State {
+                rules: Arc::new(rules),
+                modified,
+                checked: Instant::now(),
+            }),
+        })
+    }
+
+    /// Set how often the modification time of the file is checked.
+    #[must_use]
+    pub const fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Path to the rule file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reload the rule file now, regardless of its modification time.
+    ///
+    /// # Errors
+    /// Same as [`Self::open()`]. The previously loaded rules are kept on error.
+    pub fn reload(&self) -> std::io::Result<()> {
+        let (rules, modified) = load(&self.path)?;
+        *self
+            .state
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = State {
+            rules: Arc::new(rules),
+            modified,
+            checked: Instant::now(),
+        };
+        Ok(())
+    }
+
+    /// The currently loaded rules, reloading the rule file first if it has changed.
+    pub fn rules(&self) -> Arc<RuleMap> {
+        {
+            let state = self
+                .state
+                .read()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if state.checked.elapsed() < self.interval {
+                return Arc::clone(&state.rules);
+            }
+        }
+        let mut state = self
+            .state
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        state.checked = Instant::now();
+        let modified = std::fs::metadata(&self.path)
+            .and_then(|m| m.modified())
+            .ok();
+        if modified.is_some() && modified != state.modified {
+            match load(&self.path) {
+                Ok((rules, modified)) => {
+                    state.rules = Arc::new(rules);
+                    state.modified = modified;
+                }
+                #[allow(unused_variables)]
+                Err(err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::error!(path=?self.path, %err, "cannot reload poly_l10n rule file");
+                }
+            }
+        }
+        Arc::clone(&state.rules)
+    }
+}
+
+impl<C> PolyL10nRulebook<'_, C> for WatchedRulebook {
+    fn find_fallback_locale(
+        &self,
+        locale: &LanguageIdentifier,
+    ) -> impl Iterator<Item = LanguageIdentifier> {
+        self.rules().get(locale).cloned().into_iter().flatten()
+    }
+}
@@ -0,0 +1,179 @@
+//! Rules with weights and scored fallbacks, see [`RuleWeights`].
+use crate::{
+    ARulebook, Explanation, LanguageIdentifier, LocaleFallbackSolver, RuleState, Rulebook, explain,
+};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// The weights of the rules of a rulebook, kept as its [`Rulebook::owned_values`].
+///
+/// A weight is the confidence in the fallbacks given by a rule, usually in `0.0..=1.0`. Rules
+/// added without a weight, e.g. with [`Rulebook::push_fn`], have a weight of `1.0`. The weights
+/// follow the rules when they are removed or appended, see [`RuleState`].
+///
+/// [`LocaleFallbackSolver::solve_locale_scored`] scores each fallback with the product of the
+/// weights of the rules it was derived with, so that e.g. cross-language fallbacks can rank below
+/// same-language ones regardless of the order of the rules.
+///
+/// # Examples
+/// ```
+/// use poly_l10n::{langid, LocaleFallbackSolver, Rulebook};
+/// let rulebook = Rulebook::weighted()
+///     .with_weighted(0.5, |l| match l.language.as_str() {
+///         "es" => vec![langid!("pt")],
+///         _ => vec![],
+///     })
+///     .with_weighted(0.9, |l| {
+///         l.region.map(|_| langid!(l.language.as_str())).into_iter().collect()
+///     });
+/// let solver = LocaleFallbackSolver { rulebook };
+/// assert_eq!(solver.solve_locale(langid!("es-MX")), langid!["pt", "es"]);
+/// assert_eq!(
+///     solver.solve_locale_scored(langid!("es-MX")),
+///     [(langid!("es"), 0.9), (langid!("pt"), 0.5)],
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RuleWeights(Vec<f32>);
+
+impl RuleWeights {
+    pub(crate) const fn new() -> Self {
+        Self(vec![])
+    }
+
+    /// The weight of the rule at `index`.
+    #[must_use]
+    pub fn get(&self, index: usize) -> f32 {
+        self.0.get(index).copied().unwrap_or(1.0)
+    }
+
+    /// Set the weight of the rule at `index`.
+    pub fn set(&mut self, index: usize, weight: f32) {
+        if self.0.len() <= index {
+            self.0.resize(index.saturating_add(1), 1.0);
+        }
+        if let Some(w) = self.0.get_mut(index) {
+            *w = weight;
+        }
+    }
+}
+
+impl From<()> for RuleWeights {
+    fn from((): ()) -> Self {
+        Self::new()
+    }
+}
+
+impl RuleState for RuleWeights {
+    fn append(&mut self, len: usize, other: Self) {
+        self.0.resize(len, 1.0);
+        self.0.extend(other.0);
+    }
+
+    fn remove(&mut self, index: usize) {
+        if index < self.0.len() {
+            self.0.remove(index);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Score the fallbacks of `explanation` with `weights`, best first.
+fn score(explanation: Explanation, weights: &RuleWeights) -> Vec<(LanguageIdentifier, f32)> {
+    let mut by_locale: HashMap<LanguageIdentifier, f32> = HashMap::new();
+    // derivations are in breadth-first order, so the score of `from` is always known
+    let mut scored = (explanation.derivations.into_iter())
+        .map(|d| {
+            let from = by_locale.get(&d.from).copied().unwrap_or(1.0);
+            let score = from * weights.get(d.rule);
+            by_locale.insert(d.locale.clone(), score);
+            (d.locale, score)
+        })
+        .collect::<Vec<_>>();
+    // stable, so that fallbacks with the same score stay in the order of the chain
+    scored.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    scored
+}
+
+impl Rulebook<RuleWeights> {
+    /// Append the rule `f` with `weight`. See [`RuleWeights`].
+    #[must_use]
+    pub fn with_weighted<F>(mut self, weight: f32, f: F) -> Self
+    where
+        F: Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> + 'static,
+    {
+        self.owned_values.set(self.rules.len(), weight);
+        self.rules.push(Rc::new(f));
+        self
+    }
+}
+
+impl ARulebook<RuleWeights> {
+    /// See [`Rulebook::with_weighted`].
+    #[must_use]
+    pub fn with_weighted<F>(mut self, weight: f32, f: F) -> Self
+    where
+        F: Fn(&LanguageIdentifier) -> Vec<LanguageIdentifier> + Send + Sync + 'static,
+    {
+        self.owned_values.set(self.rules.len(), weight);
+        self.rules.push(Arc::new(f));
+        self
+    }
+}
+
+impl LocaleFallbackSolver<Rulebook<RuleWeights>> {
+    /// Same as [`Self::solve_locale`], but with the score of each fallback, sorted by score. See
+    /// [`RuleWeights`].
+    pub fn solve_locale_scored<L: AsRef<LanguageIdentifier>>(
+        &self,
+        locale: L,
+    ) -> Vec<(LanguageIdentifier, f32)> {
+        let explanation = explain::explain(&self.rulebook.rules, locale.as_ref());
+        score(explanation, &self.rulebook.owned_values)
+    }
+}
+
+impl LocaleFallbackSolver<ARulebook<RuleWeights>> {
+    /// See [`LocaleFallbackSolver::solve_locale_scored`].
+    pub fn solve_locale_scored<L: AsRef<LanguageIdentifier>>(
+        &self,
+        locale: L,
+    ) -> Vec<(LanguageIdentifier, f32)> {
+        let explanation = explain::explain(&self.rulebook.rules, locale.as_ref());
+        score(explanation, &self.rulebook.owned_values)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::langid;
+
+    #[test]
+    fn weights_follow_rules() {
+        let rulebook = Rulebook::weighted()
+            .with_weighted(0.1, |_| vec![langid!("aa")])
+            .with_weighted(0.2, |_| vec![langid!("bb")]);
+        let mut rulebook = rulebook
+            + Rulebook::from_fn(|_| vec![langid!("cc")])
+            + Rulebook::weighted().with_weighted(0.4, |_| vec![langid!("dd")]);
+        assert!(rulebook.remove_rule(0).is_some());
+        let solver = LocaleFallbackSolver { rulebook };
+        assert_eq!(
+            solver.solve_locale_scored(langid!("en")),
+            [
+                (langid!("cc"), 1.0),
+                (langid!("dd"), 0.4),
+                (langid!("bb"), 0.2)
+            ],
+        );
+        let mut rulebook = solver.rulebook;
+        rulebook.clear();
+        let rulebook = rulebook.with_weighted(0.5, |_| vec![langid!("ee")]);
+        assert_eq!(rulebook.owned_values, RuleWeights(vec![0.5]));
+    }
+}
@@ -0,0 +1,309 @@
+//! A minimal non-validating XML parser for translation files.
+//!
+//! Namespaces are not resolved: names keep their prefix, see [`Element::local_name`]. Document
+//! type declarations are skipped, so only the predefined and numeric entities are supported.
+
+/// Maximum nesting of elements.
+const MAX_DEPTH: usize = 256;
+
+/// Error when parsing XML.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    /// Byte offset of the error in the input.
+    pub offset: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at byte {}: {}", self.offset, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A node in the content of an element.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Node {
+    Element(Element),
+    /// Character data, with references and CDATA sections resolved.
+    Text(String),
+}
+
+/// An XML element.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Element {
+    pub name: String,
+    pub attrs: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+impl Element {
+    /// Parse an XML document, returning its root element.
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+        let mut parser = Parser { input, rest: input };
+        parser.misc()?;
+        if !parser.rest.starts_with('<') {
+            return parser.err("expected the root element");
+        }
+        let root = parser.element(0)?;
+        parser.misc()?;
+        if !parser.rest.is_empty() {
+            return parser.err("trailing characters");
+        }
+        Ok(root)
+    }
+
+    /// The name without its namespace prefix.
+    pub fn local_name(&self) -> &str {
+        local_name(&self.name)
+    }
+
+    /// The value of the attribute `name`, matched by local name if `name` has no prefix.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        (self.attrs.iter())
+            .find(|(n, _)| n == name || (!name.contains(':') && local_name(n) == name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The child elements.
+    pub fn elements(&self) -> impl Iterator<Item = &Self> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(e) => Some(e),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// The first child element with the local name `name`.
+    #[cfg_attr(not(feature = "catalog"), allow(dead_code))]
+    pub fn child(&self, name: &str) -> Option<&Self> {
+        self.elements().find(|e| e.local_name() == name)
+    }
+
+    /// The text content of the element and its descendants.
+    pub fn text(&self) -> String {
+        let mut out = String::new();
+        self.push_text(&mut out);
+        out
+    }
+
+    fn push_text(&self, out: &mut String) {
+        for node in &self.children {
+            match node {
+                Node::Element(e) => e.push_text(out),
+                Node::Text(s) => out.push_str(s),
+            }
+        }
+    }
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit_once(':').map_or(name, |(_, local)| local)
+}
+
+const fn is_name_char(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '/' | '>' | '=' | '<' | '"' | '\'')
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn err<T>(&self, message: &str) -> Result<T, Error> {
+        Err(Error {
+            offset: self.input.len().saturating_sub(self.rest.len()),
+            message: message.to_owned(),
+        })
+    }
+
+    fn skip_ws(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.rest.strip_prefix(token).is_some_and(|rest| {
+            self.rest = rest;
+            true
+        })
+    }
+
+    /// Skip past `end`, returning the text before it.
+    fn until(&mut self, end: &str) -> Result<&'a str, Error> {
+        let Some((text, rest)) = self.rest.split_once(end) else {
+            return self.err(&format!("expected `{end}`"));
+        };
+        self.rest = rest;
+        Ok(text)
+    }
+
+    fn name(&mut self) -> Result<&'a str, Error> {
+        let end = self
+            .rest
+            .find(|c| !is_name_char(c))
+            .unwrap_or(self.rest.len());
+        let (name, rest) = self.rest.split_at(end);
+        if name.is_empty() {
+            return self.err("expected a name");
+        }
+        self.rest = rest;
+        Ok(name)
+    }
+
+    /// Skip whitespace, comments, processing instructions and document type declarations.
+    fn misc(&mut self) -> Result<(), Error> {
+        loop {
+            self.skip_ws();
+            if self.eat("<?") {
+                self.until("?>")?;
+            } else if self.eat("<!--") {
+                self.until("-->")?;
+            } else if self.eat("<!DOCTYPE") {
+                let bracket = self.rest.find('[');
+                if bracket.is_some_and(|b| self.rest.find('>').is_some_and(|g| b < g)) {
+                    self.until("]")?;
+                }
+                self.until(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn unescape(&self, s: &str) -> Result<String, Error> {
+        let mut out = String::with_capacity(s.len());
+        let mut parts = s.split('&');
+        out.push_str(parts.next().unwrap_or_default());
+        for part in parts {
+            let Some((entity, text)) = part.split_once(';') else {
+                return self.err("unterminated entity reference");
+            };
+            let c = match entity {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => (entity.strip_prefix("#x"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(str::parse))
+                    .and_then(Result::ok)
+                    .and_then(char::from_u32),
+            };
+            let Some(c) = c else {
+                return self.err(&format!("unknown entity `&{entity};`"));
+            };
+            out.push(c);
+            out.push_str(text);
+        }
+        Ok(out)
+    }
+
+    fn element(&mut self, depth: usize) -> Result<Element, Error> {
+        if depth > MAX_DEPTH {
+            return self.err("nested too deeply");
+        }
+        if !self.eat("<") {
+            return self.err("expected `<`");
+        }
+        let mut element = Element {
+            name: self.name()?.to_owned(),
+            ..Element::default()
+        };
+        loop {
+            self.skip_ws();
+            if self.eat("/>") {
+                return Ok(element);
+            }
+            if self.eat(">") {
+                break;
+            }
+            let name = self.name()?;
+            self.skip_ws();
+            if !self.eat("=") {
+                return self.err("expected `=`");
+            }
+            self.skip_ws();
+            let quote = if self.eat("\"") {
+                "\""
+            } else if self.eat("'") {
+                "'"
+            } else {
+                return self.err("expected a quoted attribute value");
+            };
+            let value = self.until(quote)?;
+            element.attrs.push((name.to_owned(), self.unescape(value)?));
+        }
+        let depth = depth.saturating_add(1);
+        let mut text = String::new();
+        loop {
+            if self.eat("</") {
+                if self.name()? != element.name {
+                    return self.err(&format!("expected `</{}>`", element.name));
+                }
+                self.skip_ws();
+                if !self.eat(">") {
+                    return self.err("expected `>`");
+                }
+                break;
+            } else if self.eat("<!--") {
+                self.until("-->")?;
+            } else if self.eat("<![CDATA[") {
+                text.push_str(self.until("]]>")?);
+            } else if self.eat("<?") {
+                self.until("?>")?;
+            } else if self.rest.starts_with('<') {
+                if !text.is_empty() {
+                    element.children.push(Node::Text(std::mem::take(&mut text)));
+                }
+                element.children.push(Node::Element(self.element(depth)?));
+            } else if self.rest.is_empty() {
+                return self.err(&format!("expected `</{}>`", element.name));
+            } else {
+                let end = self.rest.find('<').unwrap_or(self.rest.len());
+                let (raw, rest) = self.rest.split_at(end);
+                text.push_str(&self.unescape(raw)?);
+                self.rest = rest;
+            }
+        }
+        if !text.is_empty() {
+            element.children.push(Node::Text(text));
+        }
+        Ok(element)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let root = Element::parse(
+            "\u{feff}<?xml version=\"1.0\"?>\n<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"x\">\n\
+             <x:root xmlns:x='urn:x' x:lang=\"en\"><!-- c --><a b=\"&lt;&#65;&#x42;\"/>t&amp;<![CDATA[<c>]]>\
+             <b>i<i>n</i>ner</b></x:root>\n",
+        )
+        .unwrap();
+        assert_eq!(root.local_name(), "root");
+        assert_eq!(root.attr("lang"), Some("en"));
+        assert_eq!(root.attr("x:lang"), Some("en"));
+        assert_eq!(root.child("a").and_then(|a| a.attr("b")), Some("<AB"));
+        assert_eq!(root.child("b").map(Element::text).as_deref(), Some("inner"));
+        assert_eq!(root.text(), "t&<c>inner");
+        for bad in [
+            "",
+            "<a>",
+            "<a></b>",
+            "<a b=c/>",
+            "<a>&foo;</a>",
+            "<a/><b/>",
+            "text",
+        ] {
+            Element::parse(bad).unwrap_err();
+        }
+        Element::parse(&"<a>".repeat(1000)).unwrap_err();
+    }
+}